@@ -0,0 +1,131 @@
+# Generates all sound effects and music tracks of the game (chiptune style)
+# Usage: python3 generate.py (writes the files next to this script, the music needs `oggenc` from vorbis-tools)
+import os
+import random
+import struct
+import subprocess
+import wave
+
+SAMPLE_RATE = 22050
+DIR = os.path.dirname(os.path.abspath(__file__))
+
+
+def note(name):
+    """Returns the frequency of a note like "A4" or "C#5" """
+    names = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"]
+    key = names.index(name[:-1]) + (int(name[-1]) + 1) * 12
+    return 440.0 * 2 ** ((key - 69) / 12)
+
+
+def square(phase, duty=0.5):
+    return 1.0 if phase % 1.0 < duty else -1.0
+
+
+def triangle(phase):
+    return 4.0 * abs(phase % 1.0 - 0.5) - 1.0
+
+
+def tone(freq_start, freq_end, duration, volume=0.5, wave_fn=square, decay=True):
+    samples = []
+    phase = 0.0
+    count = int(duration * SAMPLE_RATE)
+    for i in range(count):
+        t = i / count
+        freq = freq_start + (freq_end - freq_start) * t
+        phase += freq / SAMPLE_RATE
+        envelope = (1.0 - t) if decay else min(1.0, (count - i) / (SAMPLE_RATE * 0.01))
+        samples.append(wave_fn(phase) * volume * envelope)
+    return samples
+
+
+def noise(duration, volume=0.5, pitch=1.0):
+    samples = []
+    count = int(duration * SAMPLE_RATE)
+    step = max(1, int(8 / pitch))
+    value = 0.0
+    for i in range(count):
+        if i % step == 0:
+            value = random.uniform(-1.0, 1.0)
+        samples.append(value * volume * (1.0 - i / count))
+    return samples
+
+
+def mix(*tracks):
+    length = max(len(track) for track in tracks)
+    return [sum(track[i] for track in tracks if i < len(track)) for i in range(length)]
+
+
+def write(path, samples):
+    path = os.path.join(DIR, path)
+    os.makedirs(os.path.dirname(path), exist_ok=True)
+    with wave.open(path, "wb") as file:
+        file.setnchannels(1)
+        file.setsampwidth(2)
+        file.setframerate(SAMPLE_RATE)
+        file.writeframes(b"".join(struct.pack("<h", int(max(-1.0, min(1.0, s)) * 32767)) for s in samples))
+
+
+def write_ogg(path, samples):
+    # The music is stored as Ogg Vorbis, the WAV is only kept until it is encoded
+    wav_path = os.path.splitext(path)[0] + ".wav"
+    write(wav_path, samples)
+    wav_path = os.path.join(DIR, wav_path)
+    subprocess.run(["oggenc", "--quiet", "--quality", "3", "--output", os.path.join(DIR, path), wav_path], check=True)
+    os.remove(wav_path)
+
+
+def melody(notes, beat, wave_fn, volume):
+    """`notes` is a list of (note or None, beats)"""
+    samples = []
+    for name, beats in notes:
+        duration = beat * beats
+        if name is None:
+            samples += [0.0] * int(duration * SAMPLE_RATE)
+        else:
+            samples += tone(note(name), note(name), duration, volume, wave_fn, decay=False)
+    return samples
+
+
+def music(bass, lead, bpm):
+    beat = 60.0 / bpm
+    bass_samples = melody(bass, beat, triangle, 0.35)
+    lead_samples = melody(lead, beat, lambda p: square(p, 0.25), 0.12)
+    return mix(bass_samples, lead_samples)
+
+
+random.seed(1404)
+
+# Sound effects
+write("sfx/jump.wav", tone(300, 700, 0.15, 0.3))
+write("sfx/shoot.wav", mix(tone(900, 300, 0.12, 0.2), noise(0.08, 0.15, 2.0)))
+write("sfx/coin.wav", tone(note("B5"), note("B5"), 0.06, 0.25, decay=False) + tone(note("E6"), note("E6"), 0.2, 0.25))
+write("sfx/power_up.wav", sum((tone(note(n), note(n), 0.07, 0.25, decay=False) for n in ["C5", "E5", "G5", "C6"]), []) + tone(note("E6"), note("E6"), 0.2, 0.25))
+write("sfx/power_down.wav", sum((tone(note(n), note(n), 0.07, 0.25, decay=False) for n in ["C6", "G5", "E5"]), []) + tone(note("C5"), note("C5"), 0.2, 0.25))
+write("sfx/player_damage.wav", mix(tone(400, 120, 0.3, 0.35), noise(0.2, 0.2)))
+write("sfx/enemy_damage.wav", tone(600, 350, 0.1, 0.25))
+write("sfx/enemy_death.wav", mix(tone(500, 60, 0.4, 0.3), noise(0.35, 0.25, 0.5)))
+write("sfx/cannon.wav", mix(noise(0.3, 0.45, 0.3), tone(120, 40, 0.25, 0.35, triangle)))
+write("sfx/level_complete.wav", sum((tone(note(n), note(n), d, 0.3, decay=False) for n, d in [("C5", 0.12), ("E5", 0.12), ("G5", 0.12), ("C6", 0.3), ("G5", 0.12), ("C6", 0.5)]), []))
+write("sfx/game_over.wav", sum((tone(note(n), note(n), d, 0.3, decay=False) for n, d in [("G4", 0.25), ("D#4", 0.25), ("C4", 0.25), ("G3", 0.6)]), []))
+
+# Music (one track per level)
+write_ogg("music/level_0.ogg", music(
+    [("C3", 2), ("G2", 2), ("A2", 2), ("F2", 2)] * 2,
+    [("E5", 1), ("G5", 1), ("C6", 2), ("B4", 1), ("D5", 1), ("G5", 2), ("C5", 1), ("E5", 1), ("A5", 2), ("A4", 1), ("C5", 1), ("F5", 2)] * 2,
+    110,
+))
+write_ogg("music/level_1.ogg", music(
+    [("A2", 1), ("A2", 1), ("C3", 1), ("A2", 1), ("G2", 1), ("G2", 1), ("E2", 2)] * 4,
+    [("A4", 0.5), ("C5", 0.5), ("E5", 1), ("D5", 1), ("C5", 1), ("B4", 1), ("G4", 1), ("E4", 2)] * 4,
+    132,
+))
+write_ogg("music/level_2.ogg", music(
+    [("D3", 1), ("D3", 1), ("F3", 1), ("D3", 1), ("C3", 1), ("C3", 1), ("A2", 2)] * 4,
+    [("D5", 0.5), ("F5", 0.5), ("A5", 1), (None, 1), ("G5", 0.5), ("F5", 0.5), ("E5", 1), ("C5", 1), ("D5", 2)] * 4,
+    140,
+))
+write_ogg("music/level_3.ogg", music(
+    [("E2", 0.5), ("E2", 0.5), ("E3", 0.5), ("E2", 0.5), ("G2", 0.5), ("G2", 0.5), ("D3", 0.5), ("D2", 0.5)] * 8,
+    [("E5", 0.5), ("G5", 0.5), ("B5", 0.5), ("G5", 0.5), ("D5", 0.5), ("F#5", 0.5), ("A5", 1), ("E5", 1), ("B4", 1), ("D5", 2)] * 4,
+    150,
+))
@@ -2,10 +2,9 @@ use macroquad::color::{GREEN, ORANGE, RED, VIOLET, WHITE, YELLOW};
 use macroquad::math::Vec2;
 use macroquad::prelude::vec2;
 use macroquad::shapes::draw_rectangle_lines;
+use serde::{Deserialize, Serialize};
 use crate::logic::player::Player;
-use crate::logic::enemy::Enemy;
-use crate::logic::platform::Platform;
-use crate::logic::projectile::Projectile;
+use crate::logic::ecs::{Entities, Entity};
 use crate::utils::structs::Settings;
 use crate::utils::structs::Rect;
 
@@ -16,7 +15,7 @@ pub struct Collider {
     pub collider_type: ColliderType
 }
 
-#[derive(PartialEq, Copy, Clone, Debug)]
+#[derive(PartialEq, Copy, Clone, Serialize, Deserialize, Debug)]
 pub enum ColliderType {
     Actor,
     Enemy,
@@ -64,10 +63,10 @@ impl Collider {
         self.rect.overlaps(&player_rect).await || self.rect.overlaps(&below_rect).await
     }
 
-    /// This functions checks if an enemy of the provided Vector collides with the [Collider] on the relative position arguments <br>
+    /// This functions checks if an enemy (entity with [crate::logic::ecs::components::Health]) collides with the [Collider] on the relative position arguments <br>
     /// The position is relative to the top left corner of the collider <br>
-    /// The returned [Vec<usize>] contains the index of each enemy that collides
-    pub async fn collide_check_enemy(&self, enemies: &Vec<Enemy>, pos: Vec2) -> Vec<usize> {
+    /// The returned [Vec<Entity>] contains each enemy that collides
+    pub async fn collide_check_enemy(&self, entities: &Entities, pos: Vec2) -> Vec<Entity> {
         let mut result = Vec::new();
         let rect = {
             let mut result = self.rect;
@@ -78,16 +77,19 @@ impl Collider {
             result
         };
 
-        for (i, enemy) in enemies.iter().enumerate() {
-            if rect.overlaps(&enemy.colliders.get(0, 0).unwrap().rect).await {
-                result.push(i)
+        for entity in entities.healths.keys() {
+            if let Some(collider) = entities.colliders.get(entity) {
+                if rect.overlaps(&collider.rect).await {
+                    result.push(*entity)
+                }
             }
         }
 
         result
     }
 
-    pub async fn collide_check_projectile(&self, projectiles: &Vec<Projectile>, pos: Vec2) -> Vec<usize> {
+    /// Returns every projectile (entity with [crate::logic::ecs::components::Damage]) that collides with the [Collider]
+    pub async fn collide_check_projectile(&self, entities: &Entities, pos: Vec2) -> Vec<Entity> {
         let mut result = Vec::new();
         let rect = {
             let mut result = self.rect;
@@ -98,16 +100,19 @@ impl Collider {
             result
         };
 
-        for (i, projectile) in projectiles.iter().enumerate() {
-            if rect.overlaps(&projectile.collider.rect).await {
-                result.push(i)
+        for entity in entities.damages.keys() {
+            if let Some(collider) = entities.colliders.get(entity) {
+                if rect.overlaps(&collider.rect).await {
+                    result.push(*entity)
+                }
             }
         }
 
         result
     }
 
-    pub async fn collide_check_platform(&self, platforms: &Vec<Platform>, pos: Vec2) -> Vec<usize> {
+    /// Returns every platform (entity with [crate::logic::platform::Platform]) that collides with the [Collider]
+    pub async fn collide_check_platform(&self, entities: &Entities, pos: Vec2) -> Vec<Entity> {
         let mut result = Vec::new();
         let rect = {
             let mut result = self.rect;
@@ -118,9 +123,11 @@ impl Collider {
             result
         };
 
-        for (i, platforms) in platforms.iter().enumerate() {
-            if rect.overlaps(&platforms.collider_new.rect).await {
-                result.push(i)
+        for entity in entities.platforms.keys() {
+            if let Some(collider) = entities.colliders.get(entity) {
+                if rect.overlaps(&collider.rect).await {
+                    result.push(*entity)
+                }
             }
         }
 
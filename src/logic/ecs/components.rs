@@ -0,0 +1,156 @@
+use macroquad::color::Color;
+use macroquad::math::Vec2;
+use macroquad_platformer::Actor;
+use serde::{Deserialize, Serialize};
+use crate::logic::ecs::Entity;
+use crate::logic::player::PlayerPowerUp;
+use crate::logic::timer::{Timer, Timers};
+use crate::logic::weapon::WeaponKind;
+use crate::utils::enums::{Animation, Direction, TextureKey};
+
+/// The position and size of an entity (in pixels)
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Transform {
+    pub pos: Vec2,
+    pub size: Vec2,
+}
+
+/// Moves the entity every frame (in pixels per second)
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Velocity(pub Vec2);
+
+/// How an entity gets drawn
+#[derive(PartialEq, Clone, Debug)]
+pub struct Sprite {
+    pub texture_key: TextureKey,
+    /// The texture index that is used if there is no animation
+    pub index: usize,
+    pub animation: Option<Animation>,
+    /// Rotates the texture in the direction of the [Velocity]
+    pub rotate: bool,
+    /// Tints the texture (like the red overlay of [Health])
+    pub color: Color,
+}
+
+/// Moves the entity through the world like the player, so platforms stop it <br>
+/// The [Transform] and the [crate::logic::collider::Collider] of the entity follow the actor
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Body {
+    pub actor: Actor,
+    /// In pixels per second
+    pub speed: Vec2,
+}
+
+/// The projectiles of the player damage entities with health (see [crate::logic::ecs::systems::damage()]) <br>
+/// The entity gets removed when no health is left
+#[derive(PartialEq, Clone, Debug)]
+pub struct Health {
+    pub amount: i16,
+    /// The health bar gets drawn while the health is below this
+    pub max: i16,
+    /// The red overlay after a hit
+    pub timers: Timers<HealthTimer>,
+}
+
+#[derive(PartialEq, Clone, Ord, Eq, PartialOrd, Debug)]
+pub enum HealthTimer {
+    DamageOverlay,
+}
+
+impl Health {
+    pub fn new(amount: i16) -> Self {
+        Self { amount, max: amount, timers: Timers::new() }
+    }
+}
+
+/// Damages the player when they touch the entity (the damage is negative like [Damage::amount])
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct ContactDamage(pub i16);
+
+/// Gets spawned where the entity gets killed (see [Health])
+#[derive(PartialEq, Clone, Debug)]
+pub struct Loot(pub Pickup);
+
+/// Gets collected when the player touches the entity
+#[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub enum Pickup {
+    Coin,
+    PowerUp { power_up: PlayerPowerUp, duration: f64 },
+    /// An extra life in the lives mode
+    ExtraLife,
+    /// Heals the player by [SMALL_HEART_HEAL]
+    SmallHeart,
+    /// Heals the player by [LARGE_HEART_HEAL]
+    LargeHeart,
+    /// Gives the player the weapon with the ammo of one pickup (see [crate::logic::weapon::Weapon::ammo])
+    Weapon { weapon: WeaponKind },
+}
+
+pub const SMALL_HEART_HEAL: i16 = 200;
+pub const LARGE_HEART_HEAL: i16 = 500;
+
+impl Pickup {
+    /// Returns how much the pickup heals the player (`0` if it isn't a heart)
+    pub fn heal(&self) -> i16 {
+        match self {
+            Pickup::SmallHeart => SMALL_HEART_HEAL,
+            Pickup::LargeHeart => LARGE_HEART_HEAL,
+            _ => 0,
+        }
+    }
+}
+
+/// Damages what the entity hits and removes the entity afterward (like projectiles)
+#[derive(PartialEq, Clone, Debug)]
+pub struct Damage {
+    /// The damage is negative (it gets added to the health)
+    pub amount: i16,
+    pub origin: ProjectileOrigin,
+    /// The enemies the entity already flew through (see [ProjectileBehaviour::pierce])
+    pub pierced: Vec<Entity>,
+    /// The entities the projectile already damaged, it only damages every entity once <br>
+    /// Other projectiles (e.g. the rest of a shotgun shot) can still damage them in the same frame
+    pub damaged: Vec<Entity>,
+}
+
+/// Changes how a projectile flies and what happens when it hits something <br>
+/// The default flies in a straight line and gets removed by the first hit
+#[derive(PartialEq, Clone, Copy, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ProjectileBehaviour {
+    /// Gets added to the falling speed every second (in pixels per second at a GUI scale of 1), makes the projectile fly in an arc
+    pub gravity: f32,
+    /// How often the projectile bounces off platforms before a platform removes it
+    pub bounces: u32,
+    /// How many enemies the projectile flies through before an enemy removes it
+    pub pierce: u32,
+    /// How fast the projectile turns towards the nearest target (in degrees per second), `0.0` turns homing off <br>
+    /// Projectiles of the player follow enemies, all other projectiles follow the player
+    pub homing: f32,
+}
+
+/// Homing projectiles only follow targets inside of this radius (in tiles)
+pub const HOMING_RANGE: f32 = 8.0;
+
+#[derive(Eq, PartialEq, Clone, Ord, PartialOrd, Serialize, Deserialize, Debug)]
+pub enum ProjectileOrigin {
+    Player,
+    Canon
+}
+
+/// Removes the entity when the timer runs out
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Lifetime(pub Timer);
+
+/// Shoots projectiles in one direction every time the timer runs out (like cannons)
+#[derive(PartialEq, Clone, Debug)]
+pub struct Shooter {
+    pub timer: Timer,
+    pub direction: Direction,
+    pub projectile_speed: f32,
+    /// How long a projectile exists (in seconds)
+    pub projectile_time: f64,
+    pub projectile_texture_key: TextureKey,
+    pub projectile_behaviour: ProjectileBehaviour,
+    pub damage: i16,
+}
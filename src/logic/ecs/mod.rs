@@ -0,0 +1,99 @@
+use std::collections::BTreeMap;
+use crate::logic::collider::Collider;
+use crate::logic::ecs::components::{Body, ContactDamage, Damage, Health, Lifetime, Loot, Pickup, ProjectileBehaviour, Shooter, Sprite, Transform, Velocity};
+use crate::logic::enemy::EnemyAi;
+use crate::logic::platform::Platform;
+
+// Small entity component system for everything in a level except the player (platforms, enemies, coins, power ups, projectiles and cannons).
+// An entity is only an id. Everything it can do comes from the components it has (see `components.rs`,
+// the AI of enemies is in `logic/enemy.rs`), and the systems (see `systems.rs`) run the logic for all entities
+// with the needed components. New kinds of entities get composed in `prefabs.rs` and new components get declared in `with_storages!`.
+
+pub mod components;
+pub mod prefabs;
+pub mod systems;
+
+/// The id of an entity
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub struct Entity(u32);
+
+/// Calls the macro `$callback` with every component storage of [Entities], so new components only get added here <br>
+/// `saved` storages get saved with the level (`storage / snapshot field: Component`, see [crate::logic::snapshot::EntitySnapshot]) <br>
+/// `layout` storages belong to entities that never change, they aren't saved and come from the layout of the level again
+macro_rules! with_storages {
+    ($callback:ident) => {
+        $callback! {
+            saved {
+                transforms / transform: Transform,
+                velocities / velocity: Velocity,
+                colliders / collider: Collider,
+                sprites / sprite: Sprite,
+                pickups / pickup: Pickup,
+                damages / damage: Damage,
+                behaviours / behaviour: ProjectileBehaviour,
+                lifetimes / lifetime: Lifetime,
+                shooters / shooter: Shooter,
+                bodies / body: Body,
+                healths / health: Health,
+                contact_damages / contact_damage: ContactDamage,
+                loot / loot: Loot,
+                enemies / enemy: EnemyAi,
+            }
+            layout {
+                platforms: Platform,
+            }
+        }
+    };
+}
+pub(crate) use with_storages;
+
+macro_rules! entities {
+    (saved { $($storage:ident / $field:ident: $component:ty),* $(,)? } layout { $($layout:ident: $layout_component:ty),* $(,)? }) => {
+        /// Holds all entities of a level with their components <br>
+        /// Every component type has its own storage, so systems only iterate over what they need
+        #[derive(PartialEq, Clone, Default, Debug)]
+        pub struct Entities {
+            next: u32,
+            $(pub $storage: BTreeMap<Entity, $component>,)*
+            $(pub $layout: BTreeMap<Entity, $layout_component>,)*
+        }
+
+        impl Entities {
+            /// Removes the entity with all of its components
+            pub fn despawn(&mut self, entity: Entity) {
+                $(self.$storage.remove(&entity);)*
+                $(self.$layout.remove(&entity);)*
+            }
+
+            /// Returns whether the entity is part of the level layout and never changes (like platforms)
+            pub fn is_layout(&self, entity: &Entity) -> bool {
+                false $(|| self.$layout.contains_key(entity))*
+            }
+        }
+    };
+}
+
+with_storages!(entities);
+
+impl Entities {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new entity without any components
+    pub fn spawn(&mut self) -> Entity {
+        let entity = Entity(self.next);
+        self.next += 1;
+        entity
+    }
+
+    /// Returns the amount of entities (every entity has a [Transform])
+    pub fn count(&self) -> usize {
+        self.transforms.len()
+    }
+
+    /// Returns the amount of coins that haven't been collected yet
+    pub fn coins(&self) -> usize {
+        self.pickups.values().filter(|pickup| **pickup == Pickup::Coin).count()
+    }
+}
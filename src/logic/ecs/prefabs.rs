@@ -0,0 +1,228 @@
+use macroquad::color::WHITE;
+use macroquad::math::{vec2, Vec2};
+use macroquad_platformer::World;
+use crate::logic::collider::Collider;
+use crate::logic::ecs::components::{Body, ContactDamage, Damage, Health, Lifetime, Pickup, ProjectileBehaviour, ProjectileOrigin, Shooter, Sprite, Transform, Velocity};
+use crate::logic::ecs::{Entities, Entity};
+use crate::logic::enemy::EnemyAi;
+use crate::logic::platform::Platform;
+use crate::logic::player::PlayerPowerUp;
+use crate::logic::timer::Timer;
+use crate::logic::weapon::WeaponKind;
+use crate::utils::enums::{Animation, AnimationType, Direction, TextureKey};
+
+// All kinds of entities that can be spawned
+
+/// A power up that lies in the level
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct PowerUpSpawn {
+    pub power_up: PlayerPowerUp,
+    /// The time the power up lasts after it got collected
+    pub duration: f64,
+    pub pos: Vec2,
+    pub size: Vec2,
+    pub texture_key: TextureKey,
+    pub animation: AnimationType,
+}
+
+/// A projectile that flies from `pos` with `speed` (in pixels per second)
+#[derive(PartialEq, Clone, Debug)]
+pub struct ProjectileSpawn {
+    pub pos: Vec2,
+    pub size: Vec2,
+    pub speed: Vec2,
+    /// The damage is negative (it gets added to the health)
+    pub damage: i16,
+    pub origin: ProjectileOrigin,
+    /// The time (in seconds) until the projectile disappears
+    pub max_time: f64,
+    pub texture_key: TextureKey,
+    pub behaviour: ProjectileBehaviour,
+}
+
+/// A cannon that shoots in `direction`, the projectiles are defined by [Shooter]
+#[derive(PartialEq, Clone, Debug)]
+pub struct CannonSpawn {
+    pub pos: Vec2,
+    pub size: Vec2,
+    /// The time between two shots (in seconds)
+    pub speed: f64,
+    /// Delays the first shot (in seconds)
+    pub offset: f64,
+    /// The time the level started, the first shot happens at `start_time + offset`
+    pub start_time: f64,
+    pub direction: Direction,
+    pub texture_key: TextureKey,
+    pub projectile_speed: f32,
+    /// How long a projectile exists (in seconds)
+    pub projectile_time: f64,
+    pub projectile_texture_key: TextureKey,
+    pub projectile_behaviour: ProjectileBehaviour,
+    pub damage: i16,
+}
+
+/// Every enemy starts with this much health
+pub const ENEMY_HEALTH: i16 = 1000;
+
+impl Entities {
+    /// `pos` is the top left corner, the size comes from the tiles of the platform
+    pub async fn spawn_platform(&mut self, platform: Platform, pos: Vec2, world: &mut World) -> Entity {
+        let entity = self.spawn();
+        let size = platform.size();
+        world.add_solid(pos, size.x as i32, size.y as i32);
+
+        self.transforms.insert(entity, Transform { pos, size });
+        self.colliders.insert(entity, Collider::new_solid(pos, size.x, size.y, vec2(0.0, 0.0)).await);
+        self.platforms.insert(entity, platform);
+        entity
+    }
+
+    pub async fn spawn_coin(&mut self, pos: Vec2, size: Vec2, texture_key: TextureKey) -> Entity {
+        let entity = self.spawn();
+        self.transforms.insert(entity, Transform { pos, size });
+        self.colliders.insert(entity, Collider::new_collectible(pos, size.x, size.y, vec2(0.0, 0.0)).await);
+        self.sprites.insert(entity, Sprite {
+            texture_key,
+            index: 0,
+            animation: Some(Animation::new(AnimationType::Cycle(0, 5, 0.1))),
+            rotate: false,
+            color: WHITE,
+        });
+        self.pickups.insert(entity, Pickup::Coin);
+        entity
+    }
+
+    pub async fn spawn_power_up(&mut self, spawn: PowerUpSpawn) -> Entity {
+        let PowerUpSpawn { power_up, duration, pos, size, texture_key, animation } = spawn;
+        let entity = self.spawn();
+        self.transforms.insert(entity, Transform { pos, size });
+        self.colliders.insert(entity, Collider::new_collectible(pos, size.x, size.y, vec2(0.0, 0.0)).await);
+        self.sprites.insert(entity, Sprite {
+            texture_key,
+            index: 0,
+            animation: Some(Animation::new(animation)),
+            rotate: false,
+            color: WHITE,
+        });
+        self.pickups.insert(entity, Pickup::PowerUp { power_up, duration });
+        entity
+    }
+
+    /// Uses the front texture of the player (index 2 of `texture_key`)
+    pub async fn spawn_extra_life(&mut self, pos: Vec2, size: Vec2, texture_key: TextureKey) -> Entity {
+        let entity = self.spawn();
+        self.transforms.insert(entity, Transform { pos, size });
+        self.colliders.insert(entity, Collider::new_collectible(pos, size.x, size.y, vec2(0.0, 0.0)).await);
+        self.sprites.insert(entity, Sprite {
+            texture_key,
+            index: 2,
+            animation: None,
+            rotate: false,
+            color: WHITE,
+        });
+        self.pickups.insert(entity, Pickup::ExtraLife);
+        entity
+    }
+
+    /// `large` decides between [Pickup::SmallHeart] and [Pickup::LargeHeart] (and their texture in `texture_key`)
+    pub async fn spawn_heart(&mut self, large: bool, pos: Vec2, size: Vec2, texture_key: TextureKey) -> Entity {
+        let entity = self.spawn();
+        self.transforms.insert(entity, Transform { pos, size });
+        self.colliders.insert(entity, Collider::new_collectible(pos, size.x, size.y, vec2(0.0, 0.0)).await);
+        self.sprites.insert(entity, Sprite {
+            texture_key,
+            index: large as usize,
+            animation: None,
+            rotate: false,
+            color: WHITE,
+        });
+        self.pickups.insert(entity, if large { Pickup::LargeHeart } else { Pickup::SmallHeart });
+        entity
+    }
+
+    /// Uses the texture of the weapon in `texture_key` (see [WeaponKind::texture_index()])
+    pub async fn spawn_weapon(&mut self, weapon: WeaponKind, pos: Vec2, size: Vec2, texture_key: TextureKey) -> Entity {
+        let entity = self.spawn();
+        self.transforms.insert(entity, Transform { pos, size });
+        self.colliders.insert(entity, Collider::new_collectible(pos, size.x, size.y, vec2(0.0, 0.0)).await);
+        self.sprites.insert(entity, Sprite {
+            texture_key,
+            index: weapon.texture_index(),
+            animation: None,
+            rotate: false,
+            color: WHITE,
+        });
+        self.pickups.insert(entity, Pickup::Weapon { weapon });
+        entity
+    }
+
+    /// Spawns any [Pickup] with its default texture (e.g. what an enemy drops)
+    pub async fn spawn_drop(&mut self, pickup: Pickup, pos: Vec2, size: Vec2) -> Entity {
+        match pickup {
+            Pickup::Coin => self.spawn_coin(pos, size, TextureKey::Coin0).await,
+            Pickup::PowerUp { power_up, duration } => {
+                let (texture_key, (start, end)) = power_up.texture();
+                self.spawn_power_up(PowerUpSpawn { power_up, duration, pos, size, texture_key, animation: AnimationType::Cycle(start, end, 0.1) }).await
+            }
+            Pickup::ExtraLife => self.spawn_extra_life(pos, size, TextureKey::Player).await,
+            Pickup::SmallHeart => self.spawn_heart(false, pos, size, TextureKey::Hearts0).await,
+            Pickup::LargeHeart => self.spawn_heart(true, pos, size, TextureKey::Hearts0).await,
+            Pickup::Weapon { weapon } => self.spawn_weapon(weapon, pos, size, TextureKey::Weapons0).await,
+        }
+    }
+
+    pub async fn spawn_projectile(&mut self, spawn: ProjectileSpawn) -> Entity {
+        let ProjectileSpawn { pos, size, speed, damage, origin, max_time, texture_key, behaviour } = spawn;
+        let entity = self.spawn();
+        self.transforms.insert(entity, Transform { pos, size });
+        self.velocities.insert(entity, Velocity(speed));
+        self.colliders.insert(entity, Collider::new_projectile(pos, size.x, size.y, vec2(0.0, 0.0)).await);
+        self.sprites.insert(entity, Sprite { texture_key, index: 0, animation: None, rotate: true, color: WHITE });
+        self.damages.insert(entity, Damage { amount: damage, origin, pierced: Vec::new(), damaged: Vec::new() });
+        self.behaviours.insert(entity, behaviour);
+        self.lifetimes.insert(entity, Lifetime(Timer::new(max_time)));
+        entity
+    }
+
+    /// Adds the cannon to the world as a solid
+    pub async fn spawn_cannon(&mut self, spawn: CannonSpawn, world: &mut World) -> Entity {
+        let CannonSpawn { pos, size, speed, offset, start_time, direction, texture_key, projectile_speed, projectile_time, projectile_texture_key, projectile_behaviour, damage } = spawn;
+        let entity = self.spawn();
+        world.add_solid(pos, size.x as i32, size.y as i32);
+
+        let index = match direction {
+            Direction::Right => 0,
+            Direction::Left => 1,
+            Direction::Up => 2,
+            Direction::Down => 3,
+        };
+
+        self.transforms.insert(entity, Transform { pos, size });
+        self.colliders.insert(entity, Collider::new_solid(pos, size.x, size.y, vec2(0.0, 0.0)).await);
+        self.sprites.insert(entity, Sprite { texture_key, index, animation: None, rotate: false, color: WHITE });
+        self.shooters.insert(entity, Shooter {
+            timer: Timer::starting_at(start_time + offset, speed),
+            direction,
+            projectile_speed,
+            projectile_time,
+            projectile_texture_key,
+            projectile_behaviour,
+            damage,
+        });
+        entity
+    }
+
+    /// `damage` is what the player receives when they touch the enemy (negative) <br>
+    /// Insert [crate::logic::ecs::components::Loot] to let the enemy drop something
+    pub async fn spawn_enemy(&mut self, pos: Vec2, size: Vec2, damage: i16, texture_key: TextureKey, world: &mut World) -> Entity {
+        let entity = self.spawn();
+        self.transforms.insert(entity, Transform { pos, size });
+        self.colliders.insert(entity, Collider::new_enemy(pos, size.x, size.y, vec2(0.0, 0.0)).await);
+        self.sprites.insert(entity, Sprite { texture_key, index: 0, animation: None, rotate: false, color: WHITE });
+        self.bodies.insert(entity, Body { actor: world.add_actor(pos, size.x as i32, size.y as i32), speed: vec2(0.0, 0.0) });
+        self.healths.insert(entity, Health::new(ENEMY_HEALTH));
+        self.contact_damages.insert(entity, ContactDamage(damage));
+        self.enemies.insert(entity, EnemyAi::new(pos, size).await);
+        entity
+    }
+}
@@ -0,0 +1,413 @@
+use std::collections::BTreeMap;
+use macroquad::color::{GREEN, RED, WHITE};
+use macroquad::math::{vec2, Vec2};
+use macroquad::prelude::{draw_texture_ex, DrawTextureParams, Texture2D};
+use macroquad::shapes::draw_rectangle;
+use macroquad_platformer::World;
+use crate::logic::ecs::components::{HealthTimer, Loot, Pickup, ProjectileOrigin, HOMING_RANGE};
+use crate::logic::ecs::{Entities, Entity};
+use crate::logic::ecs::prefabs::ProjectileSpawn;
+use crate::logic::event::{EventQueue, GameEvent};
+use crate::logic::player::{CollectedPowerUp, Player, COINS_PER_LIFE};
+use crate::logic::power_up::Stacking;
+use crate::utils::enums::{Direction, TextureKey};
+use crate::utils::mathemann::{stretch_float_to, vec2_to_degrees};
+use crate::utils::runtime::get_frame_time;
+use crate::utils::structs::Settings;
+
+/// Collects all [Pickup]s the player touches <br>
+/// `coins` are the coins the player collected before this frame (see [crate::logic::tally::Tally::coins])
+pub async fn pickups(entities: &mut Entities, player: &mut Player, mut coins: u32, events: &mut EventQueue) {
+    let mut collected = Vec::new();
+
+    for (entity, pickup) in &entities.pickups {
+        let Some(collider) = entities.colliders.get(entity) else { continue };
+        if !collider.touching_player(player).await { continue; }
+
+        match pickup {
+            Pickup::Coin => {
+                let amount = player.modifiers().coin_value;
+                // Every [COINS_PER_LIFE] coins grant an extra life
+                let lives = (coins + amount) / COINS_PER_LIFE - coins / COINS_PER_LIFE;
+                coins += amount;
+                events.push(GameEvent::CoinCollected { amount });
+                for _ in 0..lives {
+                    player.add_life(events);
+                }
+            }
+            Pickup::PowerUp { power_up, duration } => {
+                let sprite = entities.sprites.get(entity).unwrap();
+                // The same power up can be collected again while it is active (see [Stacking])
+                let remaining = player.power_up_timers.remaining(power_up);
+                let duration = match power_up.stacking() {
+                    Stacking::Extend => remaining + duration,
+                    Stacking::Refresh => remaining.max(*duration),
+                };
+                player.power_ups.insert(*power_up, CollectedPowerUp {
+                    duration,
+                    texture_key: sprite.texture_key,
+                    animation: sprite.animation.unwrap(),
+                });
+                player.power_up_timers.start(*power_up, duration);
+                events.push(GameEvent::PowerUpPicked { power_up: *power_up });
+            }
+            Pickup::ExtraLife => player.add_life(events),
+            Pickup::SmallHeart | Pickup::LargeHeart => {
+                // Hearts stay in the level until the player needs them
+                if player.health >= player.max_health { continue; }
+                let amount = player.heal(pickup.heal());
+                events.push(GameEvent::PlayerHealed { amount });
+            }
+            Pickup::Weapon { weapon } => {
+                player.add_weapon(*weapon);
+                events.push(GameEvent::WeaponPicked { weapon: *weapon });
+            }
+        }
+
+        collected.push(*entity);
+    }
+
+    for entity in collected {
+        entities.despawn(entity);
+    }
+}
+
+/// Pulls the coins inside of the magnet radius of the player (see [crate::logic::power_up::Modifiers::magnet_radius]) towards them
+pub async fn magnet(entities: &mut Entities, player: &Player, settings: &Settings) {
+    let radius = player.modifiers().magnet_radius * 128.0 * settings.gui_scale;
+    if radius <= 0.0 { return; }
+
+    let center = player.pos + vec2(player.width, player.height) / 2.0;
+    let speed = 1500.0 * settings.gui_scale * get_frame_time();
+
+    for (entity, pickup) in &entities.pickups {
+        if *pickup != Pickup::Coin { continue; }
+        let Some(transform) = entities.transforms.get_mut(entity) else { continue };
+
+        let distance = center - (transform.pos + transform.size / 2.0);
+        if distance.length() > radius { continue; }
+        transform.pos += distance.clamp_length_max(speed);
+
+        if let Some(collider) = entities.colliders.get_mut(entity) {
+            collider.change_pos(transform.pos).await;
+        }
+    }
+}
+
+/// Lets the AI of all enemies decide where they go and moves their [crate::logic::ecs::components::Body] <br>
+/// Enemies that fall out of the level get removed
+pub async fn enemies(entities: &mut Entities, world: &mut World, player: &Player, settings: &Settings) {
+    let mut fallen = Vec::new();
+
+    for (entity, ai) in entities.enemies.iter_mut() {
+        let (Some(body), Some(transform)) = (entities.bodies.get_mut(entity), entities.transforms.get_mut(entity)) else { continue };
+        ai.tick(body, transform, world, player, settings).await;
+
+        if let Some(collider) = entities.colliders.get_mut(entity) {
+            collider.change_pos(transform.pos).await;
+        }
+        if transform.pos.y > 0.0 { fallen.push(*entity); }
+    }
+
+    for entity in fallen {
+        entities.despawn(entity);
+    }
+}
+
+/// Applies the damage of the projectiles of the player to all entities with [crate::logic::ecs::components::Health] <br>
+/// Every projectile damages an entity only once (see [crate::logic::ecs::components::Damage::damaged]). <br>
+/// The entities stay until [deaths()] runs, so the projectile that killed them still hits them (see [hits()])
+pub async fn damage(entities: &mut Entities, events: &mut EventQueue) {
+    // The overlay shows that the entity got hit
+    for (entity, health) in entities.healths.iter_mut() {
+        let Some(sprite) = entities.sprites.get_mut(entity) else { continue };
+        if health.timers.is_active(&HealthTimer::DamageOverlay) {
+            sprite.color = RED;
+        }
+        for timer in health.timers.expired() {
+            if timer == HealthTimer::DamageOverlay {
+                sprite.color = WHITE;
+            }
+        }
+    }
+
+    let mut hits = Vec::new();
+    for entity in entities.healths.keys() {
+        let Some(collider) = entities.colliders.get(entity) else { continue };
+        for projectile in collider.collide_check_projectile(entities, vec2(0.0, 0.0)).await {
+            let damage = entities.damages.get(&projectile).unwrap();
+            if damage.origin == ProjectileOrigin::Player {
+                hits.push((*entity, projectile));
+            }
+        }
+    }
+
+    for (entity, projectile) in hits {
+        let health = entities.healths.get_mut(&entity).unwrap();
+        let damage = entities.damages.get_mut(&projectile).unwrap();
+        if health.amount == 0 || damage.damaged.contains(&entity) { continue; }
+        damage.damaged.push(entity);
+
+        let amount = damage.amount;
+        health.amount = (health.amount + amount).max(0);
+
+        let pos = entities.transforms.get(&entity).unwrap().pos;
+        let damage = -amount as u32;
+        if health.amount == 0 {
+            events.push(GameEvent::EnemyKilled { pos, damage });
+        } else {
+            events.push(GameEvent::EnemyDamaged { pos, damage });
+        }
+
+        health.timers.start(HealthTimer::DamageOverlay, 0.25);
+    }
+}
+
+/// Removes all entities without health left and spawns their [Loot]
+pub async fn deaths(entities: &mut Entities) {
+    let dead: Vec<Entity> = entities.healths.iter()
+        .filter(|(_, health)| health.amount == 0)
+        .map(|(entity, _)| *entity)
+        .collect();
+
+    for entity in dead {
+        if let (Some(Loot(pickup)), Some(transform)) = (entities.loot.get(&entity).cloned(), entities.transforms.get(&entity).copied()) {
+            entities.spawn_drop(pickup, transform.pos, transform.size).await;
+        }
+        entities.despawn(entity);
+    }
+}
+
+/// Lets all [crate::logic::ecs::components::Shooter]s shoot if their timer ran out
+pub async fn shooters(entities: &mut Entities, events: &mut EventQueue) {
+    let mut shots = Vec::new();
+
+    for (entity, shooter) in entities.shooters.iter_mut() {
+        if !shooter.timer.finished() { continue; }
+        shooter.timer.restart();
+
+        let transform = entities.transforms.get(entity).unwrap();
+        let size = transform.size / 2.0;
+        let pos = transform.pos + transform.size / 2.0 - size / 2.0;
+
+        let movement_vector = match shooter.direction {
+            Direction::Right => vec2(1.0, 0.0) * shooter.projectile_speed,
+            Direction::Left => vec2(-1.0, 0.0) * shooter.projectile_speed,
+            Direction::Up => vec2(0.0, -1.0) * shooter.projectile_speed,
+            Direction::Down => vec2(0.0, 1.0) * shooter.projectile_speed,
+        };
+
+        events.push(GameEvent::CannonFired { pos: transform.pos });
+        shots.push(ProjectileSpawn {
+            pos,
+            size,
+            speed: movement_vector,
+            damage: shooter.damage,
+            origin: ProjectileOrigin::Canon,
+            max_time: shooter.projectile_time,
+            texture_key: shooter.projectile_texture_key,
+            behaviour: shooter.projectile_behaviour,
+        });
+    }
+
+    for shot in shots {
+        entities.spawn_projectile(shot).await;
+    }
+}
+
+/// Removes all entities with [crate::logic::ecs::components::Damage] that hit a platform, an enemy or the player <br>
+/// Projectiles that can still bounce or pierce (see [crate::logic::ecs::components::ProjectileBehaviour]) use that up instead <br>
+/// The damage itself gets applied by [damage()] and the player
+pub async fn hits(entities: &mut Entities, player: &Player, events: &mut EventQueue) {
+    let mut hit = Vec::new();
+    let mut bounced = Vec::new();
+
+    let projectiles: Vec<Entity> = entities.damages.keys().copied().collect();
+    for entity in &projectiles {
+        let Some(collider) = entities.colliders.get(entity).copied() else { continue };
+        let mut behaviour = entities.behaviours.get(entity).copied().unwrap_or_default();
+
+        let colliding_with_platform = !collider.collide_check_platform(entities, vec2(0.0, 0.0)).await.is_empty();
+        let enemies = collider.collide_check_enemy(entities, vec2(0.0, 0.0)).await;
+        let damage = entities.damages.get_mut(entity).unwrap();
+        let colliding_with_player = damage.origin != ProjectileOrigin::Player && collider.touching_player(player).await;
+
+        // Every enemy only counts once, even if the projectile is inside of it for multiple frames
+        let mut colliding_with_enemy = false;
+        let mut pierced = false;
+        for enemy in enemies {
+            if damage.pierced.contains(&enemy) { continue; }
+            if behaviour.pierce > 0 {
+                behaviour.pierce -= 1;
+                damage.pierced.push(enemy);
+                pierced = true;
+            } else {
+                colliding_with_enemy = true;
+            }
+        }
+
+        let bounce = colliding_with_platform && behaviour.bounces > 0;
+        if bounce {
+            behaviour.bounces -= 1;
+            bounced.push(*entity);
+        }
+
+        if colliding_with_platform || colliding_with_enemy || colliding_with_player || pierced {
+            events.push(GameEvent::ProjectileHit { pos: collider.pos().await, origin: damage.origin.clone() });
+        }
+        if (colliding_with_platform && !bounce) || colliding_with_enemy || colliding_with_player {
+            hit.push(*entity);
+        }
+
+        if let Some(stored) = entities.behaviours.get_mut(entity) {
+            *stored = behaviour;
+        }
+    }
+
+    // Bouncing projectiles go back to where they were before they hit the platform and fly back
+    let frame_time = get_frame_time();
+    for entity in bounced {
+        let (Some(transform), Some(velocity), Some(collider)) = (entities.transforms.get(&entity), entities.velocities.get(&entity), entities.colliders.get(&entity)) else { continue };
+        let previous = transform.pos - velocity.0 * frame_time;
+
+        // Checks which part of the last movement went into the platform
+        let hit_x = !collider.collide_check_platform(entities, vec2(0.0, previous.y - transform.pos.y)).await.is_empty();
+        let hit_y = !collider.collide_check_platform(entities, vec2(previous.x - transform.pos.x, 0.0)).await.is_empty();
+
+        let (Some(transform), Some(velocity), Some(collider)) = (entities.transforms.get_mut(&entity), entities.velocities.get_mut(&entity), entities.colliders.get_mut(&entity)) else { continue };
+        if hit_x {
+            velocity.0.x = -velocity.0.x;
+        }
+        if hit_y || !hit_x {
+            velocity.0.y = -velocity.0.y;
+        }
+
+        transform.pos = previous;
+        collider.change_pos(previous).await;
+    }
+
+    for entity in hit {
+        entities.despawn(entity);
+    }
+}
+
+/// Applies the gravity and homing of all [crate::logic::ecs::components::ProjectileBehaviour]s to the [crate::logic::ecs::components::Velocity] of their entity
+pub async fn projectile_behaviours(entities: &mut Entities, player: &Player, settings: &Settings) {
+    let frame_time = get_frame_time();
+    let enemies: Vec<Vec2> = entities.enemies.keys()
+        .filter(|enemy| entities.healths.get(enemy).is_some_and(|health| health.amount > 0))
+        .filter_map(|enemy| entities.transforms.get(enemy))
+        .map(|transform| transform.pos + transform.size / 2.0)
+        .collect();
+
+    for (entity, behaviour) in &entities.behaviours {
+        let Some(velocity) = entities.velocities.get_mut(entity) else { continue };
+        let Some(transform) = entities.transforms.get(entity) else { continue };
+
+        velocity.0.y += behaviour.gravity * settings.gui_scale * frame_time;
+
+        if behaviour.homing <= 0.0 { continue; }
+
+        let center = transform.pos + transform.size / 2.0;
+        let targets: Vec<Vec2> = match entities.damages.get(entity).map(|damage| &damage.origin) {
+            Some(ProjectileOrigin::Player) => enemies.clone(),
+            _ => vec![player.pos + vec2(player.width, player.height) / 2.0],
+        };
+        let range = HOMING_RANGE * 128.0 * settings.gui_scale;
+        let target = targets.into_iter()
+            .filter(|target| target.distance(center) <= range)
+            .min_by(|a, b| a.distance(center).total_cmp(&b.distance(center)));
+
+        // Turns the projectile towards the target without changing its speed
+        // (a projectile that stands still or is already at the target has no direction to turn)
+        let Some(target) = target else { continue };
+        if let (Some(direction), Some(to_target)) = (velocity.0.try_normalize(), (target - center).try_normalize()) {
+            let max_angle = behaviour.homing.to_radians() * frame_time;
+            let angle = direction.angle_between(to_target).clamp(-max_angle, max_angle);
+            velocity.0 = Vec2::from_angle(angle).rotate(velocity.0);
+        }
+    }
+}
+
+/// Removes all entities whose [crate::logic::ecs::components::Lifetime] ran out
+pub async fn lifetimes(entities: &mut Entities) {
+    let expired: Vec<_> = entities.lifetimes.iter()
+        .filter(|(_, lifetime)| lifetime.0.finished())
+        .map(|(entity, _)| *entity)
+        .collect();
+
+    for entity in expired {
+        entities.despawn(entity);
+    }
+}
+
+/// Moves all entities with a [crate::logic::ecs::components::Velocity]
+pub async fn movement(entities: &mut Entities) {
+    for (entity, velocity) in &entities.velocities {
+        let Some(transform) = entities.transforms.get_mut(entity) else { continue };
+        transform.pos += velocity.0 * get_frame_time();
+
+        if let Some(collider) = entities.colliders.get_mut(entity) {
+            collider.change_pos(transform.pos).await;
+        }
+    }
+}
+
+pub async fn render(entities: &mut Entities, textures: &BTreeMap<TextureKey, Vec<Texture2D>>) {
+    // Platforms are drawn below everything else
+    for (entity, platform) in &entities.platforms {
+        let Some(transform) = entities.transforms.get(entity) else { continue };
+        platform.render(transform.pos, textures).await;
+    }
+
+    for (entity, sprite) in entities.sprites.iter_mut() {
+        let Some(transform) = entities.transforms.get(entity) else { continue };
+
+        let index = match &mut sprite.animation {
+            Some(animation) => {
+                animation.animate().await;
+                animation.index as usize
+            }
+            None => sprite.index,
+        };
+
+        let rotation = match entities.velocities.get(entity) {
+            Some(velocity) if sprite.rotate => vec2_to_degrees(velocity.0.normalize_or_zero()).await,
+            _ => 0.0,
+        };
+
+        draw_texture_ex(
+            textures.get(&sprite.texture_key).unwrap().get(index).unwrap(), transform.pos.x, transform.pos.y, sprite.color,
+            DrawTextureParams {
+                dest_size: Some(transform.size),
+                rotation,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+/// Draws a health bar above every entity with [crate::logic::ecs::components::Health] that got damaged
+pub async fn health_bars(entities: &Entities, settings: &Settings) {
+    for (entity, health) in &entities.healths {
+        if health.amount >= health.max { continue; }
+        let Some(transform) = entities.transforms.get(entity) else { continue };
+
+        let spacing = transform.size.x / 16.0;
+        let width = stretch_float_to(health.amount as f32, health.max as f32, transform.size.x - spacing * 2.0).await;
+        let width_full = transform.size.x - spacing * 2.0;
+        let height = 16.0 * settings.gui_scale;
+        let pos = vec2(transform.pos.x + spacing, transform.pos.y - height * 2.0);
+
+        // Full health (start health)
+        draw_rectangle(pos.x, pos.y, width_full, height, RED);
+        // Actual health
+        draw_rectangle(pos.x, pos.y, width, height, GREEN);
+    }
+}
+
+pub async fn debug_render(entities: &Entities, settings: &Settings) {
+    for collider in entities.colliders.values() {
+        collider.debug_render(settings).await;
+    }
+}
@@ -0,0 +1,123 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use macroquad::math::Vec2;
+use crate::logic::player::PlayerPowerUp;
+use crate::logic::ecs::components::ProjectileOrigin;
+use crate::logic::weapon::WeaponKind;
+
+/// Something that happened in a level <br>
+/// Positions are in pixels of the level
+#[derive(PartialEq, Clone, Debug)]
+pub enum GameEvent {
+    CoinCollected { amount: u32 },
+    /// `damage` is the damage of the killing hit
+    EnemyKilled { pos: Vec2, damage: u32 },
+    PlayerDamaged { damage: u32 },
+    /// The player collected a heart, `amount` is the health that was gained
+    PlayerHealed { amount: u32 },
+    PlayerJumped,
+    PlayerDashed,
+    PlayerShot,
+    /// An enemy got hit but survived
+    EnemyDamaged { pos: Vec2, damage: u32 },
+    CannonFired { pos: Vec2 },
+    PowerUpPicked { power_up: PlayerPowerUp },
+    WeaponPicked { weapon: WeaponKind },
+    /// The player lost a life and respawned (lives mode), `lives` are the remaining lives
+    LifeLost { lives: u32 },
+    /// The player got an extra life (lives mode)
+    LifeGained { lives: u32 },
+    ProjectileHit { pos: Vec2, origin: ProjectileOrigin },
+    /// A timer ran out (see [crate::logic::timer::Timers::expired()])
+    TimerExpired { timer: LevelTimer },
+    LevelCompleted,
+    GameOver,
+}
+
+/// The timers that push a [GameEvent::TimerExpired] when they run out
+#[derive(PartialEq, Clone, Debug)]
+pub enum LevelTimer {
+    /// A power up of the player ran out
+    PowerUp(PlayerPowerUp),
+    /// A timer of the level script (see `timer()` in `logic/script.rs`)
+    Script(String),
+}
+
+/// Gets called with every event of the level (see [EventQueue::subscribe()])
+type Subscriber = Rc<RefCell<dyn FnMut(&GameEvent)>>;
+
+/// Collects all [GameEvent]s of the current frame <br>
+/// Everything that is interested in events (the score, the audio, the script, ...) subscribes to the queue
+/// and gets every event once [EventQueue::dispatch()] runs (see [crate::scenes::levels::levels::step_level()]) <br>
+/// Gets cleared at the start of every frame
+#[derive(Clone, Default)]
+pub struct EventQueue {
+    events: Vec<GameEvent>,
+    /// How many events were already sent to the subscribers
+    dispatched: usize,
+    subscribers: Vec<Subscriber>,
+}
+
+impl EventQueue {
+    pub fn new() -> Self {
+        Self { events: Vec::new(), dispatched: 0, subscribers: Vec::new() }
+    }
+
+    /// Calls `subscriber` with every event from now on (in the order they happened) <br>
+    /// Subscribers get the events in the order they subscribed
+    pub fn subscribe(&mut self, subscriber: impl FnMut(&GameEvent) + 'static) {
+        self.subscribers.push(Rc::new(RefCell::new(subscriber)));
+    }
+
+    pub fn push(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+
+    /// Returns all events of the current frame in the order they happened
+    pub fn iter(&self) -> impl Iterator<Item = &GameEvent> {
+        self.events.iter()
+    }
+
+    /// Sends the events that were pushed since the last call to the subscribers
+    pub fn dispatch(&mut self) {
+        for event in &self.events[self.dispatched..] {
+            for subscriber in &self.subscribers {
+                (subscriber.borrow_mut())(event);
+            }
+        }
+        self.dispatched = self.events.len();
+    }
+
+    /// Removes all events of the last frame
+    pub fn clear(&mut self) {
+        self.events.clear();
+        self.dispatched = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subscribers_get_every_event_once() {
+        let mut events = EventQueue::new();
+        let received = Rc::new(RefCell::new(Vec::new()));
+        let r = received.clone();
+        events.subscribe(move |event| r.borrow_mut().push(event.clone()));
+
+        events.push(GameEvent::PlayerJumped);
+        events.dispatch();
+        events.push(GameEvent::PlayerShot);
+        events.dispatch();
+        events.dispatch();
+
+        assert_eq!(*received.borrow(), vec![GameEvent::PlayerJumped, GameEvent::PlayerShot]);
+
+        events.clear();
+        events.push(GameEvent::LevelCompleted);
+        events.dispatch();
+        assert_eq!(received.borrow().last(), Some(&GameEvent::LevelCompleted));
+        assert_eq!(received.borrow().len(), 3);
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+use macroquad::color::{Color, GREEN, RED};
+use macroquad::math::{vec2, Vec2};
+use macroquad::prelude::{draw_texture_ex, DrawTextureParams, Texture2D};
+use macroquad::window::screen_width;
+use serde::{Deserialize, Serialize};
+use crate::logic::level::{Level, PersistentLevelData};
+use crate::logic::player::Player;
+use crate::utils::enums::TextureKey;
+use crate::utils::structs::Settings;
+use crate::utils::text::{draw_str, measure_str};
+
+/// A single recorded position of the player <br>
+/// The position is saved in tiles (not pixels) so replays work with every GUI scale
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct ReplayFrame {
+    /// The time since the start of the level
+    pub time: f64,
+    pub x: f32,
+    pub y: f32,
+    /// The state of the player (see [Player::state])
+    pub state: i8,
+}
+
+/// Records the path of the player while a level is played
+#[derive(PartialEq, Clone, Debug)]
+pub struct ReplayRecorder {
+    pub frames: Vec<ReplayFrame>,
+    last_record: f64,
+}
+
+impl ReplayRecorder {
+    /// How often a frame gets recorded (in seconds)
+    const INTERVAL: f64 = 1.0 / 30.0;
+
+    pub fn new() -> Self {
+        Self { frames: Vec::new(), last_record: f64::MIN }
+    }
+
+    /// Continues the recording of a saved level (see [crate::logic::snapshot::LevelSnapshot])
+    pub fn resume(frames: Vec<ReplayFrame>) -> Self {
+        let last_record = frames.last().map(|frame| frame.time).unwrap_or(f64::MIN);
+        Self { frames, last_record }
+    }
+
+    pub async fn record(&mut self, player: &Player, elapsed: f64, settings: &Settings) {
+        if self.last_record + Self::INTERVAL > elapsed { return; }
+        self.last_record = elapsed;
+
+        let tile_size = 128.0 * settings.gui_scale;
+        self.frames.push(ReplayFrame {
+            time: elapsed,
+            x: player.pos.x / tile_size,
+            y: player.pos.y / tile_size,
+            state: player.state,
+        });
+    }
+}
+
+/// A translucent copy of the player that follows the path of the best run
+#[derive(PartialEq, Clone, Debug)]
+pub struct Ghost {
+    pub frames: Vec<ReplayFrame>,
+    /// The playtime of the run the ghost is based on
+    pub playtime: f64,
+    /// The index of the frame the player was compared with last (see [Ghost::delta()])
+    matched: usize,
+}
+
+impl Ghost {
+    /// How far ahead of the last matched frame the player gets searched for (in seconds of the ghost)
+    const SEARCH_WINDOW: f64 = 2.0;
+
+    /// Returns the [Ghost] of the fastest completed run of the level (if one exists)
+    pub async fn best(level: &Level, persistent_level_data: &PersistentLevelData) -> Option<Self> {
+        let (playtime, frames) = persistent_level_data.scores.best_run(level)?;
+        if frames.is_empty() { return None; }
+
+        Some(Self { frames, playtime, matched: 0 })
+    }
+
+    /// Returns the interpolated position (in tiles) and the state of the ghost at the provided time
+    async fn at(&self, elapsed: f64) -> (Vec2, i8) {
+        let next = self.frames.partition_point(|frame| frame.time <= elapsed);
+
+        if next == 0 {
+            let frame = self.frames.first().unwrap();
+            return (vec2(frame.x, frame.y), frame.state);
+        }
+        if next >= self.frames.len() {
+            let frame = self.frames.last().unwrap();
+            return (vec2(frame.x, frame.y), frame.state);
+        }
+
+        let previous = &self.frames[next - 1];
+        let next = &self.frames[next];
+        let t = ((elapsed - previous.time) / (next.time - previous.time)) as f32;
+        let pos = vec2(previous.x, previous.y).lerp(vec2(next.x, next.y), t);
+
+        (pos, previous.state)
+    }
+
+    /// Returns how many seconds the player is behind (positive) or ahead (negative) of the ghost <br>
+    /// This compares the current time with the time the ghost needed to reach the closest point of its path <br>
+    /// Only the frames shortly after the last matched one get searched, so paths that cross themselves don't make the delta jump
+    pub async fn delta(&mut self, player: &Player, elapsed: f64, settings: &Settings) -> f64 {
+        let tile_size = 128.0 * settings.gui_scale;
+        let pos = player.pos / tile_size;
+        let window_end = self.frames[self.matched].time + Self::SEARCH_WINDOW;
+
+        let (closest, _) = self.frames.iter()
+            .enumerate()
+            .skip(self.matched)
+            .take_while(|(_, frame)| frame.time <= window_end)
+            .min_by(|(_, a), (_, b)| {
+                let a = vec2(a.x, a.y).distance_squared(pos);
+                let b = vec2(b.x, b.y).distance_squared(pos);
+                a.total_cmp(&b)
+            })
+            .unwrap();
+        self.matched = closest;
+
+        elapsed - self.frames[closest].time
+    }
+
+    pub async fn render(&self, textures: &BTreeMap<TextureKey, Vec<Texture2D>>, player: &Player, elapsed: f64, settings: &Settings) {
+        let tile_size = 128.0 * settings.gui_scale;
+        let (pos, state) = self.at(elapsed).await;
+        let pos = pos * tile_size;
+
+        draw_texture_ex(
+            textures.get(&TextureKey::Player).unwrap().get(state as usize).unwrap(), pos.x, pos.y, Color::new(1.0, 1.0, 1.0, 0.4),
+            DrawTextureParams {
+                dest_size: Some(vec2(player.width, player.height)),
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Draws the time delta to the best run at the top center of the screen
+    pub async fn render_delta(&mut self, player: &Player, elapsed: f64, zero: Vec2, settings: &Settings) {
+        let delta = self.delta(player, elapsed, settings).await;
+        let (text, color) = if delta > 0.0 {
+            (format!("+{:.2}s", delta), RED)
+        } else {
+            (format!("-{:.2}s", delta.abs()), GREEN)
+        };
+
+        let font_size = 64.0 * settings.gui_scale;
+        let size = measure_str(&text, font_size);
+        draw_str(&text, zero.x + screen_width() / 2.0 - size.width / 2.0, zero.y + size.offset_y + 8.0 * settings.gui_scale, font_size, color);
+    }
+}
@@ -0,0 +1,193 @@
+use std::fmt;
+use macroquad::input::{is_key_down, is_key_pressed, is_mouse_button_pressed, mouse_position, KeyCode, MouseButton};
+use macroquad::math::{vec2, Vec2};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use crate::utils::i18n;
+
+/// Contains everything the player wants to do in the current frame <br>
+/// Gets read from the keyboard or provided by a script when simulating levels
+#[derive(PartialEq, Clone, Copy, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct PlayerInput {
+    pub left: bool,
+    pub right: bool,
+    pub jump: bool,
+    pub shoot_left: bool,
+    pub shoot_right: bool,
+    pub dash: bool,
+    /// Switches to the next weapon the player carries
+    pub next_weapon: bool,
+    /// The screen position the player shoots at (if any)
+    #[serde(skip)]
+    pub aim: Option<Vec2>,
+}
+
+impl PlayerInput {
+    /// Reads the input of the current frame from the keyboard (using the keybindings of the profile) and mouse
+    pub async fn from_keyboard(keybindings: &Keybindings) -> Self {
+        let aim = match is_mouse_button_pressed(MouseButton::Left) {
+            true => {
+                let (x, y) = mouse_position();
+                Some(vec2(x, y))
+            }
+            false => None,
+        };
+
+        Self {
+            left: keybindings.is_down(InputAction::Left),
+            right: keybindings.is_down(InputAction::Right),
+            jump: keybindings.is_down(InputAction::Jump),
+            shoot_left: keybindings.is_pressed(InputAction::ShootLeft),
+            shoot_right: keybindings.is_pressed(InputAction::ShootRight),
+            dash: keybindings.is_pressed(InputAction::Dash),
+            next_weapon: keybindings.is_pressed(InputAction::NextWeapon),
+            aim,
+        }
+    }
+}
+
+/// Everything the player can do with the keyboard
+#[derive(PartialEq, Eq, Clone, Copy, Ord, PartialOrd, Debug)]
+pub enum InputAction {
+    Left,
+    Right,
+    Jump,
+    ShootLeft,
+    ShootRight,
+    Dash,
+    NextWeapon,
+}
+
+impl InputAction {
+    pub const ALL: [InputAction; 7] = [InputAction::Left, InputAction::Right, InputAction::Jump, InputAction::ShootLeft, InputAction::ShootRight, InputAction::Dash, InputAction::NextWeapon];
+
+    /// Returns the name of the action in the language of the player
+    pub fn name(&self) -> String {
+        let key = match self {
+            InputAction::Left => "input.move_left",
+            InputAction::Right => "input.move_right",
+            InputAction::Jump => "input.jump",
+            InputAction::ShootLeft => "input.shoot_left",
+            InputAction::ShootRight => "input.shoot_right",
+            InputAction::Dash => "input.dash",
+            InputAction::NextWeapon => "input.next_weapon",
+        };
+        i18n::text(key)
+    }
+}
+
+/// The keys of every [InputAction] (every action can have multiple keys)
+#[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Keybindings {
+    pub left: Vec<Key>,
+    pub right: Vec<Key>,
+    pub jump: Vec<Key>,
+    pub shoot_left: Vec<Key>,
+    pub shoot_right: Vec<Key>,
+    pub dash: Vec<Key>,
+    pub next_weapon: Vec<Key>,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self {
+            left: vec![Key(KeyCode::A), Key(KeyCode::Left)],
+            right: vec![Key(KeyCode::D), Key(KeyCode::Right)],
+            jump: vec![Key(KeyCode::Space)],
+            shoot_left: vec![Key(KeyCode::Q)],
+            shoot_right: vec![Key(KeyCode::E)],
+            dash: vec![Key(KeyCode::LeftShift)],
+            next_weapon: vec![Key(KeyCode::Tab)],
+        }
+    }
+}
+
+impl Keybindings {
+    pub fn keys(&self, action: InputAction) -> &Vec<Key> {
+        match action {
+            InputAction::Left => &self.left,
+            InputAction::Right => &self.right,
+            InputAction::Jump => &self.jump,
+            InputAction::ShootLeft => &self.shoot_left,
+            InputAction::ShootRight => &self.shoot_right,
+            InputAction::Dash => &self.dash,
+            InputAction::NextWeapon => &self.next_weapon,
+        }
+    }
+
+    /// Replaces all keys of the action with the provided key
+    pub fn bind(&mut self, action: InputAction, key: Key) {
+        let keys = match action {
+            InputAction::Left => &mut self.left,
+            InputAction::Right => &mut self.right,
+            InputAction::Jump => &mut self.jump,
+            InputAction::ShootLeft => &mut self.shoot_left,
+            InputAction::ShootRight => &mut self.shoot_right,
+            InputAction::Dash => &mut self.dash,
+            InputAction::NextWeapon => &mut self.next_weapon,
+        };
+        *keys = vec![key];
+    }
+
+    pub fn is_down(&self, action: InputAction) -> bool {
+        self.keys(action).iter().any(|key| is_key_down(key.0))
+    }
+
+    pub fn is_pressed(&self, action: InputAction) -> bool {
+        self.keys(action).iter().any(|key| is_key_pressed(key.0))
+    }
+
+    /// Returns the keys of the action as text (e.g. `A / Left`)
+    pub fn text(&self, action: InputAction) -> String {
+        self.keys(action).iter().map(|key| key.to_string()).collect::<Vec<_>>().join(" / ")
+    }
+}
+
+/// A key that can be used in [Keybindings] <br>
+/// Gets saved with its name (e.g. `"Space"`)
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Key(pub KeyCode);
+
+impl Key {
+    /// All keys that can be bound
+    const BINDABLE: [KeyCode; 56] = [
+        KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G, KeyCode::H, KeyCode::I,
+        KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N, KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R,
+        KeyCode::S, KeyCode::T, KeyCode::U, KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+        KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+        KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+        KeyCode::Left, KeyCode::Right, KeyCode::Up, KeyCode::Down,
+        KeyCode::Space, KeyCode::Enter, KeyCode::Tab, KeyCode::Backspace,
+        KeyCode::LeftShift, KeyCode::RightShift, KeyCode::LeftControl, KeyCode::RightControl, KeyCode::LeftAlt, KeyCode::RightAlt,
+        KeyCode::Comma, KeyCode::Period, KeyCode::Minus, KeyCode::Slash, KeyCode::Semicolon, KeyCode::Apostrophe,
+    ];
+
+    /// Returns the key if it can be bound
+    pub fn new(key_code: KeyCode) -> Option<Self> {
+        Self::BINDABLE.contains(&key_code).then_some(Self(key_code))
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::BINDABLE.iter().find(|key_code| format!("{:?}", key_code) == name).map(|key_code| Self(*key_code))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl Serialize for Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Key::from_name(&name).ok_or_else(|| serde::de::Error::custom(format!("Unknown key \"{}\"", name)))
+    }
+}
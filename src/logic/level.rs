@@ -1,203 +1,225 @@
+use std::cell::RefCell;
 use std::cmp::max;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use macroquad::camera::set_default_camera;
-use macroquad::color::{BLACK, WHITE};
-use macroquad::prelude::{get_time, Texture2D};
-use macroquad::window::{clear_background, screen_height};
+use macroquad::prelude::Texture2D;
 use std::time::SystemTime;
 use macroquad_platformer::World;
 use stopwatch2::Stopwatch;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use std::fs;
-use std::io::Write;
+use std::path::Path;
+use std::rc::Rc;
 use macroquad::math::{vec2, Vec2};
-use crate::logic::cannon::Cannon;
-use crate::logic::collectible::{Collectible, CollectibleType};
-use crate::logic::enemy::Enemy;
-use crate::logic::platform::Platform;
-use crate::logic::player::{Player, PlayerPowerUp, PowerUp};
-use crate::logic::projectile::Projectile;
+use crate::logic::ecs::{systems, Entities};
+use crate::logic::event::{EventQueue, GameEvent};
+use crate::logic::ghost::{Ghost, ReplayFrame, ReplayRecorder};
+use crate::logic::physics::PhysicsConfig;
+use crate::logic::player::Player;
+use crate::logic::script::LevelScript;
+use crate::logic::tally::Tally;
 use crate::utils::structs::{Settings};
-use crate::utils::enums::{Scene, TextureKey};
+use crate::utils::enums::{Scene, SoundKey, TextureKey};
 use crate::utils::enums::Scene::LevelSelector;
-use crate::utils::random::remove_elements_vec;
-use crate::utils::text::{draw_text_center, draw_text_centered};
+use crate::utils::{audio, runtime};
+use crate::utils::runtime::get_time;
 use crate::utils::texture::get_resources_path;
+use crate::utils::versioning::{current_version, load_save_file, save_file, Migration};
+use crate::logic::score_log::ScoreLog;
+use crate::ui::notice;
+use crate::utils::i18n;
+use crate::utils::debugger::DebugView;
 
+/// Renders the level (the result screen gets drawn above it when the level is over, see [crate::scenes::levels::result_screen])
 pub async fn render_level(level_scene_data: &mut LevelSceneData, textures: &BTreeMap<TextureKey, Vec<Texture2D>>, settings: &Settings) {
-    if *level_scene_data.level_data.triggers.get(&Trigger::LevelCompleted).unwrap_or(&false) {
-        set_default_camera();
-        clear_background(BLACK);
-        draw_text_center("Congratulations!", 150.0 * settings.gui_scale, WHITE).await;
-        draw_text_centered(format!("You completed {}! Press ESC to go back", level_scene_data.level_data.level.as_ref().unwrap().name()).as_str(), screen_height() / 2.0 + 250.0 * settings.gui_scale, 60.0 * settings.gui_scale, WHITE).await;
-    } else if *level_scene_data.level_data.triggers.get(&Trigger::GameOver).unwrap_or(&false) {
-        set_default_camera();
-        clear_background(BLACK);
-        draw_text_center("GAME OVER", 250.0 * settings.gui_scale, WHITE).await;
-        draw_text_centered("Press ESC to go back or Ctrl + R to retry", screen_height() / 2.0 + 250.0 * settings.gui_scale, 60.0 * settings.gui_scale, WHITE).await;
-    } else {
-        let world = &level_scene_data.world;
-        let platforms = &level_scene_data.level_data.platforms;
-        let collectibles = &mut level_scene_data.level_data.collectibles;
-        let enemies = &level_scene_data.level_data.enemies;
-        let cannons = &level_scene_data.level_data.cannons;
-        let projectiles = &level_scene_data.level_data.projectiles;
-        let power_ups = &mut level_scene_data.level_data.power_ups;
-
-        // Render collectibles
-        for collectible in collectibles {
-            collectible.render(textures).await;
-        }
-
-        let platforms = async {
-            // Render Platforms
-            for platform in platforms {
-                platform.render(textures, world).await;
-            }
-        };
+    // Render platforms, enemies, coins, power ups, cannons and projectiles
+    systems::render(&mut level_scene_data.level_data.entities, textures).await;
+    systems::health_bars(&level_scene_data.level_data.entities, settings).await;
 
-        let enemies = async {
-            // Render enemies
-            for enemy in enemies {
-                enemy.render(textures, settings).await;
-            }
-        };
-
-        let cannons = async {
-            for cannon in cannons {
-                cannon.render(textures).await;
-            }
-        };
-
-        let projectiles = async {
-            // Render projectiles
-            for projectile in projectiles {
-                projectile.render(textures).await;
-            }
-        };
+    // Render texts of the level script
+    if let Some(script) = &level_scene_data.level_data.script {
+        script.render(level_scene_data.level_data.player.as_ref().unwrap(), textures, settings).await;
+    }
 
-        // Render power ups
-        for power_up in power_ups {
-            power_up.render(textures).await;
+    // Render ghost of the best run
+    if settings.ghost {
+        let elapsed = level_scene_data.level_data.playtime().await;
+        let level_data = &mut level_scene_data.level_data;
+        if let Some(ghost) = &mut level_data.ghost {
+            let player = level_data.player.as_ref().unwrap();
+            ghost.render(textures, player, elapsed, settings).await;
+            ghost.render_delta(player, elapsed, level_data.zero, settings).await;
         }
-
-        platforms.await;
-        enemies.await;
-        cannons.await;
-        projectiles.await;
-
-
-        // Render Player
-        level_scene_data.level_data.player.as_mut().unwrap().render(&world, textures, settings).await;
     }
+
+    // Render Player
+    let world = &level_scene_data.world;
+    let tally = level_scene_data.level_data.tally.borrow().clone();
+    level_scene_data.level_data.player.as_mut().unwrap().render(world, textures, &tally, settings).await;
 }
 
 pub async fn tick_level(level_scene_data: &mut LevelSceneData, settings: &Settings) {
 
-    {   // Tick collectibles
-        let collectibles = &mut level_scene_data.level_data.collectibles;
-        let mut collectibles_to_remove = Vec::new();
-
-        for (i, collectible) in collectibles.iter_mut().enumerate() {
-            collectible.check(level_scene_data.level_data.player.as_ref().unwrap()).await;
-            if collectible.collected {
-                collectibles_to_remove.push(i);
-            }
-        }
-
-        for i in collectibles_to_remove.to_owned() {
-            if collectibles.get(i).unwrap().collectible_type == CollectibleType::Coin {
-                let player = level_scene_data.level_data.player.as_mut().unwrap();
-                if player.power_ups.contains_key(&PlayerPowerUp::Coins2x) {
-                    player.coins += 2;
-                } else {
-                    player.coins += 1;
-                }
-            }
-        }
-
-        *collectibles = remove_elements_vec(collectibles, collectibles_to_remove).await;
+    { // Collect coins and power ups
+        let level_data = &mut level_scene_data.level_data;
+        systems::magnet(&mut level_data.entities, level_data.player.as_ref().unwrap(), settings).await;
+        let coins = level_data.tally.borrow().coins;
+        systems::pickups(&mut level_data.entities, level_data.player.as_mut().unwrap(), coins, &mut level_data.events).await;
     }
     { // Tick enemies
-        let enemies = &mut level_scene_data.level_data.enemies;
-        let projectiles = &mut level_scene_data.level_data.projectiles;
-        let world = &mut level_scene_data.world;
-        let player = &mut level_scene_data.level_data.player.as_mut().unwrap();
+        let level_data = &mut level_scene_data.level_data;
+        let entities = &mut level_data.entities;
 
-        let mut enemies_to_remove = Vec::new();
+        systems::enemies(entities, &mut level_scene_data.world, level_data.player.as_ref().unwrap(), settings).await;
+        systems::damage(entities, &mut level_data.events).await;
+    }
+    { // Tick cannons and projectiles
+        let level_data = &mut level_scene_data.level_data;
+        let entities = &mut level_data.entities;
+
+        systems::shooters(entities, &mut level_data.events).await;
+        systems::hits(entities, level_data.player.as_ref().unwrap(), &mut level_data.events).await;
+        systems::lifetimes(entities).await;
+        systems::projectile_behaviours(entities, level_data.player.as_ref().unwrap(), settings).await;
+        systems::movement(entities).await;
+        // Enemies that got killed in this frame still stopped the projectile that killed them
+        systems::deaths(entities).await;
+    }
+    { // Record replay
+        let level_data = &mut level_scene_data.level_data;
+        let elapsed = level_data.playtime().await;
+        level_data.replay.record(level_data.player.as_ref().unwrap(), elapsed, settings).await;
+    }
+}
 
-        for (i, enemy) in enemies.iter_mut().enumerate() {
-            if enemy.deletable {
-                enemies_to_remove.push(i);
-                continue;
-            }
-            enemy.tick(world, player, projectiles, settings).await;
-        }
+#[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
+/// Contains ALL data that may be saved across multiple sessions of levels
+pub struct PersistentLevelData {
+    /// The version of the file format (see [LEVEL_DATA_MIGRATIONS])
+    pub version: u32,
+    pub stats: BTreeMap<Level, LevelStat>,
+    /// All levels that got unlocked by completing the level before them (see [PersistentLevelData::is_unlocked()])
+    pub unlocks: BTreeSet<Level>,
+    /// The history of all runs, it is stored in its own file and only read when needed
+    #[serde(skip)]
+    pub scores: ScoreLog,
+    /// Scores of version 1 files that still have to be moved into the [ScoreLog] (see [PersistentLevelData::load()])
+    #[serde(default, skip_serializing)]
+    legacy_scores: BTreeMap<Level, Vec<LevelScore>>,
+}
 
-        *enemies = remove_elements_vec(&enemies, enemies_to_remove).await;
+/// Upgrades old level data files (see [crate::utils::versioning])
+pub const LEVEL_DATA_MIGRATIONS: [Migration; 2] = [
+    level_data_v0_to_v1,
+    level_data_v1_to_v2,
+];
+
+/// Version 0 had no version field and only the scores, so the unlocks get created from them <br>
+/// Every level up to the highest played one stays playable and the level after a completed one gets unlocked
+fn level_data_v0_to_v1(value: &mut Value) {
+    // Every played level and whether it got completed
+    let played: Vec<(Level, bool)> = value["scores"].as_object()
+        .map(|scores| scores.iter()
+            .filter_map(|(level, scores)| {
+                let scores = scores.as_array().filter(|scores| !scores.is_empty())?;
+                let level = serde_json::from_value::<Level>(json!(level)).ok()?;
+                Some((level, scores.iter().any(|score| score["completed"] == json!(true))))
+            })
+            .collect())
+        .unwrap_or_default();
+
+    let highest = played.iter().map(|(level, _)| level.level_selector_page()).max();
+    let mut unlocks: BTreeSet<Level> = highest
+        .map(|highest| (0..=highest).filter_map(Level::from_number).collect())
+        .unwrap_or_default();
+    unlocks.extend(played.iter().filter(|(_, completed)| *completed).filter_map(|(level, _)| level.next()));
+
+    if let Some(persistent_level_data) = value.as_object_mut() {
+        persistent_level_data.entry("unlocks").or_insert(json!(unlocks));
     }
-    { // Tick cannons
-        let cannons = &mut level_scene_data.level_data.cannons;
-        let projectiles = &mut level_scene_data.level_data.projectiles;
+}
 
-        for cannon in cannons {
-            cannon.tick(projectiles).await;
+/// Version 2 moved the scores into the [ScoreLog]
+fn level_data_v1_to_v2(value: &mut Value) {
+    if let Some(persistent_level_data) = value.as_object_mut() {
+        if let Some(scores) = persistent_level_data.remove("scores") {
+            persistent_level_data.insert("legacy_scores".to_string(), scores);
         }
     }
-    { // Tick projectiles
-        let mut level_data = level_scene_data.level_data.clone();
-        let projectiles = &mut level_scene_data.level_data.projectiles;
+}
 
-        let mut projectiles_to_remove = Vec::new();
+impl PersistentLevelData {
+    pub fn new() -> Self {
+        let version = current_version(&LEVEL_DATA_MIGRATIONS);
+        let stats = BTreeMap::new();
+        let unlocks = BTreeSet::new();
 
-        for (i, projectile) in projectiles.iter_mut().enumerate() {
-            projectile.tick(&level_data).await;
+        Self { version, stats, unlocks, scores: ScoreLog::default(), legacy_scores: BTreeMap::new() }
+    }
 
-            if projectile.deletable {
-                projectiles_to_remove.push(i);
-            }
+    /// Loads the level data from the provided path or creates it if it doesn't exist <br>
+    /// Old files get migrated, a file that cannot be read at all gets replaced by its backup or moved to `level_data.<i>.json` <br>
+    /// The [ScoreLog] is stored in the same directory
+    pub async fn load(path: &str) -> Self {
+        let mut persistent_level_data = match fs::exists(path).unwrap() {
+            true => load_save_file(path, "file.level_data", &LEVEL_DATA_MIGRATIONS).unwrap_or_else(PersistentLevelData::new),
+            false => PersistentLevelData::new(),
+        };
+
+        let dir = Path::new(path).parent().and_then(|dir| dir.to_str()).unwrap_or(".");
+        persistent_level_data.scores = ScoreLog::new(dir);
+
+        if !persistent_level_data.legacy_scores.is_empty() {
+            persistent_level_data.import_legacy_scores();
+            save_file(path, "file.level_data", &persistent_level_data, true);
         }
 
-        level_data.projectiles = remove_elements_vec(&projectiles, projectiles_to_remove).await;
-        level_scene_data.level_data = level_data;
+        persistent_level_data
     }
-    { // Tick power ups
-        let player = level_scene_data.level_data.player.as_mut().unwrap();
-        let power_ups = &mut level_scene_data.level_data.power_ups;
 
-        let mut power_ups_to_remove = Vec::new();
+    /// Moves the scores of version 1 files into the [ScoreLog] <br>
+    /// Does nothing if the log already exists (the scores got imported before, but the level data couldn't be saved afterward)
+    fn import_legacy_scores(&mut self) {
+        let legacy_scores = std::mem::take(&mut self.legacy_scores);
+        if self.scores.exists() { return; }
+
+        let mut stopwatch = Stopwatch::default();
+        stopwatch.start();
+
+        for (level, scores) in legacy_scores {
+            for score in &scores {
+                if let Err(e) = self.scores.append(&level, score) {
+                    println!("Couldn't import score with error \"{}\"", e);
+                }
+            }
 
-        for (i, power_up) in power_ups.iter_mut().enumerate() {
-            power_up.tick(player).await;
-            if power_up.collected {
-                power_ups_to_remove.push(i);
+            if let Some(best) = scores.iter().find(|score| score.completed && !score.replay.is_empty()) {
+                if let Err(e) = self.scores.save_best_run(&level, best.playtime, &best.replay) {
+                    println!("Couldn't import best run with error \"{}\"", e);
+                }
             }
         }
 
-        *power_ups = remove_elements_vec(&power_ups, power_ups_to_remove).await;
+        stopwatch.stop();
+        println!("Imported old scores into the score log! Took {}ms", stopwatch.elapsed().as_millis());
     }
-}
-
-#[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
-/// Contains ALL data that may be saved across multiple sessions of levels
-pub struct PersistentLevelData {
-    pub stats: BTreeMap<Level, LevelStat>,
-    pub scores: BTreeMap<Level, Vec<LevelScore>>
-}
 
-impl PersistentLevelData {
-    pub fn new() -> Self {
-        let stats = BTreeMap::new();
-        let scores= BTreeMap::new();
+    /// The tutorial and the first level are always unlocked
+    pub fn is_unlocked(&self, level: &Level) -> bool {
+        level.level_selector_page() <= 1 || self.unlocks.contains(level)
+    }
 
-        Self { stats, scores }
+    /// Unlocks the level after the provided one
+    pub fn unlock_after(&mut self, level: &Level) {
+        if let Some(next) = level.next() {
+            self.unlocks.insert(next);
+        }
     }
 
+    /// Writes the level data to its file (errors are shown to the player)
     pub async fn save(&self, settings: &Settings) {
-        let mut file = fs::File::create(&settings.level_data_path).unwrap();
-
-        let s_persistent_level_data = serde_json::to_string_pretty(&self).expect("Couldn't serialize level data");
-        file.write_all(s_persistent_level_data.as_bytes()).expect("Couldn't write level data file");
+        save_file(&settings.level_data_path, "file.level_data", self, true);
     }
 }
 
@@ -240,13 +262,20 @@ pub struct LevelScore {
     /// The total mount of damage that the player has done
     pub total_damage: u32,
     /// The total amount of damage that the player received
-    pub total_damage_received: u32
+    pub total_damage_received: u32,
+    /// Whether the level was completed in this run
+    #[serde(default)]
+    pub completed: bool,
+    /// The recorded path of the player <br>
+    /// Only read from old level data files, the [ScoreLog] stores the replay of the best run on its own
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replay: Vec<ReplayFrame>,
 }
 
 impl LevelScore {
-    pub fn new(playtime: f64, coins: u32, kills: u32, total_damage: u32, total_damage_received: u32) -> LevelScore {
+    pub fn new(playtime: f64, coins: u32, kills: u32, total_damage: u32, total_damage_received: u32, completed: bool) -> LevelScore {
         let time = SystemTime::now();
-        Self { time, playtime, coins, kills, total_damage, total_damage_received }
+        Self { time, playtime, coins, kills, total_damage, total_damage_received, completed, replay: Vec::new() }
     }
 }
 
@@ -260,12 +289,11 @@ pub enum Level {
 }
 
 impl Level {
-    pub fn name(&self) -> &'static str {
+    /// Returns the name of the level in the language of the player
+    pub fn name(&self) -> String {
         match self {
-            Level::Level0 => "Tutorial",
-            Level::Level1 => "Level 1",
-            Level::Level2 => "Level 2",
-            Level::Level3 => "Level 3",
+            Level::Level0 => i18n::text("level.tutorial"),
+            _ => i18n::text_with("level.number", &[("number", self.level_selector_page().to_string())]),
         }
     }
 
@@ -286,6 +314,42 @@ impl Level {
         }
     }
 
+    /// Returns the path of the script of the level (see [LevelScript])
+    pub fn script_path(&self) -> String {
+        self.path().replace(".png", ".rhai")
+    }
+
+    /// Returns the path of the physics the level changes (see [PhysicsConfig])
+    pub fn physics_path(&self) -> String {
+        self.path().replace(".png", ".physics.json")
+    }
+
+    /// Returns the level with the provided number (`0` is the tutorial)
+    pub fn from_number(number: usize) -> Option<Self> {
+        match number {
+            0 => Some(Level::Level0),
+            1 => Some(Level::Level1),
+            2 => Some(Level::Level2),
+            3 => Some(Level::Level3),
+            _ => None,
+        }
+    }
+
+    /// Returns the music track of the level
+    pub fn music(&self) -> SoundKey {
+        match self {
+            Level::Level0 => SoundKey::Music0,
+            Level::Level1 => SoundKey::Music1,
+            Level::Level2 => SoundKey::Music2,
+            Level::Level3 => SoundKey::Music3,
+        }
+    }
+
+    /// Returns the level that comes after this one (if there is one)
+    pub fn next(&self) -> Option<Self> {
+        Self::from_number(self.level_selector_page() + 1)
+    }
+
     pub fn level_selector_page(&self) -> usize {
         match self {
             Level::Level0 => 0,
@@ -296,23 +360,6 @@ impl Level {
     }
 }
 
-#[derive(Eq, PartialEq, Clone, Ord, PartialOrd, Debug)]
-pub enum Trigger {
-    // Debugging
-    ShowCameraColliders,
-    ShowColliders,
-    ShowFPS,
-    ShowPlayerPos,
-
-    LevelCompleted,
-    GameOver,
-
-    // Tutorial Trigger
-    TutorialWalking,
-    TutorialSpace,
-    TutorialCoins
-}
-
 #[derive(Clone)]
 pub struct LevelData {
     pub start_time: f64,
@@ -322,80 +369,128 @@ pub struct LevelData {
 
     pub level: Option<Level>,
     pub player: Option<Player>,
-    pub platforms: Vec<Platform>,
-    pub collectibles: Vec<Collectible>,
-    pub enemies: Vec<Enemy>,
-    pub cannons: Vec<Cannon>,
-    pub projectiles: Vec<Projectile>,
-    pub power_ups: Vec<PowerUp>,
-    /// Saves temporary triggers / settings
-    pub triggers: BTreeMap<Trigger, bool>,
-    pub triggers_exec: BTreeMap<Trigger, f64>,
-    pub trigger_locks: BTreeMap<Trigger, bool>
+    /// Platforms, enemies, coins, power ups, cannons and projectiles
+    pub entities: Entities,
+    /// The score and the outcome of the current run, it subscribes to the [LevelData::events]
+    pub tally: Rc<RefCell<Tally>>,
+    /// The debug views that are turned on (see [crate::utils::debugger::check()])
+    pub debug_views: BTreeSet<DebugView>,
+    /// Records the path of the player for ghost replays
+    pub replay: ReplayRecorder,
+    /// The ghost of the best run (if one exists)
+    pub ghost: Option<Ghost>,
+    /// The script of the level (if one exists)
+    pub script: Option<LevelScript>,
+    /// How the player moves in this level
+    pub physics: PhysicsConfig,
+    /// Everything that happened in the current frame
+    pub events: EventQueue,
 }
 
 impl LevelData {
-    pub async fn new(level: Level, player: Player, platforms: Vec<Platform>, collectibles: Vec<Collectible>, enemies: Vec<Enemy>, cannons: Vec<Cannon>, power_ups: Vec<PowerUp>) -> Self {
+    pub async fn new(level: Level, player: Player, entities: Entities) -> Self {
         let start_time = get_time();
         let zero = vec2(0.0, 0.0);
         let level = Some(level);
         let player = Some(player);
-        let projectiles = Vec::new();
-        let triggers = BTreeMap::new();
-        let triggers_exec = BTreeMap::new();
-        let trigger_locks = BTreeMap::new();
+        let mut events = EventQueue::new();
+        let tally = Tally::subscribe(&mut events);
+        let debug_views = BTreeSet::new();
+        let replay = ReplayRecorder::new();
+        let ghost = None;
+        let script = LevelScript::load(level.as_ref().unwrap()).await;
+        if let Some(script) = script.as_ref() {
+            script.subscribe(&mut events);
+        }
+        audio::subscribe(&mut events);
+        let physics = PhysicsConfig::load(level.as_ref().unwrap()).await;
+
+        Self { start_time, zero, level, player, entities, tally, debug_views, replay, ghost, script, physics, events }
+    }
+
+    /// Returns the time the player spent in the level <br>
+    /// The time stops as soon as the level is completed or the game is over
+    pub async fn playtime(&self) -> f64 {
+        let end_time = self.tally.borrow().outcome.map_or(get_time(), |(_, time)| time);
+
+        end_time - self.start_time
+    }
+
+    /// Ends the level as completed (only the first call counts) <br>
+    /// The level ends when the [Tally] reads the event
+    pub async fn complete(&mut self) {
+        if !self.ending() {
+            self.events.push(GameEvent::LevelCompleted);
+        }
+    }
+
+    /// Ends the level as lost (only the first call counts) <br>
+    /// The level ends when the [Tally] reads the event
+    pub async fn game_over(&mut self) {
+        if !self.ending() {
+            self.events.push(GameEvent::GameOver);
+        }
+    }
 
-        Self { start_time, zero, level, player, platforms, collectibles, enemies, cannons, projectiles, power_ups, triggers, triggers_exec, trigger_locks  }
+    /// Returns true if the level is over or will be over after the events of this frame got read
+    fn ending(&self) -> bool {
+        self.tally.borrow().is_over() || self.events.iter().any(|event| matches!(event, GameEvent::LevelCompleted | GameEvent::GameOver))
+    }
+
+    /// Returns the score of the current run
+    pub async fn score(&self) -> LevelScore {
+        let playtime = self.playtime().await;
+        let tally = self.tally.borrow();
+        LevelScore::new(
+            playtime,
+            tally.coins,
+            tally.kills,
+            tally.total_damage,
+            tally.total_damage_received,
+            tally.completed()
+        )
+    }
+
+    /// Returns the amount of coins the level has (collected or not)
+    pub fn total_coins(&self) -> u32 {
+        self.tally.borrow().coins + self.entities.coins() as u32
     }
 
     pub async fn save(&self, persistent_level_data: &mut PersistentLevelData, settings: &Settings) {
         let mut stopwatch = Stopwatch::default();
         print!("Saving level score and updating stats... ");
         stopwatch.start();
-        let playtime = get_time() - self.start_time;
-        let player = self.player.as_ref().unwrap();
         let level = self.level.as_ref().unwrap();
+        let score = self.score().await;
+        let playtime = score.playtime;
+        let completed = score.completed;
 
-        let score = LevelScore::new(
-            playtime,
-            player.coins,
-            player.kills,
-            player.total_damage,
-            player.total_damage_received
-        );
-
-        if persistent_level_data.scores.get(level).is_none() {
-            persistent_level_data.scores.insert(level.to_owned(), Vec::new());
+        let stats_ref = persistent_level_data.stats.entry(level.to_owned()).or_insert_with(|| LevelStat::new(level.to_owned()));
+        // Lost lives count as deaths too
+        stats_ref.update(self.tally.borrow().deaths, &score);
+
+        let scores = &mut persistent_level_data.scores;
+
+        // Keep the replay if this is the new best run
+        if completed && scores.best_time(level).is_none_or(|best| playtime < best) {
+            if let Err(e) = scores.save_best_run(level, playtime, &self.replay.frames) {
+                println!("Couldn't save best run with error \"{}\"", e);
+                notice::push(i18n::text_with("notice.best_run_not_saved", &[("error", e.to_string())]));
+            }
         }
 
-        if persistent_level_data.stats.get(level).is_none() {
-            persistent_level_data.stats.insert(level.to_owned(), LevelStat::new(level.to_owned()));
+        if let Err(e) = scores.append(level, &score) {
+            println!("Couldn't save level score with error \"{}\"", e);
+            notice::push(i18n::text_with("notice.score_not_saved", &[("error", e.to_string())]));
         }
-        let stats_ref = persistent_level_data.stats.get_mut(level).unwrap();
-        let deaths = {
-            if *self.triggers.get(&Trigger::GameOver).unwrap_or(&false) {
-                1
-            } else {
-                0
-            }
-        };
-        stats_ref.update(deaths, &score);
 
-        let score_space = persistent_level_data.scores.get_mut(level).unwrap();
-        // 10000 entries are about 3 MB large, and we don't want to go larger than that
-        if score_space.len() > 10000 { score_space.remove(0); }
-        score_space.push(score);
+        if completed { persistent_level_data.unlock_after(level); }
 
         persistent_level_data.save(settings).await;
 
         stopwatch.stop();
         println!("Took {}ms", stopwatch.elapsed().as_millis());
     }
-
-    pub async fn insert_trigger(&mut self, trigger: Trigger, value: bool) {
-        self.triggers.insert(trigger.to_owned(), value);
-        self.triggers_exec.insert(trigger, get_time());
-    }
 }
 
 /// Holds all data a level can possibly have
@@ -410,6 +505,7 @@ impl LevelSceneData {
     }
 
     pub async fn empty() -> Self {
+        let mut events = EventQueue::new();
         let level_data = LevelData {
             start_time: 0.0,
 
@@ -417,15 +513,14 @@ impl LevelSceneData {
 
             level: None,
             player: None,
-            platforms: Vec::new(),
-            collectibles: Vec::new(),
-            enemies: Vec::new(),
-            cannons: Vec::new(),
-            projectiles: Vec::new(),
-            power_ups: Vec::new(),
-            triggers: BTreeMap::new(),
-            triggers_exec: BTreeMap::new(),
-            trigger_locks: BTreeMap::new()
+            entities: Entities::new(),
+            tally: Tally::subscribe(&mut events),
+            debug_views: BTreeSet::new(),
+            replay: ReplayRecorder::new(),
+            ghost: None,
+            script: None,
+            physics: PhysicsConfig::default(),
+            events,
         };
 
         Self {
@@ -434,10 +529,52 @@ impl LevelSceneData {
         }
     }
 
+    /// Loads the ghost of the best run of the current level
+    pub async fn load_ghost(&mut self, persistent_level_data: &PersistentLevelData) {
+        let level = self.level_data.level.as_ref().unwrap();
+        self.level_data.ghost = Ghost::best(level, persistent_level_data).await;
+    }
+
     pub async fn escape(&mut self, persistent_level_data: &mut PersistentLevelData, settings: &Settings, scene: &mut Scene) {
+        runtime::resume();
         self.level_data.save(persistent_level_data, settings).await;
         *scene = LevelSelector(self.level_data.level.as_ref().unwrap().level_selector_page());
         *self = Self::empty().await;
         set_default_camera()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn old_level_data_unlocks_every_level_up_to_the_highest_played_one() {
+        let mut value = json!({
+            "scores": {
+                "Level0": [{ "completed": true }],
+                "Level2": [{ "completed": false }],
+            }
+        });
+        level_data_v0_to_v1(&mut value);
+
+        let unlocks: BTreeSet<Level> = serde_json::from_value(value["unlocks"].clone()).unwrap();
+        assert_eq!(unlocks, BTreeSet::from([Level::Level0, Level::Level1, Level::Level2]));
+    }
+
+    #[test]
+    fn old_level_data_unlocks_the_level_after_a_completed_one() {
+        let mut value = json!({ "scores": { "Level2": [{ "completed": true }] } });
+        level_data_v0_to_v1(&mut value);
+
+        let unlocks: BTreeSet<Level> = serde_json::from_value(value["unlocks"].clone()).unwrap();
+        assert!(unlocks.contains(&Level::Level3));
+    }
+
+    #[test]
+    fn old_level_data_without_scores_unlocks_nothing() {
+        let mut value = json!({ "scores": {} });
+        level_data_v0_to_v1(&mut value);
+
+        assert_eq!(value["unlocks"], json!([]));
+    }
+}
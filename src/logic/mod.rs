@@ -3,6 +3,14 @@ pub mod collider;
 pub mod enemy;
 pub mod level;
 pub mod platform;
-pub mod collectible;
-pub mod projectile;
-pub mod cannon;
\ No newline at end of file
+pub mod ghost;
+pub mod input;
+pub mod script;
+pub mod event;
+pub mod tally;
+pub mod timer;
+pub mod ecs;
+pub mod snapshot;pub mod score_log;
+pub mod power_up;
+pub mod physics;
+pub mod weapon;
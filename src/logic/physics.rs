@@ -0,0 +1,123 @@
+use std::fs;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::logic::level::Level;
+#[cfg(target_os = "macos")]
+use crate::utils::texture::get_resources_path;
+
+// How the player moves. All speeds are in pixels per second at a GUI scale of 1, all times in seconds.
+// `res/physics.json` contains the values for all levels. A level can change some of them with
+// `res/levels/level_<number>.physics.json`, values that are missing in both files use [PhysicsConfig::default()].
+
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct PhysicsConfig {
+    /// Gets added to the falling speed every second
+    pub gravity: f32,
+    /// The highest falling speed
+    pub terminal_velocity: f32,
+    /// How long the player can still jump after leaving the ground
+    pub coyote_time: f64,
+    /// A jump that was pressed this long before landing still happens
+    pub jump_buffer: f64,
+    /// The upwards speed gets multiplied with this if jump is released while rising (`1.0` turns it off)
+    pub jump_cut: f32,
+    /// The horizontal speed during a dash
+    pub dash_speed: f32,
+    pub dash_duration: f64,
+    /// The time between the start of two dashes
+    pub dash_cooldown: f64,
+    /// The highest falling speed while sliding down a wall
+    pub wall_slide_speed: f32,
+    /// The speed a wall jump pushes the player away from the wall
+    pub wall_jump_speed: f32,
+    /// How long the player can't move back to the wall after a wall jump
+    pub wall_jump_time: f64,
+    /// The abilities the player has in the level without power ups
+    pub abilities: Abilities,
+}
+
+/// Movement abilities that have to be unlocked by the level (see [PhysicsConfig::abilities]) or by power ups
+#[derive(PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Abilities {
+    /// A short and fast horizontal move
+    pub dash: bool,
+    /// Falling slower while moving against a wall
+    pub wall_slide: bool,
+    /// Jumping off a wall while in the air
+    pub wall_jump: bool,
+}
+
+impl Abilities {
+    /// Returns the abilities that are unlocked in at least one of both
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            dash: self.dash || other.dash,
+            wall_slide: self.wall_slide || other.wall_slide,
+            wall_jump: self.wall_jump || other.wall_jump,
+        }
+    }
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            gravity: 4800.0,
+            terminal_velocity: 4000.0,
+            coyote_time: 0.1,
+            jump_buffer: 0.12,
+            jump_cut: 0.5,
+            dash_speed: 3600.0,
+            dash_duration: 0.15,
+            dash_cooldown: 0.6,
+            wall_slide_speed: 600.0,
+            wall_jump_speed: 1300.0,
+            wall_jump_time: 0.15,
+            abilities: Abilities::default(),
+        }
+    }
+}
+
+/// Returns the path of the physics config of all levels
+pub fn get_physics_path() -> String {
+    #[cfg(target_os = "linux")]
+    let resource_path = "./res";
+    #[cfg(target_os = "windows")]
+    let resource_path = ".\\res";
+    #[cfg(target_os = "macos")]
+    let resource_path = get_resources_path().unwrap().to_str().unwrap().to_string();
+
+    format!("{}/physics.json", resource_path)
+}
+
+impl PhysicsConfig {
+    /// Loads the physics of the level (files that don't exist or can't be read are skipped)
+    pub async fn load(level: &Level) -> Self {
+        let mut value = serde_json::to_value(Self::default()).unwrap();
+
+        for path in [get_physics_path(), level.physics_path()] {
+            if !fs::exists(&path).unwrap_or(false) { continue; }
+
+            let result = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|s_value| serde_json::from_str::<Value>(&s_value).map_err(|e| e.to_string()));
+
+            match result {
+                Ok(Value::Object(overrides)) => {
+                    // Only the values in the file get replaced
+                    for (key, override_value) in overrides {
+                        value[key] = override_value;
+                    }
+                }
+                Ok(_) => println!("Couldn't load physics config {} with error \"not an object\"! Ignoring...", path),
+                Err(e) => println!("Couldn't load physics config {} with error \"{}\"! Ignoring...", path, e),
+            }
+        }
+
+        serde_json::from_value(value).unwrap_or_else(|e| {
+            println!("Invalid physics config with error \"{}\"! Using the default physics...", e);
+            Self::default()
+        })
+    }
+}
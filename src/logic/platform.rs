@@ -1,29 +1,25 @@
 use macroquad::math::{f32, vec2, Vec2};
-use macroquad_platformer::{Solid, World};
 use std::collections::BTreeMap;
 use macroquad::prelude::{draw_texture_ex, DrawTextureParams, Texture2D};
 use macroquad::color::WHITE;
-use crate::logic::collider::Collider;
 use crate::utils::enums::TextureKey;
 
+/// Draws the tiles of a platform entity (see [crate::logic::ecs::Entities::spawn_platform()]) <br>
+/// Platforms are solids in the world and never move
 #[derive(PartialEq, Clone, Debug)]
 pub struct Platform {
-    pub collider: Solid,
-    pub collider_new: Collider,
     pub tile_size: Vec2,
     pub tiles: Vec<PlatformTile>,
-    pub speed: Vec2,
 }
 
 impl Platform {
-    pub async fn new(collider: Solid, pos: Vec2, size: Vec2, tile_size: Vec2, tiles: Vec<PlatformTile>, speed: Vec2) -> Self {
-        let collider_new = Collider::new_solid(pos, size.x, size.y, vec2(0.0, 0.0)).await;
-        Self { collider, collider_new, tile_size, tiles, speed }
+    pub async fn new(tile_size: Vec2, tiles: Vec<PlatformTile>) -> Self {
+        Self { tile_size, tiles }
     }
 
     /// Basic Floating platform
     /// `length` are the tiles between the start and end
-    pub async fn floating(length: i32, tile_size: Vec2, texture_key: TextureKey, pos: Vec2, world: &mut World) -> Self {
+    pub async fn floating(length: i32, tile_size: Vec2, texture_key: TextureKey) -> Self {
         let mut tiles = vec![
             PlatformTile {
                 texture_key,
@@ -46,19 +42,10 @@ impl Platform {
             pos: vec2(length as f32, 0.0),
         });
 
-        let width = tile_size.x * (length + 1) as f32;
-
-        Self::new(
-            world.add_solid(pos, width as i32, tile_size.y as i32),
-            pos,
-            vec2(width, tile_size.y),
-            tile_size,
-            tiles,
-            vec2(0.0, 0.0)
-        ).await
+        Self::new(tile_size, tiles).await
     }
 
-    pub async fn full(length: usize, height: usize, tile_size: Vec2, texture_key: TextureKey, pos: Vec2, world: &mut World) -> Self {
+    pub async fn full(length: usize, height: usize, tile_size: Vec2, texture_key: TextureKey) -> Self {
         let mut tiles = vec![
             // Top left corner
             PlatformTile {
@@ -123,21 +110,17 @@ impl Platform {
             })
         }
 
-        let width = (length as f32 + 2.0) * tile_size.x;
-        let height = (height as f32 + 2.0) * tile_size.y;
-        Self::new(
-            world.add_solid(pos, width as i32, height as i32),
-            pos,
-            vec2(width, height),
-            tile_size,
-            tiles,
-            vec2(0.0, 0.0)
-        ).await
+        Self::new(tile_size, tiles).await
     }
 
-    pub async fn render(&self, textures: &BTreeMap<TextureKey, Vec<Texture2D>>, world: &World) {
-        let pos = world.solid_pos(self.collider);
+    /// Returns the size of all tiles together
+    pub fn size(&self) -> Vec2 {
+        let tiles = self.tiles.iter().fold(vec2(0.0, 0.0), |size, tile| size.max(tile.pos + vec2(1.0, 1.0)));
+        tiles * self.tile_size
+    }
 
+    /// `pos` is the top left corner of the platform
+    pub async fn render(&self, pos: Vec2, textures: &BTreeMap<TextureKey, Vec<Texture2D>>) {
         for tile in &self.tiles {
             let texture = textures.get(&tile.texture_key).unwrap().get(tile.texture_index).unwrap();
             let pos =  pos + self.tile_size * tile.pos;
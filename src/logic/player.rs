@@ -1,20 +1,25 @@
 use std::collections::BTreeMap;
-use macroquad::camera::set_camera;
-use macroquad::color::{Color, GREEN, RED, WHITE};
-use macroquad::input::{is_key_down, is_key_pressed, is_mouse_button_pressed, mouse_position, KeyCode, MouseButton};
+use macroquad::color::{Color, GOLD, GREEN, RED, SKYBLUE, WHITE};
 use macroquad::math::{vec2, Vec2};
-use macroquad::prelude::{draw_texture_ex, get_frame_time, screen_height, Camera2D, DrawTextureParams, Rect, Texture2D};
-use macroquad::shapes::draw_rectangle;
-use macroquad::text::{draw_text, measure_text};
-use macroquad::time::get_time;
-use macroquad::window::screen_width;
+use macroquad::prelude::{draw_texture_ex, Camera2D, DrawTextureParams, Rect, Texture2D};
+use macroquad::shapes::{draw_circle_lines, draw_rectangle};
 use macroquad_platformer::{Actor, World};
+use serde::{Deserialize, Serialize};
 use crate::logic::collider::Collider;
-use crate::logic::level::{LevelData, Trigger};
-use crate::logic::projectile::{Projectile, ProjectileOrigin};
+use crate::logic::input::PlayerInput;
+use crate::logic::event::{EventQueue, GameEvent, LevelTimer};
+use crate::logic::level::LevelData;
+use crate::logic::power_up::Modifiers;
+use crate::logic::tally::Tally;
+use crate::logic::timer::Timers;
+use crate::logic::weapon::WeaponKind;
+use crate::logic::ecs::components::{ContactDamage, ProjectileOrigin};
+use crate::logic::ecs::prefabs::ProjectileSpawn;
 use crate::utils::structs::Settings;
 use crate::utils::enums::{Animation, AnimationType, Direction, TextureKey};
 use crate::utils::mathemann::{point_to_point_direction_with_speed, stretch_float_to};
+use crate::utils::runtime::{get_frame_time, screen_height, screen_width, set_camera};
+use crate::utils::text::{draw_str, measure_str};
 
 // This file contains everything that is for the player
 #[derive(PartialEq, Clone, Debug)]
@@ -30,7 +35,11 @@ pub struct PlayerUIElement {
 #[derive(PartialEq, Eq, Clone, Ord, PartialOrd, Copy, Debug)]
 pub enum PlayerUIElementType {
     Coins,
-    Kills
+    Kills,
+    /// Only exists in the lives mode
+    Lives,
+    /// The selected weapon and its ammo
+    Weapon,
 }
 
 impl PlayerUIElement {
@@ -49,7 +58,7 @@ impl PlayerUIElement {
         self.pos = pos;
     }
 
-    pub async fn render(&mut self, textures: &BTreeMap<TextureKey, Vec<Texture2D>>, value: &String) {
+    pub async fn render(&mut self, textures: &BTreeMap<TextureKey, Vec<Texture2D>>, value: &str) {
         match self.animation.animation_type {
             AnimationType::Cycle(_, _, _) => {
                 self.animation.animate().await;
@@ -67,9 +76,9 @@ impl PlayerUIElement {
             }
         }
 
-        let text_d = measure_text(value, None, self.font_size as _, 1.0);
+        let text_d = measure_str(value, self.font_size);
         let pos = vec2(self.pos.x + self.texture_size.x + self.texture_size.x / 4.0, self.pos.y + text_d.height * 1.5);
-        draw_text(value, pos.x, pos.y, self.font_size, WHITE);
+        draw_str(value, pos.x, pos.y, self.font_size, WHITE);
     }
 
 }
@@ -78,34 +87,49 @@ impl PlayerUIElement {
 pub struct Player {
     pub pos: Vec2,
     pub health: i16,
-    /// The total amount of kills
-    pub kills: u32,
-    /// The total amount of collected coins
-    pub coins: u32,
-    /// The total mount of damage that the player has done
-    pub total_damage: u32,
-    /// The total amount of damage that the player received
-    pub total_damage_received: u32,
+    /// Healing can't go above this (see [Player::heal()])
+    pub max_health: i16,
+    /// The remaining lives (including the current one), `None` if the lives mode is off
+    pub lives: Option<u32>,
+    /// Where the player respawns after losing a life
+    pub spawn: Vec2,
     pub ui_elements: BTreeMap<PlayerUIElementType, PlayerUIElement>,
     pub color: Color,
     pub width: f32,
     pub height: f32,
     /// 0: Left <br>
     /// 1: Right <br>
-    /// 2: Straight
+    /// 2: Straight <br>
+    /// 3: Dashing left <br>
+    /// 4: Dashing right <br>
+    /// 5: Sliding down a wall on the left <br>
+    /// 6: Sliding down a wall on the right <br>
+    /// The state is also the index of the texture
     pub state: i8,
+    /// The direction the player looks at (`-1.0` left, `1.0` right), dashes go this way
+    pub facing: f32,
     pub collider: Actor,
     pub collider_new: Collider,
     pub camera_collider: [Actor; 4],
     pub speed: Vec2,
-    /// All triggers and if a Trigger is active or not
-    pub triggers: BTreeMap<PlayerTrigger, bool>,
-    /// Contains the last time a trigger was executed
-    pub triggers_exec: BTreeMap<PlayerTrigger, f64>,
+    /// `true` if the player is allowed to jump
+    pub on_ground: bool,
+    /// The amount of jumps in the air since the player left the ground (see [Modifiers::air_jumps])
+    pub air_jumps: u32,
+    /// Whether jump was pressed in the last frame (jumping in the air needs a new press)
+    pub jump_held: bool,
+    /// `true` while the player rises from a jump that can still be cut short (see [crate::logic::physics::PhysicsConfig::jump_cut])
+    pub jumping: bool,
+    /// All cooldowns and overlays of the player
+    pub timers: Timers<PlayerTrigger>,
+    /// The weapons the player carries and their remaining ammo (`None` if it never runs out)
+    pub weapons: BTreeMap<WeaponKind, Option<u32>>,
+    /// The weapon that gets fired
+    pub weapon: WeaponKind,
     /// All power ups and its duration
     pub power_ups: BTreeMap<PlayerPowerUp, CollectedPowerUp>,
-    /// Contains the time the power up was there
-    pub power_ups_exec: BTreeMap<PlayerPowerUp, f64>,
+    /// Contains the remaining time of every power up
+    pub power_up_timers: Timers<PlayerPowerUp>,
 }
 
 #[derive(PartialEq, Eq, Clone, Ord, PartialOrd, Copy, Debug)]
@@ -113,19 +137,44 @@ pub enum PlayerTrigger {
     DamageOverlay,
     DamageCooldown,
     ShootTimeout,
-    OnGround
+    /// Runs while the player is on the ground and for the coyote time after leaving it (see [crate::logic::physics::PhysicsConfig::coyote_time])
+    OnGround,
+    /// Runs after jump was pressed (see [crate::logic::physics::PhysicsConfig::jump_buffer])
+    JumpBuffer,
+    Dash,
+    DashCooldown,
+    /// Runs while a wall jump pushes the player away from the wall
+    WallJump,
 }
 
-#[derive(PartialEq, Eq, Clone, Ord, PartialOrd, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Ord, PartialOrd, Copy, Serialize, Deserialize, Debug)]
+/// What every power up does is defined in [crate::logic::power_up]
 pub enum PlayerPowerUp {
     JumpBoost,
     SpeedBoost,
     Coins2x,
     Damage2x,
+    /// Absorbs one hit
+    Shield,
+    Invincibility,
+    DoubleJump,
+    /// Pulls coins to the player
+    Magnet,
+    RapidFire,
+    Dash,
+    /// Unlocks wall sliding and wall jumping
+    WallJump,
 }
 
+/// The health the player starts with (if the level doesn't change [Player::max_health])
+pub const DEFAULT_MAX_HEALTH: i16 = 1000;
+
+/// The player gets an extra life for every this many coins (in the lives mode)
+pub const COINS_PER_LIFE: u32 = 100;
+
 impl Player {
-    pub async fn new(width: f32, height: f32, pos: Vec2, state: i8, world: &mut World) -> Self {
+    /// `lives` is the amount of lives the player starts with (see [Settings::lives])
+    pub async fn new(width: f32, height: f32, pos: Vec2, state: i8, lives: Option<u32>, world: &mut World) -> Self {
         let mut ui_elements = BTreeMap::new();
         // Coin counter
         ui_elements.insert(PlayerUIElementType::Coins, PlayerUIElement::new(
@@ -149,18 +198,42 @@ impl Player {
             height / 2.0
         ));
 
+        // Weapon and ammo (the texture index gets set to the selected weapon)
+        ui_elements.insert(PlayerUIElementType::Weapon, PlayerUIElement::new(
+            PlayerUIElementType::Weapon,
+            TextureKey::Weapons0,
+            vec2(width, height) / 2.0,
+            Animation::new(
+                AnimationType::Cycle(0, 0, 0.1)
+            ),
+            height / 2.0
+        ));
+
+        // Lives counter
+        if lives.is_some() {
+            ui_elements.insert(PlayerUIElementType::Lives, PlayerUIElement::new(
+                PlayerUIElementType::Lives,
+                TextureKey::Player,
+                vec2(width, height) / 2.0,
+                Animation::new(
+                    AnimationType::Cycle(2, 2, 0.1)
+                ),
+                height / 2.0
+            ));
+        }
+
         Player {
             pos,
-            health: 1000,
-            kills: 0,
-            coins: 0,
-            total_damage: 0,
-            total_damage_received: 0,
+            health: DEFAULT_MAX_HEALTH,
+            max_health: DEFAULT_MAX_HEALTH,
+            lives,
+            spawn: pos,
             ui_elements,
             color: WHITE,
             width,
             height,
             state,
+            facing: if state == 0 { -1.0 } else { 1.0 },
             collider: world.add_actor(pos, width as i32, height as i32),
             collider_new: Collider::new_actor(pos, width, height, vec2(0.0, 0.0)).await,
             camera_collider: [
@@ -174,58 +247,89 @@ impl Player {
                 world.add_actor(vec2(0.0,  screen_height() - screen_height() / 8.0), screen_width() as i32, (screen_height() / 8.0) as i32),
             ],
             speed: vec2(0.0, 0.0),
-            triggers: BTreeMap::new(),
-            triggers_exec: BTreeMap::new(),
+            on_ground: true,
+            air_jumps: 0,
+            jump_held: false,
+            jumping: false,
+            timers: Timers::new(),
+            weapons: BTreeMap::from([(WeaponKind::Blaster, None)]),
+            weapon: WeaponKind::Blaster,
             power_ups: BTreeMap::new(),
-            power_ups_exec: BTreeMap::new(),
+            power_up_timers: Timers::new(),
         }
     }
 
     /// This function handles everything regarding the controls of the player (including moving)
-    pub async fn control(&mut self, world: &mut World, level_data: &mut LevelData, settings: &Settings) {
+    pub async fn control(&mut self, world: &mut World, level_data: &mut LevelData, input: &PlayerInput, settings: &Settings) {
         // gets the current position of the player from the world
         let pos = world.actor_pos(self.collider);
         // Checks if the player is on another collider by checking if one collider is 1px beyond him
         let on_ground = world.collide_check(self.collider, pos + vec2(0.0, 1.0));
         // Checks if the player is hitting a sealing by checking if one collider is 1px above him
         let sealing_hit = world.collide_check(self.collider, pos + vec2(0.0, -1.0));
+        // Checks if the player is next to a wall the same way
+        let wall_left = world.collide_check(self.collider, pos + vec2(-1.0, 0.0));
+        let wall_right = world.collide_check(self.collider, pos + vec2(1.0, 0.0));
 
         // If the player is hitting the sealing reset the velocity to 0
         if sealing_hit {
             self.speed.y = (100.0 * settings.gui_scale) * get_frame_time(); // I have no idea why but if this doesn't get multiplied by the frame time it's inconsistent on different Frame Rates
         }
 
-        // If the player is not on the ground change velocity of y to 500 (to simulate gravity)
+        let physics = level_data.physics;
+
+        // If the player is not on the ground increase the falling speed (to simulate gravity)
         if !on_ground {      // multiplies by get_frame_time() so the speed is on all refresh rates the same
-            self.speed.y += (4800.0 * settings.gui_scale) * get_frame_time();
-            if self.triggers_exec.get(&PlayerTrigger::OnGround).unwrap_or(&0.0) + 0.3 < get_time() && *self.triggers.get(&PlayerTrigger::OnGround).unwrap_or(&true) {
-                self.triggers.insert(PlayerTrigger::OnGround, false);
+            self.speed.y = (self.speed.y + physics.gravity * settings.gui_scale * get_frame_time()).min(physics.terminal_velocity * settings.gui_scale);
+            // Coyote time: the player can still jump shortly after walking off a platform
+            if !self.timers.is_active(&PlayerTrigger::OnGround) && self.on_ground {
+                self.on_ground = false;
             }
         } else {
-            if !self.triggers.get(&PlayerTrigger::OnGround).unwrap_or(&false) {
-                self.triggers.insert(PlayerTrigger::OnGround, true);
-                self.triggers_exec.insert(PlayerTrigger::OnGround, get_time());
-            }
+            self.on_ground = true;
+            self.timers.start(PlayerTrigger::OnGround, physics.coyote_time);
             self.speed.y = 0.0;
+            self.air_jumps = 0;
         }
 
         let mut direction = 0;
 
-        let movement_speed = {
-            if self.power_ups.contains_key(&PlayerPowerUp::SpeedBoost) {
-                2000.0 * settings.gui_scale
-            } else {
-                1300.0 * settings.gui_scale
+        let modifiers = self.modifiers();
+        let movement_speed = modifiers.movement_speed * settings.gui_scale;
+        let abilities = modifiers.abilities.union(physics.abilities);
+
+        // Dashes and wall jumps take over the horizontal movement for a moment
+        let locked = self.timers.is_active(&PlayerTrigger::Dash) || self.timers.is_active(&PlayerTrigger::WallJump);
+        if !locked {
+            if input.right {
+                self.facing = 1.0;
+            } else if input.left {
+                self.facing = -1.0;
             }
-        };
+        }
 
-        // Checks if key is currently pressed
-        if is_key_down(KeyCode::D) || is_key_down(KeyCode::Right) {
+        if input.dash && abilities.dash && !locked && !self.timers.is_active(&PlayerTrigger::DashCooldown) {
+            self.timers.start(PlayerTrigger::Dash, physics.dash_duration);
+            self.timers.start(PlayerTrigger::DashCooldown, physics.dash_cooldown);
+            level_data.events.push(GameEvent::PlayerDashed);
+        }
+        let dashing = self.timers.is_active(&PlayerTrigger::Dash);
+
+        if dashing {
+            // Dashes ignore gravity
+            self.speed.x = self.facing * physics.dash_speed * settings.gui_scale;
+            self.speed.y = 0.0;
+            direction = if self.facing > 0.0 { 2 } else { 1 };
+        } else if self.timers.is_active(&PlayerTrigger::WallJump) {
+            self.speed.x = self.facing * physics.wall_jump_speed * settings.gui_scale;
+            self.state = if self.facing > 0.0 { 1 } else { 0 };
+            direction = if self.facing > 0.0 { 2 } else { 1 };
+        } else if input.right {
             // If D or Right Arrow is pressed the Player will be moved to the right by increasing the speed on the x-axis
             self.speed.x = movement_speed;
             self.state = 1;
             direction = 2;
-        } else if is_key_down(KeyCode::A) || is_key_down(KeyCode::Left) {
+        } else if input.left {
             self.speed.x = -movement_speed;
             self.state = 0;
             direction = 1;
@@ -235,20 +339,57 @@ impl Player {
             self.state = 2;
         }
 
-        let on_ground = self.triggers.get(&PlayerTrigger::OnGround).unwrap_or(&true);
-        if is_key_down(KeyCode::Space) {
-            if *on_ground {
-                self.triggers.insert(PlayerTrigger::OnGround, false);
-                if self.power_ups.contains_key(&PlayerPowerUp::JumpBoost) {
-                    self.speed.y = 2500.0 * -settings.gui_scale;
-                } else {
-                    self.speed.y = 2000.0 * -settings.gui_scale;
-                }
+        // Jump buffer: a jump that gets pressed shortly before landing still happens
+        let jump_pressed = input.jump && !self.jump_held;
+        if jump_pressed {
+            self.timers.start(PlayerTrigger::JumpBuffer, physics.jump_buffer);
+        }
+
+        let wall_jump = jump_pressed && !self.on_ground && abilities.wall_jump && (wall_left || wall_right);
+        let air_jump = jump_pressed && !self.on_ground && !wall_jump && self.air_jumps < modifiers.air_jumps;
+        if ((input.jump || self.timers.is_active(&PlayerTrigger::JumpBuffer)) && self.on_ground) || wall_jump || air_jump {
+            if air_jump { self.air_jumps += 1; }
+            if wall_jump {
+                // Jump away from the wall
+                self.facing = if wall_left { 1.0 } else { -1.0 };
+                self.speed.x = self.facing * physics.wall_jump_speed * settings.gui_scale;
+                self.state = if self.facing > 0.0 { 1 } else { 0 };
+                self.timers.stop(&PlayerTrigger::Dash);
+                self.timers.start(PlayerTrigger::WallJump, physics.wall_jump_time);
             }
+            self.on_ground = false;
+            self.jumping = true;
+            self.timers.stop(&PlayerTrigger::JumpBuffer);
+            level_data.events.push(GameEvent::PlayerJumped);
+            self.speed.y = modifiers.jump_speed * -settings.gui_scale;
+        }
+
+        // Variable jump height: releasing jump while rising makes the jump shorter
+        if self.jumping && !input.jump && self.speed.y < 0.0 {
+            self.speed.y *= physics.jump_cut;
+            self.jumping = false;
+        }
+        if self.speed.y >= 0.0 {
+            self.jumping = false;
+        }
+        self.jump_held = input.jump;
+
+        // Wall slide: moving against a wall while falling makes the player fall slower
+        let wall_slide = abilities.wall_slide && !on_ground && !dashing && self.speed.y > 0.0
+            && ((wall_left && input.left) || (wall_right && input.right));
+        if wall_slide {
+            self.speed.y = self.speed.y.min(physics.wall_slide_speed * settings.gui_scale);
+            self.state = if wall_left { 5 } else { 6 };
+        } else if self.timers.is_active(&PlayerTrigger::Dash) {
+            self.state = if self.facing > 0.0 { 4 } else { 3 };
         }
 
         self.perform_move(world).await;
-        self.tick(level_data, world, settings).await;
+        self.tick(level_data, world, input, settings).await;
+
+        if self.health == 0 {
+            self.die(world, level_data).await;
+        }
 
         let pos = world.actor_pos(self.collider);
 
@@ -316,117 +457,174 @@ impl Player {
         }
     }
 
-    pub async fn tick(&mut self, level_data: &mut LevelData, world: &World, settings: &Settings) {
+    pub async fn tick(&mut self, level_data: &mut LevelData, world: &World, input: &PlayerInput, settings: &Settings) {
         let zero = vec2(world.actor_pos(self.camera_collider[0]).x, world.actor_pos(self.camera_collider[2]).y);
         level_data.zero = zero;
 
-        let enemies = &level_data.enemies;
-        let colliding_enemies = self.collider_new.collide_check_enemy(enemies, vec2(0.0, 0.0)).await;
-        if !colliding_enemies.is_empty() {
-            for enemy in colliding_enemies {
-                let damage = enemies.get(enemy).expect("Oh no! This shouldn't be impossible!").damage;
-                self.damage(damage).await;
+        let mut damage_received = 0;
+
+        let entities = &level_data.entities;
+        let colliding_enemies = self.collider_new.collide_check_enemy(entities, vec2(0.0, 0.0)).await;
+        for enemy in colliding_enemies {
+            if let Some(ContactDamage(damage)) = entities.contact_damages.get(&enemy) {
+                damage_received += self.damage(*damage).await;
             }
         }
 
-        let projectiles = &level_data.projectiles;
-        let colliding_projectiles = self.collider_new.collide_check_projectile(projectiles, vec2(0.0, 0.0)).await;
+        let colliding_projectiles = self.collider_new.collide_check_projectile(entities, vec2(0.0, 0.0)).await;
         for projectile in colliding_projectiles {
-            let projectile = projectiles.get(projectile).expect("Oh no! This shouldn't be impossible!");
+            let projectile = entities.damages.get(&projectile).expect("Oh no! This shouldn't be impossible!");
             match projectile.origin {
                 ProjectileOrigin::Player => { continue; }
                 ProjectileOrigin::Canon => {
-                    self.damage(projectile.damage).await;
+                    damage_received += self.damage(projectile.amount).await;
                 }
             }
         }
 
-        if *self.triggers.get(&PlayerTrigger::DamageOverlay).unwrap_or(&false) {
+        if damage_received > 0 {
+            level_data.events.push(GameEvent::PlayerDamaged { damage: damage_received });
+        }
+
+        if self.timers.is_active(&PlayerTrigger::DamageOverlay) {
             self.color = RED;
-            if self.triggers_exec.get(&PlayerTrigger::DamageOverlay).unwrap() + 0.25 < get_time() {
-                self.triggers.remove(&PlayerTrigger::DamageOverlay);
-                self.triggers_exec.remove(&PlayerTrigger::DamageOverlay);
+        }
+
+        for trigger in self.timers.expired() {
+            if trigger == PlayerTrigger::DamageOverlay {
                 self.color = WHITE;
             }
         }
 
-        if self.health == 0 {
-            level_data.triggers.insert(Trigger::GameOver, true);
+        if input.next_weapon {
+            self.next_weapon();
         }
 
-        if !self.triggers.get(&PlayerTrigger::ShootTimeout).unwrap_or(&false) {
-            let damage = match self.power_ups.contains_key(&PlayerPowerUp::Damage2x) {
-                true => -350,
-                false => -200,
-            };
-            if is_mouse_button_pressed(MouseButton::Left) {
-                let size = vec2(32.0, 32.0) * settings.gui_scale;
-                let pos = world.actor_pos(self.collider) + vec2(self.width / 2.0, self.height / 2.0) - vec2(size.x / 2.0, size.y / 2.0);
+        if !self.timers.is_active(&PlayerTrigger::ShootTimeout) {
+            let center = world.actor_pos(self.collider) + vec2(self.width / 2.0, self.height / 2.0);
+            if let Some(aim) = input.aim {
                 let pos_c_x = world.actor_pos(self.camera_collider[0]);
                 let pos_c_y = world.actor_pos(self.camera_collider[2]);
-                let (mut mouse_x, mut mouse_y) = mouse_position();
-                mouse_x += pos_c_x.x;
-                mouse_y += pos_c_y.y;
+                let target = aim + vec2(pos_c_x.x, pos_c_y.y);
 
-                let movement_vector = point_to_point_direction_with_speed(pos, vec2(mouse_x, mouse_y), 2000.0 * settings.gui_scale).await;
-
-                let projectile  = Projectile::new(
-                    pos,
-                    size,
-                    damage,
-                    4.0,
-                    TextureKey::Projectile0, ProjectileOrigin::Player, movement_vector).await;
-
-                self.triggers.insert(PlayerTrigger::ShootTimeout, true);
-                self.triggers_exec.insert(PlayerTrigger::ShootTimeout, get_time());
-
-                level_data.projectiles.push(projectile);
-            } else if is_key_pressed(KeyCode::Q) {
-                let size = vec2(32.0, 32.0) * settings.gui_scale;
-                let pos = world.actor_pos(self.collider) + vec2(self.width / 2.0, self.height / 2.0)- vec2(size.x / 2.0, size.y / 2.0);
-
-                let movement_vector = vec2(-1.0, 0.0) * (2000.0 * settings.gui_scale);
+                let direction = point_to_point_direction_with_speed(center, target, 1.0).await;
+                // Aiming at the center of the player has no direction
+                if direction != Vec2::ZERO {
+                    self.shoot(center, direction, level_data, settings).await;
+                }
+            } else if input.shoot_left {
+                self.shoot(center, vec2(-1.0, 0.0), level_data, settings).await;
+            } else if input.shoot_right {
+                self.shoot(center, vec2(1.0, 0.0), level_data, settings).await;
+            }
+        }
 
-                let projectile  = Projectile::new(
-                    pos,
-                    size,
-                    damage,
-                    4.0,
-                    TextureKey::Projectile0, ProjectileOrigin::Player, movement_vector).await;
+        for power_up in self.power_up_timers.expired() {
+            self.power_ups.remove(&power_up);
+            level_data.events.push(GameEvent::TimerExpired { timer: LevelTimer::PowerUp(power_up) });
+        }
+    }
 
-                self.triggers.insert(PlayerTrigger::ShootTimeout, true);
-                self.triggers_exec.insert(PlayerTrigger::ShootTimeout, get_time());
+    /// Fires the selected weapon from `center` in `direction` (a normalized vector) <br>
+    /// Switches back to the [WeaponKind::Blaster] when the weapon runs out of ammo
+    async fn shoot(&mut self, center: Vec2, direction: Vec2, level_data: &mut LevelData, settings: &Settings) {
+        let weapon = self.weapon.weapon();
+        let modifiers = self.modifiers();
+        let size = vec2(weapon.projectile_size, weapon.projectile_size) * settings.gui_scale;
+        let pos = center - size / 2.0;
+        let damage = -((weapon.damage as f32 * modifiers.damage_factor) as i16);
+
+        for i in 0..weapon.count {
+            // The projectiles get spread evenly from one edge of the spread to the other
+            let angle = match weapon.count {
+                1 => 0.0,
+                count => -weapon.spread / 2.0 + weapon.spread * i as f32 / (count - 1) as f32,
+            };
+            let movement_vector = Vec2::from_angle(angle.to_radians()).rotate(direction) * (weapon.speed * settings.gui_scale);
+
+            level_data.entities.spawn_projectile(ProjectileSpawn {
+                pos,
+                size,
+                speed: movement_vector,
+                damage,
+                origin: ProjectileOrigin::Player,
+                max_time: weapon.lifetime,
+                texture_key: weapon.projectile_texture_key,
+                behaviour: weapon.behaviour,
+            }).await;
+        }
 
-                level_data.projectiles.push(projectile);
-            } else if is_key_pressed(KeyCode::E) {
-                let size = vec2(32.0, 32.0) * settings.gui_scale;
-                let pos = world.actor_pos(self.collider) + vec2(self.width / 2.0, self.height / 2.0)- vec2(size.x / 2.0, size.y / 2.0);
+        if let Some(Some(ammo)) = self.weapons.get_mut(&self.weapon) {
+            *ammo -= 1;
+            if *ammo == 0 {
+                self.weapons.remove(&self.weapon);
+                self.weapon = WeaponKind::Blaster;
+            }
+        }
 
-                let movement_vector = vec2(1.0, 0.0) * (2000.0 * settings.gui_scale);
+        self.timers.start(PlayerTrigger::ShootTimeout, modifiers.shoot_timeout_factor / weapon.fire_rate);
+        level_data.events.push(GameEvent::PlayerShot);
+    }
 
-                let projectile  = Projectile::new(
-                    pos,
-                    size,
-                    damage,
-                    4.0,
-                    TextureKey::Projectile0, ProjectileOrigin::Player, movement_vector).await;
+    /// Gives the player the weapon (or more ammo if they already carry it) and selects it
+    pub fn add_weapon(&mut self, weapon: WeaponKind) {
+        let ammo = weapon.weapon().ammo;
+        self.weapons.entry(weapon)
+            .and_modify(|current| *current = current.zip(ammo).map(|(current, ammo)| current + ammo))
+            .or_insert(ammo);
+        self.weapon = weapon;
+    }
 
-                self.triggers.insert(PlayerTrigger::ShootTimeout, true);
-                self.triggers_exec.insert(PlayerTrigger::ShootTimeout, get_time());
+    /// Selects the next weapon the player carries
+    pub fn next_weapon(&mut self) {
+        self.weapon = self.weapons.range(self.weapon..).nth(1)
+            .or(self.weapons.iter().next())
+            .map(|(weapon, _)| *weapon)
+            .unwrap_or_default();
+    }
 
-                level_data.projectiles.push(projectile);
+    /// Costs a life and respawns the player, the game is over if it was the last life (or the lives mode is off)
+    async fn die(&mut self, world: &mut World, level_data: &mut LevelData) {
+        match self.lives {
+            Some(lives) if lives > 1 => {
+                self.lives = Some(lives - 1);
+                self.respawn(world).await;
+                level_data.events.push(GameEvent::LifeLost { lives: lives - 1 });
+            }
+            _ => {
+                self.lives = self.lives.map(|_| 0);
+                level_data.game_over().await;
             }
-        } else if self.triggers_exec.get(&PlayerTrigger::ShootTimeout).unwrap_or(&0.0) + 0.05 < get_time() {
-            self.triggers.remove(&PlayerTrigger::ShootTimeout);
-            self.triggers_exec.remove(&PlayerTrigger::ShootTimeout);
         }
+    }
 
-        for (power_up_key, power_up) in self.power_ups.clone() {
-            let start_time = self.power_ups_exec.get(&power_up_key).unwrap_or(&0.0);
-            if start_time + power_up.duration < get_time()  {
-                self.power_ups.remove(&power_up_key);
-                self.power_ups_exec.remove(&power_up_key);
-            }
+    /// Puts the player back to the spawn with full health and the camera back to the start of the level <br>
+    /// Power ups get lost and the player can't be damaged for a moment
+    async fn respawn(&mut self, world: &mut World) {
+        self.health = self.max_health;
+        self.speed = vec2(0.0, 0.0);
+        self.color = WHITE;
+        self.power_ups.clear();
+        self.power_up_timers = Timers::new();
+        self.timers.start(PlayerTrigger::DamageCooldown, 2.0);
+
+        world.set_actor_position(self.collider, self.spawn);
+        self.collider_new.change_pos(self.spawn).await;
+        self.pos = self.spawn;
+
+        // The same positions as in [Player::new()]
+        world.set_actor_position(self.camera_collider[0], vec2(0.0, 0.0));
+        world.set_actor_position(self.camera_collider[1], vec2(screen_width() - (screen_width() / 4.0), 0.0));
+        world.set_actor_position(self.camera_collider[2], vec2(0.0, 0.0));
+        world.set_actor_position(self.camera_collider[3], vec2(0.0,  screen_height() - screen_height() / 8.0));
+        set_camera(&Camera2D::from_display_rect(Rect::new(0.0, screen_height(), screen_width(), -screen_height())));
+    }
+
+    /// Adds an extra life (does nothing if the lives mode is off)
+    pub fn add_life(&mut self, events: &mut EventQueue) {
+        if let Some(lives) = self.lives.as_mut() {
+            *lives += 1;
+            events.push(GameEvent::LifeGained { lives: *lives });
         }
     }
 
@@ -441,43 +639,73 @@ impl Player {
         self.collider_new.change_pos(pos).await;
     }
 
-    pub async fn damage(&mut self, health: i16) {
-        if self.triggers_exec.get(&PlayerTrigger::DamageCooldown).unwrap_or(&0.0) + 0.5 < get_time() {
-            self.triggers.remove(&PlayerTrigger::DamageCooldown);
-            self.triggers_exec.remove(&PlayerTrigger::DamageCooldown);
-        }
+    /// Returns what the active power ups change about the player
+    pub fn modifiers(&self) -> Modifiers {
+        Modifiers::from_power_ups(self.power_ups.keys())
+    }
+
+    /// Removes a power up before its time runs out
+    pub fn remove_power_up(&mut self, power_up: PlayerPowerUp) {
+        self.power_ups.remove(&power_up);
+        self.power_up_timers.stop(&power_up);
+    }
+
+    /// Damages the player and returns the damage that was received <br>
+    /// `0` during the damage cooldown, while invincible or if the shield absorbed the hit
+    pub async fn damage(&mut self, health: i16) -> u32 {
+        let modifiers = self.modifiers();
+        if modifiers.invincible { return 0; }
+
+        if !self.timers.is_active(&PlayerTrigger::DamageCooldown) {
+            if modifiers.shield {
+                self.remove_power_up(PlayerPowerUp::Shield);
+                self.timers.start(PlayerTrigger::DamageCooldown, 0.5);
+                return 0;
+            }
 
-        if !self.triggers.get(&PlayerTrigger::DamageCooldown).unwrap_or(&false) {
             self.health += health;
-            self.total_damage_received += -health as u32;
 
             if self.health < 0 { self.health = 0; }
 
-            self.triggers.insert(PlayerTrigger::DamageOverlay, true);
-            self.triggers_exec.insert(PlayerTrigger::DamageOverlay, get_time());
-            self.triggers.insert(PlayerTrigger::DamageCooldown, true);
-            self.triggers_exec.insert(PlayerTrigger::DamageCooldown, get_time());
+            self.timers.start(PlayerTrigger::DamageOverlay, 0.25);
+            self.timers.start(PlayerTrigger::DamageCooldown, 0.5);
+
+            return -health as u32;
         }
+
+        0
     }
 
-    pub async fn render(&mut self, world: &World, textures: &BTreeMap<TextureKey, Vec<Texture2D>>, settings: &Settings) {
+    /// Heals the player (up to [Player::max_health]) and returns how much health was gained
+    pub fn heal(&mut self, health: i16) -> u32 {
+        let healed = health.min(self.max_health - self.health).max(0);
+        self.health += healed;
+        healed as u32
+    }
+
+    pub async fn render(&mut self, world: &World, textures: &BTreeMap<TextureKey, Vec<Texture2D>>, tally: &Tally, settings: &Settings) {
         let pos = world.actor_pos(self.collider);
+        let modifiers = self.modifiers();
 
         draw_texture_ex(
-            &textures.get(&TextureKey::Player).unwrap().get(self.state as usize).unwrap(), pos.x, pos.y, self.color,
+            &textures.get(&TextureKey::Player).unwrap().get(self.state as usize).unwrap(), pos.x, pos.y, if modifiers.invincible { GOLD } else { self.color },
             DrawTextureParams {
                 dest_size: Some(vec2(self.width, self.height)),
                 ..Default::default()
             },
         );
 
-        self.render_stats(settings, textures, world).await;
+        if modifiers.shield {
+            draw_circle_lines(pos.x + self.width / 2.0, pos.y + self.height / 2.0, self.width.max(self.height) * 0.75, 4.0 * settings.gui_scale, SKYBLUE);
+        }
+
+        self.render_stats(settings, textures, world, tally).await;
 
         // Draw power ups & remaining time
         let power_up_pos = self.power_up_render_pos(settings, world).await;
         for (power_up_key, (pos, texture_size, font_size, spacing)) in power_up_pos {
             let power_up = self.power_ups.get_mut(&power_up_key).unwrap();
-            let duration = (power_up.duration - (get_time() - self.power_ups_exec.get(&power_up_key).unwrap_or(&0.0))).round();
+            let duration = self.power_up_timers.remaining(&power_up_key).round();
             let time = {
                 let mut result = (0, duration as i32);
                 while result.1 > 59 {
@@ -507,7 +735,7 @@ impl Player {
 
             let text_pos = pos + vec2(spacing, 0.0);
 
-            draw_text(
+            draw_str(
                 format!("{:02}:{:02}", time.0, time.1).as_str(),
                 text_pos.x,
                 text_pos.y + texture_size.y,
@@ -517,13 +745,13 @@ impl Player {
         }
     }
 
-    async fn render_stats(&mut self, settings: &Settings, textures: &BTreeMap<TextureKey, Vec<Texture2D>>, world: &World) {
+    async fn render_stats(&mut self, settings: &Settings, textures: &BTreeMap<TextureKey, Vec<Texture2D>>, world: &World, tally: &Tally) {
         let zero = vec2(world.actor_pos(self.camera_collider[0]).x, world.actor_pos(self.camera_collider[2]).y);
 
         // Draw Health bar
         let health_height = 32.0 * settings.gui_scale;
         draw_rectangle(zero.x, zero.y, screen_width() / 4.0, health_height, RED);
-        let width = stretch_float_to(self.health as f32, 1000.0, screen_width() / 4.0).await;
+        let width = stretch_float_to(self.health as f32, self.max_health as f32, screen_width() / 4.0).await;
         draw_rectangle(zero.x, zero.y, width, health_height, GREEN);
 
         // Draw UI Elements
@@ -533,10 +761,20 @@ impl Player {
             current_height += element.texture_size.y + 8.0 * settings.gui_scale;
             match element_type {
                 PlayerUIElementType::Coins => {
-                    element.render(textures, &self.coins.to_string()).await;
+                    element.render(textures, &tally.coins.to_string()).await;
                 }
                 PlayerUIElementType::Kills => {
-                    element.render(textures, &self.kills.to_string()).await;
+                    element.render(textures, &tally.kills.to_string()).await;
+                }
+                PlayerUIElementType::Lives => {
+                    element.render(textures, &self.lives.unwrap_or(0).to_string()).await;
+                }
+                PlayerUIElementType::Weapon => {
+                    let index = self.weapon.texture_index() as u32;
+                    element.animation.animation_type = AnimationType::Cycle(index, index, 0.1);
+                    element.animation.index = index as i32;
+                    let ammo = self.weapons.get(&self.weapon).copied().flatten();
+                    element.render(textures, &ammo.map(|ammo| ammo.to_string()).unwrap_or_default()).await;
                 }
             }
         }
@@ -548,7 +786,7 @@ impl Player {
     /// `result.3` is the spacing between texture and text
     async fn power_up_render_pos(&mut self, settings: &Settings, world: &World) -> BTreeMap<PlayerPowerUp, (Vec2, Vec2, f32, f32)> {
         let font_size = 64.0 * settings.gui_scale;
-        let text_size = measure_text("00:00", None, font_size as _, 1.0);
+        let text_size = measure_str("00:00", font_size);
         let cp_y = world.actor_pos(self.camera_collider[2]).y;
         let mut current_y = cp_y;
         let mut result = BTreeMap::new();
@@ -563,83 +801,9 @@ impl Player {
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
-pub struct PowerUp {
-    pub collected: bool,
-    pub power_up: PlayerPowerUp,
-    /// Stores the duration of the [PowerUp]
-    pub duration: f64,
-    pub collider: Collider,
-    pub texture_key: TextureKey,
-    pub animation: Animation,
-    pub size: Vec2,
-    pub speed: Vec2,
-}
-
-impl PowerUp {
-    pub async fn new(power_up: PlayerPowerUp, duration: f64, pos: Vec2, size: Vec2, texture_key: TextureKey, texture_range: (u32, u32), texture_speed: f64) -> Self {
-        let collected = false;
-        let collider = Collider::new_collectible(pos, size.x, size.y, vec2(0.0, 0.0)).await;
-        let animation = Animation::new(AnimationType::Cycle(texture_range.0, texture_range.1, texture_speed));
-        let speed = vec2(0.0, 0.0);
-
-        Self {
-            collected,
-            power_up,
-            duration,
-            collider,
-            texture_key,
-            animation,
-            size,
-            speed,
-        }
-    }
-
-    /// Runs all checks that may be needed on an [PowerUp]
-    pub async fn tick(&mut self, player: &mut Player) {
-        // Check if the collectible collides with another thing
-        if self.collider.touching_player(player).await {
-            self.collected = true;
-            player.power_ups.insert(self.power_up.clone(), self.clone().into());
-            player.power_ups_exec.insert(self.power_up.clone(), get_time());
-        }
-    }
-
-    pub async fn render(&mut self, textures: &BTreeMap<TextureKey, Vec<Texture2D>>) {
-        let pos = self.collider.pos().await;
-
-        match self.animation.animation_type {
-            AnimationType::Cycle(_, _, _) => {
-                self.animation.animate().await;
-                let texture = textures.get(&self.texture_key).unwrap().get(self.animation.index as usize).unwrap();
-                draw_texture_ex(
-                    texture,
-                    pos.x,
-                    pos.y,
-                    WHITE,
-                    DrawTextureParams {
-                        dest_size: Some(self.size),
-                        ..Default::default()
-                    },
-                );
-            }
-        }
-    }
-}
-
 #[derive(PartialEq, Clone, Debug)]
 pub struct CollectedPowerUp {
     pub duration: f64,
     pub texture_key: TextureKey,
     pub animation: Animation,
 }
-
-impl From<PowerUp> for CollectedPowerUp {
-    fn from(value: PowerUp) -> Self {
-        Self {
-            duration: value.duration,
-            texture_key: value.texture_key,
-            animation: value.animation,
-        }
-    }
-}
\ No newline at end of file
@@ -0,0 +1,180 @@
+use crate::logic::physics::Abilities;
+use crate::logic::player::PlayerPowerUp;
+use crate::utils::enums::TextureKey;
+
+// Power ups don't change the player directly. Every frame the player starts with the [Modifiers] of a player without
+// power ups and every active power up changes them (see [PlayerPowerUp::modify()]). This way power ups can be combined
+// without knowing each other and the player only has to read the modifiers.
+
+/// The values of the player that power ups can change
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Modifiers {
+    /// In pixels per second (gets multiplied with the GUI scale)
+    pub movement_speed: f32,
+    /// The upwards speed of a jump in pixels per second (gets multiplied with the GUI scale)
+    pub jump_speed: f32,
+    /// How many coins one collected coin is worth
+    pub coin_value: u32,
+    /// The damage of every weapon gets multiplied with this
+    pub damage_factor: f32,
+    /// The time between two shots of every weapon gets multiplied with this
+    pub shoot_timeout_factor: f64,
+    /// How often the player can jump again while in the air
+    pub air_jumps: u32,
+    /// Coins inside of this radius (in tiles) fly to the player
+    pub magnet_radius: f32,
+    /// The next hit gets absorbed (which uses up the shield)
+    pub shield: bool,
+    /// The player can't be damaged
+    pub invincible: bool,
+    /// Gets combined with the abilities of the level
+    pub abilities: Abilities,
+}
+
+impl Default for Modifiers {
+    fn default() -> Self {
+        Self {
+            movement_speed: 1300.0,
+            jump_speed: 2000.0,
+            coin_value: 1,
+            damage_factor: 1.0,
+            shoot_timeout_factor: 1.0,
+            air_jumps: 0,
+            magnet_radius: 0.0,
+            shield: false,
+            invincible: false,
+            abilities: Abilities::default(),
+        }
+    }
+}
+
+impl Modifiers {
+    /// Applies the power ups on top of the modifiers of a player without power ups
+    pub fn from_power_ups<'a>(power_ups: impl Iterator<Item = &'a PlayerPowerUp>) -> Self {
+        let mut modifiers = Self::default();
+        for power_up in power_ups {
+            power_up.modify(&mut modifiers);
+        }
+        modifiers
+    }
+}
+
+/// What happens if a power up gets collected while it is still active
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Stacking {
+    /// The duration gets added to the remaining time
+    Extend,
+    /// The remaining time gets set to the duration (if that is longer)
+    Refresh,
+}
+
+impl PlayerPowerUp {
+    /// Changes the modifiers while the power up is active
+    pub fn modify(&self, modifiers: &mut Modifiers) {
+        match self {
+            PlayerPowerUp::JumpBoost => modifiers.jump_speed *= 1.25,
+            PlayerPowerUp::SpeedBoost => modifiers.movement_speed += 700.0,
+            PlayerPowerUp::Coins2x => modifiers.coin_value *= 2,
+            PlayerPowerUp::Damage2x => modifiers.damage_factor *= 1.75,
+            PlayerPowerUp::Shield => modifiers.shield = true,
+            PlayerPowerUp::Invincibility => modifiers.invincible = true,
+            PlayerPowerUp::DoubleJump => modifiers.air_jumps += 1,
+            PlayerPowerUp::Magnet => modifiers.magnet_radius += 4.0,
+            PlayerPowerUp::RapidFire => modifiers.shoot_timeout_factor /= 2.0,
+            PlayerPowerUp::Dash => modifiers.abilities.dash = true,
+            PlayerPowerUp::WallJump => {
+                modifiers.abilities.wall_slide = true;
+                modifiers.abilities.wall_jump = true;
+            }
+        }
+    }
+
+    /// Shields and invincibility would be too strong if they could be collected in advance
+    pub fn stacking(&self) -> Stacking {
+        match self {
+            PlayerPowerUp::Shield | PlayerPowerUp::Invincibility => Stacking::Refresh,
+            _ => Stacking::Extend,
+        }
+    }
+
+    /// Returns the texture of the power up and the range of its animation
+    pub fn texture(&self) -> (TextureKey, (u32, u32)) {
+        match self {
+            PlayerPowerUp::JumpBoost => (TextureKey::PowerUps0, (0, 17)),
+            PlayerPowerUp::SpeedBoost => (TextureKey::PowerUps0, (18, 40)),
+            PlayerPowerUp::Coins2x => (TextureKey::PowerUps0, (41, 63)),
+            PlayerPowerUp::Damage2x => (TextureKey::PowerUps0, (64, 83)),
+            PlayerPowerUp::Shield => (TextureKey::PowerUps1, (0, 0)),
+            PlayerPowerUp::Invincibility => (TextureKey::PowerUps1, (1, 1)),
+            PlayerPowerUp::DoubleJump => (TextureKey::PowerUps1, (2, 2)),
+            PlayerPowerUp::Magnet => (TextureKey::PowerUps1, (3, 3)),
+            PlayerPowerUp::RapidFire => (TextureKey::PowerUps1, (4, 4)),
+            PlayerPowerUp::Dash => (TextureKey::PowerUps1, (5, 5)),
+            PlayerPowerUp::WallJump => (TextureKey::PowerUps1, (6, 6)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [PlayerPowerUp; 11] = [
+        PlayerPowerUp::JumpBoost,
+        PlayerPowerUp::SpeedBoost,
+        PlayerPowerUp::Coins2x,
+        PlayerPowerUp::Damage2x,
+        PlayerPowerUp::Shield,
+        PlayerPowerUp::Invincibility,
+        PlayerPowerUp::DoubleJump,
+        PlayerPowerUp::Magnet,
+        PlayerPowerUp::RapidFire,
+        PlayerPowerUp::Dash,
+        PlayerPowerUp::WallJump,
+    ];
+
+    #[test]
+    fn no_power_ups_keep_the_defaults() {
+        assert_eq!(Modifiers::from_power_ups([].iter()), Modifiers::default());
+    }
+
+    #[test]
+    fn all_power_ups_stack() {
+        let modifiers = Modifiers::from_power_ups(ALL.iter());
+        let default = Modifiers::default();
+
+        assert_eq!(modifiers.jump_speed, default.jump_speed * 1.25);
+        assert_eq!(modifiers.movement_speed, default.movement_speed + 700.0);
+        assert_eq!(modifiers.coin_value, 2);
+        assert_eq!(modifiers.damage_factor, 1.75);
+        assert_eq!(modifiers.shoot_timeout_factor, 0.5);
+        assert_eq!(modifiers.air_jumps, 1);
+        assert_eq!(modifiers.magnet_radius, 4.0);
+        assert!(modifiers.shield);
+        assert!(modifiers.invincible);
+        assert_eq!(modifiers.abilities, Abilities { dash: true, wall_slide: true, wall_jump: true });
+    }
+
+    #[test]
+    fn order_does_not_matter() {
+        let reversed: Vec<PlayerPowerUp> = ALL.iter().rev().copied().collect();
+        assert_eq!(Modifiers::from_power_ups(ALL.iter()), Modifiers::from_power_ups(reversed.iter()));
+    }
+
+    #[test]
+    fn power_ups_only_change_their_own_modifier() {
+        let modifiers = Modifiers::from_power_ups([PlayerPowerUp::Coins2x, PlayerPowerUp::DoubleJump].iter());
+        assert_eq!(modifiers, Modifiers { coin_value: 2, air_jumps: 1, ..Modifiers::default() });
+    }
+
+    #[test]
+    fn only_shield_and_invincibility_refresh() {
+        for power_up in ALL {
+            let expected = match power_up {
+                PlayerPowerUp::Shield | PlayerPowerUp::Invincibility => Stacking::Refresh,
+                _ => Stacking::Extend,
+            };
+            assert_eq!(power_up.stacking(), expected, "{:?}", power_up);
+        }
+    }
+}
@@ -0,0 +1,292 @@
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, Read, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::logic::ghost::ReplayFrame;
+use crate::logic::level::{Level, LevelScore};
+use crate::utils::versioning::write_atomic;
+
+// The history of all runs of a profile is stored in `scores.bin` (next to `level_data.json`).
+// It is an append-only binary log: a header (`MAGIC` and the format version) followed by records of `RECORD_SIZE` bytes.
+// Only the best completed run of a level keeps its replay, it is stored in `best_run_<level>.bin`.
+// Nothing gets read until the first query, after that only the index (see [IndexEntry]) is kept in memory.
+
+const MAGIC: &[u8; 4] = b"JMSL";
+const FORMAT_VERSION: u32 = 1;
+const HEADER_SIZE: u64 = 8;
+/// level (1) + time (8 + 4) + playtime (8) + coins, kills, damage, damage received (4 * 4) + completed (1)
+const RECORD_SIZE: u64 = 38;
+/// time (8) + x (4) + y (4) + state (1)
+const REPLAY_FRAME_SIZE: usize = 17;
+
+/// What is kept in memory about every run
+#[derive(PartialEq, Clone, Copy, Debug)]
+struct IndexEntry {
+    time: SystemTime,
+    playtime: f64,
+    completed: bool,
+}
+
+/// The history of all runs of a profile (see the top of this file)
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct ScoreLog {
+    /// The directory of the profile (empty if nothing gets saved, e.g. in simulations)
+    dir: String,
+    /// The runs of every level (oldest first), gets read with the first query
+    index: OnceCell<BTreeMap<Level, Vec<IndexEntry>>>,
+}
+
+impl ScoreLog {
+    pub fn new(dir: &str) -> Self {
+        Self { dir: dir.to_string(), index: OnceCell::new() }
+    }
+
+    fn path(&self) -> String {
+        format!("{}/scores.bin", self.dir)
+    }
+
+    fn best_run_path(&self, level: &Level) -> String {
+        format!("{}/best_run_{}.bin", self.dir, level.level_selector_page())
+    }
+
+    pub fn exists(&self) -> bool {
+        !self.dir.is_empty() && fs::exists(self.path()).unwrap_or(false)
+    }
+
+    fn index(&self) -> &BTreeMap<Level, Vec<IndexEntry>> {
+        self.index.get_or_init(|| match self.read_index() {
+            Ok(index) => index,
+            Err(e) => {
+                println!("Couldn't read score log with error \"{}\"", e);
+                BTreeMap::new()
+            }
+        })
+    }
+
+    fn read_index(&self) -> io::Result<BTreeMap<Level, Vec<IndexEntry>>> {
+        let mut index = BTreeMap::new();
+        if !self.exists() { return Ok(index); }
+
+        let mut reader = BufReader::new(File::open(self.path())?);
+        read_header(&mut reader)?;
+
+        // A record that was only partly written (the game crashed while saving) is left out
+        let mut record = [0; RECORD_SIZE as usize];
+        while reader.read_exact(&mut record).is_ok() {
+            if let Some((level, score)) = decode(&record) {
+                index.entry(level).or_insert_with(Vec::new).push(IndexEntry { time: score.time, playtime: score.playtime, completed: score.completed });
+            }
+        }
+
+        Ok(index)
+    }
+
+    /// Returns how often the level got played since the provided time
+    pub fn count_since(&self, level: &Level, time: SystemTime) -> usize {
+        match self.index().get(level) {
+            Some(entries) => entries.len() - entries.partition_point(|entry| entry.time < time),
+            None => 0,
+        }
+    }
+
+    /// Returns the playtime of the fastest completed run of the level
+    pub fn best_time(&self, level: &Level) -> Option<f64> {
+        self.index().get(level)?.iter()
+            .filter(|entry| entry.completed)
+            .map(|entry| entry.playtime)
+            .min_by(f64::total_cmp)
+    }
+
+    /// Adds the run to the end of the log
+    pub fn append(&mut self, level: &Level, score: &LevelScore) -> io::Result<()> {
+        if self.dir.is_empty() { return Ok(()); }
+
+        let mut file = OpenOptions::new().create(true).read(true).append(true).open(self.path())?;
+        let len = file.metadata()?.len();
+        if len < HEADER_SIZE {
+            file.set_len(0)?;
+            file.write_all(MAGIC)?;
+            file.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        } else if !(len - HEADER_SIZE).is_multiple_of(RECORD_SIZE) {
+            // Drops a record that was only partly written, so the following records stay aligned
+            file.set_len(len - (len - HEADER_SIZE) % RECORD_SIZE)?;
+        }
+
+        file.write_all(&encode(level, score))?;
+        file.sync_data()?;
+
+        if let Some(index) = self.index.get_mut() {
+            index.entry(*level).or_insert_with(Vec::new).push(IndexEntry { time: score.time, playtime: score.playtime, completed: score.completed });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the playtime and the replay of the fastest completed run of the level
+    pub fn best_run(&self, level: &Level) -> Option<(f64, Vec<ReplayFrame>)> {
+        if self.dir.is_empty() { return None; }
+
+        let bytes = fs::read(self.best_run_path(level)).ok()?;
+        let playtime = f64::from_le_bytes(bytes.get(0..8)?.try_into().ok()?);
+        let frames = bytes[8..].chunks_exact(REPLAY_FRAME_SIZE)
+            .map(|frame| ReplayFrame {
+                time: f64::from_le_bytes(frame[0..8].try_into().unwrap()),
+                x: f32::from_le_bytes(frame[8..12].try_into().unwrap()),
+                y: f32::from_le_bytes(frame[12..16].try_into().unwrap()),
+                state: frame[16] as i8,
+            })
+            .collect();
+
+        Some((playtime, frames))
+    }
+
+    /// Replaces the best run of the level
+    pub fn save_best_run(&self, level: &Level, playtime: f64, frames: &[ReplayFrame]) -> io::Result<()> {
+        if self.dir.is_empty() { return Ok(()); }
+
+        let mut bytes = Vec::with_capacity(8 + frames.len() * REPLAY_FRAME_SIZE);
+        bytes.extend_from_slice(&playtime.to_le_bytes());
+        for frame in frames {
+            bytes.extend_from_slice(&frame.time.to_le_bytes());
+            bytes.extend_from_slice(&frame.x.to_le_bytes());
+            bytes.extend_from_slice(&frame.y.to_le_bytes());
+            bytes.push(frame.state as u8);
+        }
+
+        write_atomic(&self.best_run_path(level), &bytes, false)
+    }
+}
+
+fn read_header(reader: &mut impl Read) -> io::Result<()> {
+    let mut header = [0; HEADER_SIZE as usize];
+    reader.read_exact(&mut header)?;
+
+    if &header[0..4] != MAGIC {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Not a score log"));
+    }
+    let version = u32::from_le_bytes(header[4..8].try_into().unwrap());
+    if version != FORMAT_VERSION {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Unknown score log version {}", version)));
+    }
+
+    Ok(())
+}
+
+fn encode(level: &Level, score: &LevelScore) -> [u8; RECORD_SIZE as usize] {
+    let time = score.time.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+    let mut record = [0; RECORD_SIZE as usize];
+    record[0] = level.level_selector_page() as u8;
+    record[1..9].copy_from_slice(&time.as_secs().to_le_bytes());
+    record[9..13].copy_from_slice(&time.subsec_nanos().to_le_bytes());
+    record[13..21].copy_from_slice(&score.playtime.to_le_bytes());
+    record[21..25].copy_from_slice(&score.coins.to_le_bytes());
+    record[25..29].copy_from_slice(&score.kills.to_le_bytes());
+    record[29..33].copy_from_slice(&score.total_damage.to_le_bytes());
+    record[33..37].copy_from_slice(&score.total_damage_received.to_le_bytes());
+    record[37] = score.completed as u8;
+
+    record
+}
+
+/// Returns `None` if the record belongs to a level that doesn't exist (anymore)
+fn decode(record: &[u8; RECORD_SIZE as usize]) -> Option<(Level, LevelScore)> {
+    let u32_at = |i: usize| u32::from_le_bytes(record[i..i + 4].try_into().unwrap());
+
+    let level = Level::from_number(record[0] as usize)?;
+    let secs = u64::from_le_bytes(record[1..9].try_into().unwrap());
+    let score = LevelScore {
+        time: UNIX_EPOCH + Duration::new(secs, u32_at(9)),
+        playtime: f64::from_le_bytes(record[13..21].try_into().unwrap()),
+        coins: u32_at(21),
+        kills: u32_at(25),
+        total_damage: u32_at(29),
+        total_damage_received: u32_at(33),
+        completed: record[37] != 0,
+        replay: Vec::new(),
+    };
+
+    Some((level, score))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the score log of a new profile directory, so the tests don't share their files
+    fn score_log(test: &str) -> ScoreLog {
+        let dir = std::env::temp_dir().join(format!("jumbo_mumbo_score_log_{}_{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        ScoreLog::new(dir.to_str().unwrap())
+    }
+
+    fn score(playtime: f64, completed: bool) -> LevelScore {
+        LevelScore {
+            time: UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789),
+            playtime,
+            coins: 12,
+            kills: 3,
+            total_damage: 4500,
+            total_damage_received: 250,
+            completed,
+            replay: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn records_decode_to_the_encoded_score() {
+        let score = score(42.5, true);
+        let record = encode(&Level::Level2, &score);
+
+        assert_eq!(decode(&record), Some((Level::Level2, score)));
+    }
+
+    #[test]
+    fn records_of_unknown_levels_are_skipped() {
+        let mut record = encode(&Level::Level1, &score(1.0, false));
+        record[0] = 200;
+
+        assert_eq!(decode(&record), None);
+    }
+
+    #[test]
+    fn appended_runs_can_be_queried_after_loading_again() {
+        let mut log = score_log("append");
+        log.append(&Level::Level1, &score(30.0, true)).unwrap();
+        log.append(&Level::Level1, &score(20.0, false)).unwrap();
+        log.append(&Level::Level1, &score(25.0, true)).unwrap();
+
+        let log = ScoreLog::new(&log.dir);
+        assert_eq!(log.count_since(&Level::Level1, UNIX_EPOCH), 3);
+        assert_eq!(log.count_since(&Level::Level0, UNIX_EPOCH), 0);
+        assert_eq!(log.best_time(&Level::Level1), Some(25.0));
+    }
+
+    #[test]
+    fn partly_written_records_are_dropped() {
+        let mut log = score_log("partial");
+        log.append(&Level::Level0, &score(10.0, true)).unwrap();
+        // The game crashed in the middle of the next record
+        OpenOptions::new().append(true).open(log.path()).unwrap().write_all(&[1, 2, 3]).unwrap();
+        log.append(&Level::Level0, &score(5.0, true)).unwrap();
+
+        let log = ScoreLog::new(&log.dir);
+        assert_eq!(log.count_since(&Level::Level0, UNIX_EPOCH), 2);
+        assert_eq!(log.best_time(&Level::Level0), Some(5.0));
+    }
+
+    #[test]
+    fn best_runs_keep_their_replay() {
+        let log = score_log("best_run");
+        let frames = vec![
+            ReplayFrame { time: 0.0, x: 1.5, y: -2.0, state: 0 },
+            ReplayFrame { time: 0.5, x: 2.5, y: -2.25, state: -1 },
+        ];
+        log.save_best_run(&Level::Level3, 12.5, &frames).unwrap();
+
+        assert_eq!(log.best_run(&Level::Level3), Some((12.5, frames)));
+        assert_eq!(log.best_run(&Level::Level0), None);
+    }
+}
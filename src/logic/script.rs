@@ -0,0 +1,488 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::rc::Rc;
+use macroquad::color::{BLACK, RED, WHITE};
+use macroquad::math::{vec2, Vec2};
+use macroquad::shapes::draw_rectangle;
+use macroquad::texture::Texture2D;
+use rhai::{Dynamic, Engine, FuncArgs, Map, Scope, AST};
+use serde::{Deserialize, Serialize};
+use crate::logic::event::{EventQueue, GameEvent, LevelTimer};
+use crate::logic::input::{InputAction, Keybindings};
+use crate::logic::level::{Level, LevelSceneData};
+use crate::logic::player::{Player, PlayerUIElementType};
+use crate::utils::enums::TextureKey;
+use crate::utils::i18n;
+use crate::utils::runtime::screen_height;
+use crate::utils::structs::{Rect, Settings};
+use crate::utils::text::{draw_text_box, TextAlign, TextStyle};
+
+// Every level can have a script (`res/levels/level_<number>.rhai`) that reacts to things happening in the level.
+// All positions and sizes inside of scripts are in tiles, so scripts work with every GUI scale.
+//
+// Events (define a function with the same name to react to them):
+//   on_start()            the level starts
+//   on_update()           every frame
+//   on_enter(area)        the player enters an area (see `area()`)
+//   on_leave(area)        the player leaves an area
+//   on_coin(coins)        the player collected a coin
+//   on_kill(kills)        the player killed an enemy
+//   on_damage(damage)     the player got damaged
+//   on_heal(amount)       the player collected a heart
+//   on_power_up(name)     the player picked up a power up (e.g. "JumpBoost")
+//   on_weapon(name)       the player picked up a weapon (e.g. "Shotgun")
+//   on_life_lost(lives)   the player lost a life and respawned (only in the lives mode)
+//   on_power_up_end(name) a power up of the player ran out
+//   on_timer(name)        a timer (see `timer()`) ran out
+//
+// Functions:
+//   area(name, x, y, w, h)                        adds an area that fires `on_enter` and `on_leave`
+//   timer(name, seconds)                          fires `on_timer` after the provided time
+//   show_text(id, text, x, y, size)               shows a text until it gets hidden
+//   show_text(id, text, x, y, size, seconds)      shows a text for the provided time
+//   hide_text(id)
+//   align_text(id, align)                         "left", "center" or "right" (x is then the left edge, center or right edge)
+//   wrap_text(id, width)                          the width after which the text wraps (default 10 tiles)
+//   texts can contain icons like [coin] or [kill]
+//   highlight(ui_element, seconds)                highlights the "coins", "kills" or "lives" counter
+//   flag(name) / set_flag(name, value)            flags the script can use to remember things
+//   spawn_coin(x, y) / spawn_enemy(x, y)
+//   complete() / game_over()                      ends the level
+//   player_x() / player_y() / coins() / kills() / time() / screen_bottom()
+//   tr(key) / tr(key, #{ name: value })           a text in the language of the player (see `src/utils/i18n.rs`)
+//   keys(action)                                  the keys of "left", "right", "jump", "shoot_left", "shoot_right", "dash" or "next_weapon" as text
+
+/// The default width of texts in tiles (see `wrap_text()`)
+const TEXT_WIDTH: f32 = 10.0;
+
+/// A text that got shown by a script
+#[derive(PartialEq, Clone, Debug)]
+struct ScriptText {
+    text: String,
+    /// The position in tiles
+    pos: Vec2,
+    font_size: f32,
+    /// The time at which the text disappears (if ever)
+    until: Option<f64>,
+    /// `pos` is the left edge, the center or the right edge of the text
+    align: TextAlign,
+    /// The width in tiles after which the text wraps
+    width: f32,
+}
+
+/// Things a script wants to change in the level <br>
+/// They get applied after the script has run, because the script has no access to the level itself
+#[derive(PartialEq, Clone, Debug)]
+enum ScriptCommand {
+    SpawnCoin(Vec2),
+    SpawnEnemy(Vec2),
+    Complete,
+    GameOver,
+}
+
+/// The state that is shared between the script functions and the level
+#[derive(Default)]
+struct ScriptState {
+    commands: Vec<ScriptCommand>,
+    /// The events of the level that the script didn't run yet (see [LevelScript::subscribe()])
+    events: Vec<GameEvent>,
+    texts: BTreeMap<String, ScriptText>,
+    highlights: BTreeMap<PlayerUIElementType, f64>,
+    flags: BTreeMap<String, bool>,
+    areas: BTreeMap<String, Rect>,
+    timers: BTreeMap<String, f64>,
+
+    // Snapshot of the level (in tiles) that the script can read
+    player: Vec2,
+    coins: u32,
+    kills: u32,
+    time: f64,
+    screen_bottom: f32,
+    keybindings: Keybindings,
+}
+
+/// The script of a level
+#[derive(Clone)]
+pub struct LevelScript {
+    engine: Rc<Engine>,
+    ast: AST,
+    scope: Scope<'static>,
+    state: Rc<RefCell<ScriptState>>,
+    started: bool,
+    /// `on_start` already ran before the level got saved (see [LevelScript::restore()])
+    resumed: bool,
+    /// The areas the player is currently in
+    inside: BTreeSet<String>,
+    /// The functions that failed, they don't get called again so the error is only printed once
+    failed: HashSet<String>,
+}
+
+/// Everything a script remembers between frames, so a saved level continues where the script was <br>
+/// Texts and highlights aren't saved, they only last a few seconds
+#[derive(PartialEq, Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ScriptSnapshot {
+    /// Whether `on_start` already ran
+    pub started: bool,
+    pub flags: BTreeMap<String, bool>,
+    pub areas: BTreeMap<String, Rect>,
+    /// The areas the player is currently in
+    pub inside: BTreeSet<String>,
+    /// The playtime at which each timer runs out
+    pub timers: BTreeMap<String, f64>,
+}
+
+impl LevelScript {
+    /// Loads the script of the provided level <br>
+    /// Returns `None` if the level has no script or the script cannot be compiled
+    pub async fn load(level: &Level) -> Option<Self> {
+        let path = level.script_path();
+        let source = fs::read_to_string(&path).ok()?;
+
+        Self::compile(&source, &path).await
+    }
+
+    /// Compiles the source of a script, `path` is only used for the error message
+    async fn compile(source: &str, path: &str) -> Option<Self> {
+        let state = Rc::new(RefCell::new(ScriptState::default()));
+        let engine = Self::engine(&state).await;
+
+        let ast = match engine.compile(source) {
+            Ok(ast) => ast,
+            Err(e) => {
+                println!("Cannot compile level script '{}': {}", path, e);
+                return None;
+            }
+        };
+
+        Some(Self {
+            engine: Rc::new(engine),
+            ast,
+            scope: Scope::new(),
+            state,
+            started: false,
+            resumed: false,
+            inside: BTreeSet::new(),
+            failed: HashSet::new(),
+        })
+    }
+
+    pub fn snapshot(&self) -> ScriptSnapshot {
+        let state = self.state.borrow();
+        ScriptSnapshot {
+            started: self.started || self.resumed,
+            flags: state.flags.clone(),
+            areas: state.areas.clone(),
+            inside: self.inside.clone(),
+            timers: state.timers.clone(),
+        }
+    }
+
+    /// Collects the events of the level, so they get run by the next [LevelScript::tick()]
+    pub fn subscribe(&self, events: &mut EventQueue) {
+        let s = self.state.clone();
+        events.subscribe(move |event| s.borrow_mut().events.push(event.clone()));
+    }
+
+    /// Continues the script of a saved level <br>
+    /// The script still runs its top level code again, but `on_start` only gets called if it didn't run before
+    pub fn restore(&mut self, snapshot: ScriptSnapshot) {
+        let mut state = self.state.borrow_mut();
+        state.flags = snapshot.flags;
+        state.areas = snapshot.areas;
+        state.timers = snapshot.timers;
+        self.inside = snapshot.inside;
+        self.resumed = snapshot.started;
+    }
+
+    /// Creates the [Engine] with all functions a script can call
+    async fn engine(state: &Rc<RefCell<ScriptState>>) -> Engine {
+        let mut engine = Engine::new();
+
+        let s = state.clone();
+        engine.register_fn("area", move |name: &str, x: Dynamic, y: Dynamic, w: Dynamic, h: Dynamic| {
+            let rect = Rect { x: number(x), y: number(y), w: number(w), h: number(h) };
+            s.borrow_mut().areas.insert(name.to_string(), rect);
+        });
+
+        let s = state.clone();
+        engine.register_fn("timer", move |name: &str, seconds: Dynamic| {
+            let mut state = s.borrow_mut();
+            let time = state.time + number(seconds) as f64;
+            state.timers.insert(name.to_string(), time);
+        });
+
+        let s = state.clone();
+        engine.register_fn("show_text", move |id: &str, text: &str, x: Dynamic, y: Dynamic, size: Dynamic| {
+            let text = ScriptText { text: text.to_string(), pos: vec2(number(x), number(y)), font_size: number(size), until: None, align: TextAlign::Left, width: TEXT_WIDTH };
+            s.borrow_mut().texts.insert(id.to_string(), text);
+        });
+
+        let s = state.clone();
+        engine.register_fn("show_text", move |id: &str, text: &str, x: Dynamic, y: Dynamic, size: Dynamic, seconds: Dynamic| {
+            let mut state = s.borrow_mut();
+            let until = Some(state.time + number(seconds) as f64);
+            let text = ScriptText { text: text.to_string(), pos: vec2(number(x), number(y)), font_size: number(size), until, align: TextAlign::Left, width: TEXT_WIDTH };
+            state.texts.insert(id.to_string(), text);
+        });
+
+        let s = state.clone();
+        engine.register_fn("hide_text", move |id: &str| { s.borrow_mut().texts.remove(id); });
+
+        let s = state.clone();
+        engine.register_fn("align_text", move |id: &str, align: &str| {
+            if let (Some(text), Some(align)) = (s.borrow_mut().texts.get_mut(id), TextAlign::from_name(align)) {
+                text.align = align;
+            }
+        });
+
+        let s = state.clone();
+        engine.register_fn("wrap_text", move |id: &str, width: Dynamic| {
+            if let Some(text) = s.borrow_mut().texts.get_mut(id) {
+                text.width = number(width);
+            }
+        });
+
+        let s = state.clone();
+        engine.register_fn("highlight", move |element: &str, seconds: Dynamic| {
+            let element = match element {
+                "coins" => PlayerUIElementType::Coins,
+                "kills" => PlayerUIElementType::Kills,
+                "lives" => PlayerUIElementType::Lives,
+                _ => return,
+            };
+            let mut state = s.borrow_mut();
+            let until = state.time + number(seconds) as f64;
+            state.highlights.insert(element, until);
+        });
+
+        let s = state.clone();
+        engine.register_fn("flag", move |name: &str| *s.borrow().flags.get(name).unwrap_or(&false));
+
+        let s = state.clone();
+        engine.register_fn("set_flag", move |name: &str, value: bool| { s.borrow_mut().flags.insert(name.to_string(), value); });
+
+        let s = state.clone();
+        engine.register_fn("spawn_coin", move |x: Dynamic, y: Dynamic| s.borrow_mut().commands.push(ScriptCommand::SpawnCoin(vec2(number(x), number(y)))));
+
+        let s = state.clone();
+        engine.register_fn("spawn_enemy", move |x: Dynamic, y: Dynamic| s.borrow_mut().commands.push(ScriptCommand::SpawnEnemy(vec2(number(x), number(y)))));
+
+        let s = state.clone();
+        engine.register_fn("complete", move || s.borrow_mut().commands.push(ScriptCommand::Complete));
+
+        let s = state.clone();
+        engine.register_fn("game_over", move || s.borrow_mut().commands.push(ScriptCommand::GameOver));
+
+        let s = state.clone();
+        engine.register_fn("player_x", move || s.borrow().player.x as f64);
+        let s = state.clone();
+        engine.register_fn("player_y", move || s.borrow().player.y as f64);
+        let s = state.clone();
+        engine.register_fn("coins", move || s.borrow().coins as i64);
+        let s = state.clone();
+        engine.register_fn("kills", move || s.borrow().kills as i64);
+        let s = state.clone();
+        engine.register_fn("time", move || s.borrow().time);
+        let s = state.clone();
+        engine.register_fn("screen_bottom", move || s.borrow().screen_bottom as f64);
+
+        engine.register_fn("tr", |key: &str| i18n::text(key));
+        engine.register_fn("tr", |key: &str, args: Map| {
+            let args: Vec<(&str, String)> = args.iter().map(|(name, value)| (name.as_str(), value.to_string())).collect();
+            i18n::text_with(key, &args)
+        });
+
+        let s = state.clone();
+        engine.register_fn("keys", move |action: &str| {
+            let action = match action {
+                "left" => InputAction::Left,
+                "right" => InputAction::Right,
+                "jump" => InputAction::Jump,
+                "shoot_left" => InputAction::ShootLeft,
+                "shoot_right" => InputAction::ShootRight,
+                "dash" => InputAction::Dash,
+                "next_weapon" => InputAction::NextWeapon,
+                _ => return String::new(),
+            };
+            s.borrow().keybindings.text(action)
+        });
+
+        engine
+    }
+
+    /// Runs the events the script got since the last call (see [LevelScript::subscribe()]) <br>
+    /// Gets called every frame by [crate::scenes::levels::levels::step_level()]
+    pub async fn tick(&mut self, level_scene_data: &mut LevelSceneData, settings: &Settings) {
+        let tile_size = 128.0 * settings.gui_scale;
+        let level_data = &level_scene_data.level_data;
+        let player = level_data.player.as_ref().unwrap();
+        let time = level_data.playtime().await;
+
+        { // Update the snapshot
+            let mut state = self.state.borrow_mut();
+            state.player = player.pos / tile_size;
+            let tally = level_data.tally.borrow();
+            state.coins = tally.coins;
+            state.kills = tally.kills;
+            state.time = time;
+            state.screen_bottom = screen_height() / tile_size;
+            state.keybindings.clone_from(&settings.keybindings);
+        }
+
+        if !self.started {
+            self.started = true;
+            if let Err(e) = self.engine.run_ast_with_scope(&mut self.scope, &self.ast) {
+                println!("Level script failed: {}", e);
+            }
+            if !self.resumed {
+                self.call("on_start", ()).await;
+            }
+        }
+
+        self.call("on_update", ()).await;
+
+        { // Timers (they reach the script as events like everything else)
+            let finished: Vec<String> = self.state.borrow().timers.iter()
+                .filter(|(_, until)| **until <= time)
+                .map(|(name, _)| name.to_owned())
+                .collect();
+
+            for name in finished {
+                self.state.borrow_mut().timers.remove(&name);
+                level_scene_data.level_data.events.push(GameEvent::TimerExpired { timer: LevelTimer::Script(name) });
+            }
+            level_scene_data.level_data.events.dispatch();
+        }
+
+        let player = level_scene_data.level_data.player.as_ref().unwrap();
+        let (coins, kills) = {
+            let state = self.state.borrow();
+            (state.coins, state.kills)
+        };
+        let events = std::mem::take(&mut self.state.borrow_mut().events);
+        for event in events {
+            match event {
+                GameEvent::CoinCollected { .. } => self.call("on_coin", (coins as i64,)).await,
+                GameEvent::EnemyKilled { .. } => self.call("on_kill", (kills as i64,)).await,
+                GameEvent::PlayerDamaged { damage } => self.call("on_damage", (damage as i64,)).await,
+                GameEvent::PlayerHealed { amount } => self.call("on_heal", (amount as i64,)).await,
+                GameEvent::PowerUpPicked { power_up } => self.call("on_power_up", (format!("{:?}", power_up),)).await,
+                GameEvent::WeaponPicked { weapon } => self.call("on_weapon", (format!("{:?}", weapon),)).await,
+                GameEvent::LifeLost { lives } => self.call("on_life_lost", (lives as i64,)).await,
+                GameEvent::TimerExpired { timer: LevelTimer::PowerUp(power_up) } => self.call("on_power_up_end", (format!("{:?}", power_up),)).await,
+                GameEvent::TimerExpired { timer: LevelTimer::Script(name) } => self.call("on_timer", (name,)).await,
+                _ => {}
+            }
+        }
+
+        { // Areas
+            let player_rect = Rect { x: player.pos.x / tile_size, y: player.pos.y / tile_size, w: player.width / tile_size, h: player.height / tile_size };
+            let areas = self.state.borrow().areas.clone();
+
+            for (name, area) in areas {
+                let inside = area.overlaps(&player_rect).await;
+                if inside && self.inside.insert(name.clone()) {
+                    self.call("on_enter", (name,)).await;
+                } else if !inside && self.inside.remove(&name) {
+                    self.call("on_leave", (name,)).await;
+                }
+            }
+        }
+
+        { // Remove old texts and highlights
+            let mut state = self.state.borrow_mut();
+            state.texts.retain(|_, text| text.until.is_none_or(|until| until > time));
+            state.highlights.retain(|_, until| *until > time);
+        }
+
+        let commands = std::mem::take(&mut self.state.borrow_mut().commands);
+        for command in commands {
+            self.apply(command, level_scene_data, settings).await;
+        }
+    }
+
+    /// Calls a function of the script if it exists
+    async fn call(&mut self, name: &str, args: impl FuncArgs) {
+        let mut values = Vec::new();
+        args.parse(&mut values);
+
+        if self.failed.contains(name) || !self.ast.iter_functions().any(|function| function.name == name && function.params.len() == values.len()) {
+            return;
+        }
+
+        if let Err(e) = self.engine.call_fn::<Dynamic>(&mut self.scope, &self.ast, name, values) {
+            println!("Level script failed in '{}': {} (it won't be called again)", name, e);
+            self.failed.insert(name.to_string());
+        }
+    }
+
+    async fn apply(&self, command: ScriptCommand, level_scene_data: &mut LevelSceneData, settings: &Settings) {
+        let size = vec2(128.0, 128.0) * settings.gui_scale;
+        let level_data = &mut level_scene_data.level_data;
+
+        match command {
+            ScriptCommand::SpawnCoin(pos) => {
+                level_data.entities.spawn_coin(pos * size, size, TextureKey::Coin0).await;
+            }
+            ScriptCommand::SpawnEnemy(pos) => {
+                level_data.entities.spawn_enemy(pos * size, size, -50, TextureKey::Enemy0, &mut level_scene_data.world).await;
+            }
+            ScriptCommand::Complete => level_data.complete().await,
+            ScriptCommand::GameOver => {
+                level_data.player.as_mut().unwrap().health = 0;
+                level_data.game_over().await;
+            }
+        }
+    }
+
+    /// Draws the texts and highlights of the script
+    pub async fn render(&self, player: &Player, textures: &BTreeMap<TextureKey, Vec<Texture2D>>, settings: &Settings) {
+        let tile_size = 128.0 * settings.gui_scale;
+        let state = self.state.borrow();
+
+        for element_type in state.highlights.keys() {
+            if let Some(element) = player.ui_elements.get(element_type) {
+                draw_rectangle(element.pos.x + element.texture_size.x / 8.0, element.pos.y, element.texture_size.x, element.texture_size.y, RED);
+            }
+        }
+
+        for text in state.texts.values() {
+            let style = TextStyle { align: text.align, shadow: Some(BLACK), ..TextStyle::new(text.font_size * settings.gui_scale, WHITE) };
+            draw_text_box(&text.text, text.pos.x * tile_size, text.pos.y * tile_size, text.width * tile_size, &style, Some(textures));
+        }
+    }
+}
+
+/// Converts an integer or float of a script into an `f32`
+fn number(value: Dynamic) -> f32 {
+    value.as_float().map(|v| v as f32).or_else(|_| value.as_int().map(|v| v as f32)).unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::runtime;
+
+    #[test]
+    fn failing_function_only_runs_once() {
+        runtime::block_on(async {
+            let source = r#"
+                fn on_update() { spawn_coin(1.0, 2.0); throw "broken"; }
+                fn on_timer(name) { spawn_enemy(3.0, 4.0); }
+            "#;
+            let mut script = LevelScript::compile(source, "test.rhai").await.unwrap();
+
+            for _ in 0..3 {
+                script.call("on_update", ()).await;
+                script.call("on_timer", ("a".to_string(),)).await;
+            }
+
+            let commands = &script.state.borrow().commands;
+            assert_eq!(commands.iter().filter(|command| matches!(command, ScriptCommand::SpawnCoin(_))).count(), 1);
+            assert_eq!(commands.iter().filter(|command| matches!(command, ScriptCommand::SpawnEnemy(_))).count(), 3);
+            assert!(script.failed.contains("on_update"));
+        });
+    }
+}
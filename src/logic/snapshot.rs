@@ -0,0 +1,658 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use macroquad::color::WHITE;
+use macroquad::math::{vec2, Vec2};
+use macroquad::prelude::{Camera2D, Rect};
+use macroquad_platformer::World;
+use serde::{Deserialize, Serialize};
+use crate::logic::collider::{Collider, ColliderType};
+use crate::logic::ecs::components::{Body, ContactDamage, Damage, Health, Lifetime, Loot, Pickup, ProjectileBehaviour, ProjectileOrigin, Shooter, Sprite, Transform, Velocity};
+use crate::logic::ecs::{with_storages, Entities, Entity};
+use crate::logic::enemy::EnemyAi;
+use crate::logic::ghost::{ReplayFrame, ReplayRecorder};
+use crate::logic::level::{Level, LevelSceneData};
+use crate::logic::script::{LevelScript, ScriptSnapshot};
+use crate::logic::player::{CollectedPowerUp, PlayerPowerUp, DEFAULT_MAX_HEALTH};
+use crate::logic::timer::{Timer, Timers};
+use crate::logic::weapon::WeaponKind;
+use crate::scenes::levels::levels;
+use crate::utils::enums::{Animation, AnimationType, Direction, TextureKey};
+use crate::utils::runtime::{get_time, screen_height, screen_width, set_camera};
+use crate::utils::structs::Settings;
+use crate::utils::i18n;
+use crate::utils::versioning::{save_file, set_aside};
+use crate::ui::notice;
+
+// Everything that is needed to continue a level later ("Save & Quit" with Ctrl + S and "Continue" in the main menu).
+// A snapshot doesn't contain the level itself. The level gets created again with its layout and the snapshot
+// gets applied on top of it. All positions are in tiles and all timers contain the remaining time, so a
+// snapshot works with every GUI scale.
+
+/// A saved level in progress
+#[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub struct LevelSnapshot {
+    pub level: Level,
+    pub playtime: f64,
+    pub player: PlayerSnapshot,
+    pub entities: Vec<EntitySnapshot>,
+    /// The state of the level script (if the level has one)
+    #[serde(default)]
+    pub script: Option<ScriptSnapshot>,
+    /// The path of the player so far, so the run can still become the ghost of the best run
+    #[serde(default)]
+    pub replay: Vec<ReplayFrame>,
+}
+
+#[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub struct PlayerSnapshot {
+    pub x: f32,
+    pub y: f32,
+    pub speed_x: f32,
+    pub speed_y: f32,
+    pub state: i8,
+    pub health: i16,
+    #[serde(default = "default_max_health")]
+    pub max_health: i16,
+    pub kills: u32,
+    pub coins: u32,
+    pub total_damage: u32,
+    pub total_damage_received: u32,
+    /// The remaining lives in the lives mode
+    #[serde(default)]
+    pub lives: Option<u32>,
+    #[serde(default)]
+    pub deaths: u32,
+    pub power_ups: Vec<PowerUpSnapshot>,
+    /// The weapons the player carries and their ammo (empty in older saves)
+    #[serde(default)]
+    pub weapons: BTreeMap<WeaponKind, Option<u32>>,
+    #[serde(default)]
+    pub weapon: WeaponKind,
+    /// The positions of the left, right, upper and lower camera collider
+    pub camera: [(f32, f32); 4],
+}
+
+fn default_max_health() -> i16 {
+    DEFAULT_MAX_HEALTH
+}
+
+/// A power up the player has collected
+#[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub struct PowerUpSnapshot {
+    pub power_up: PlayerPowerUp,
+    pub duration: f64,
+    pub remaining: f64,
+    pub texture_key: TextureKey,
+    pub animation: AnimationType,
+}
+
+/// How a component gets saved in an [EntitySnapshot] <br>
+/// Positions, sizes and speeds are saved in tiles, so a snapshot works with every GUI scale
+pub trait ComponentSnapshot: Sized {
+    type Snapshot;
+
+    fn capture(&self, tile_size: f32) -> Self::Snapshot;
+
+    async fn restore(snapshot: &Self::Snapshot, context: &mut RestoreContext<'_>) -> Self;
+}
+
+/// What the components of an entity need to be restored
+pub struct RestoreContext<'a> {
+    /// The position of the entity (in pixels)
+    pub pos: Vec2,
+    /// The size of the entity (in pixels)
+    pub size: Vec2,
+    pub tile_size: f32,
+    pub world: &'a mut World,
+}
+
+macro_rules! entity_snapshot {
+    (saved { $($storage:ident / $field:ident: $component:ty),* $(,)? } layout { $($layout:ident: $layout_component:ty),* $(,)? }) => {
+        /// One entity with all of its components (see [crate::logic::ecs::Entities]) <br>
+        /// Components the entity doesn't have are `None`, so every kind of entity gets saved the same way
+        #[derive(PartialEq, Clone, Default, Serialize, Deserialize, Debug)]
+        #[serde(default)]
+        pub struct EntitySnapshot {
+            $(pub $field: Option<<$component as ComponentSnapshot>::Snapshot>,)*
+        }
+
+        impl EntitySnapshot {
+            /// Saves all components of the entity
+            pub fn capture(entities: &Entities, entity: &Entity, tile_size: f32) -> Self {
+                Self {
+                    $($field: entities.$storage.get(entity).map(|component| component.capture(tile_size)),)*
+                }
+            }
+
+            /// Spawns the entity again with all of its components
+            pub async fn restore(&self, entities: &mut Entities, world: &mut World, tile_size: f32) -> Entity {
+                let entity = entities.spawn();
+                let Some(transform) = self.transform else { return entity };
+                let mut context = RestoreContext {
+                    pos: vec2(transform.x, transform.y) * tile_size,
+                    size: vec2(transform.w, transform.h) * tile_size,
+                    tile_size,
+                    world,
+                };
+
+                $(if let Some(snapshot) = &self.$field {
+                    let component = <$component as ComponentSnapshot>::restore(snapshot, &mut context).await;
+                    entities.$storage.insert(entity, component);
+                })*
+
+                entity
+            }
+        }
+    };
+}
+
+with_storages!(entity_snapshot);
+
+/// Position and size in tiles
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct TransformSnapshot {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+impl ComponentSnapshot for Transform {
+    type Snapshot = TransformSnapshot;
+
+    fn capture(&self, tile_size: f32) -> Self::Snapshot {
+        TransformSnapshot {
+            x: self.pos.x / tile_size,
+            y: self.pos.y / tile_size,
+            w: self.size.x / tile_size,
+            h: self.size.y / tile_size,
+        }
+    }
+
+    async fn restore(_: &Self::Snapshot, context: &mut RestoreContext<'_>) -> Self {
+        Transform { pos: context.pos, size: context.size }
+    }
+}
+
+/// In tiles per second
+impl ComponentSnapshot for Velocity {
+    type Snapshot = (f32, f32);
+
+    fn capture(&self, tile_size: f32) -> Self::Snapshot {
+        (self.0 / tile_size).into()
+    }
+
+    async fn restore(&(x, y): &Self::Snapshot, context: &mut RestoreContext<'_>) -> Self {
+        Velocity(vec2(x, y) * context.tile_size)
+    }
+}
+
+/// The collider has the position and size of the transform
+impl ComponentSnapshot for Collider {
+    type Snapshot = ColliderType;
+
+    fn capture(&self, _: f32) -> Self::Snapshot {
+        self.collider_type
+    }
+
+    async fn restore(&collider_type: &Self::Snapshot, context: &mut RestoreContext<'_>) -> Self {
+        let rect = crate::utils::structs::Rect::new(context.pos.x, context.pos.y, context.size.x, context.size.y).await;
+        Collider { rect, offset: vec2(0.0, 0.0), collider_type }
+    }
+}
+
+#[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub struct SpriteSnapshot {
+    pub texture_key: TextureKey,
+    pub index: usize,
+    /// The animation starts again from its first texture
+    pub animation: Option<AnimationType>,
+    pub rotate: bool,
+}
+
+impl ComponentSnapshot for Sprite {
+    type Snapshot = SpriteSnapshot;
+
+    fn capture(&self, _: f32) -> Self::Snapshot {
+        SpriteSnapshot {
+            texture_key: self.texture_key,
+            index: self.index,
+            animation: self.animation.map(|animation| animation.animation_type),
+            rotate: self.rotate,
+        }
+    }
+
+    async fn restore(snapshot: &Self::Snapshot, _: &mut RestoreContext<'_>) -> Self {
+        Sprite {
+            texture_key: snapshot.texture_key,
+            index: snapshot.index,
+            animation: snapshot.animation.map(Animation::new),
+            rotate: snapshot.rotate,
+            color: WHITE,
+        }
+    }
+}
+
+impl ComponentSnapshot for Pickup {
+    type Snapshot = Pickup;
+
+    fn capture(&self, _: f32) -> Self::Snapshot {
+        self.clone()
+    }
+
+    async fn restore(snapshot: &Self::Snapshot, _: &mut RestoreContext<'_>) -> Self {
+        snapshot.clone()
+    }
+}
+
+/// The enemies that were already pierced or damaged aren't saved, because entities get new ids
+#[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub struct DamageSnapshot {
+    pub amount: i16,
+    pub origin: ProjectileOrigin,
+}
+
+impl ComponentSnapshot for Damage {
+    type Snapshot = DamageSnapshot;
+
+    fn capture(&self, _: f32) -> Self::Snapshot {
+        DamageSnapshot { amount: self.amount, origin: self.origin.clone() }
+    }
+
+    async fn restore(snapshot: &Self::Snapshot, _: &mut RestoreContext<'_>) -> Self {
+        Damage { amount: snapshot.amount, origin: snapshot.origin.clone(), pierced: Vec::new(), damaged: Vec::new() }
+    }
+}
+
+/// Contains the remaining bounces and pierces
+impl ComponentSnapshot for ProjectileBehaviour {
+    type Snapshot = ProjectileBehaviour;
+
+    fn capture(&self, _: f32) -> Self::Snapshot {
+        *self
+    }
+
+    async fn restore(snapshot: &Self::Snapshot, _: &mut RestoreContext<'_>) -> Self {
+        *snapshot
+    }
+}
+
+/// The duration and the remaining time of the lifetime
+impl ComponentSnapshot for Lifetime {
+    type Snapshot = (f64, f64);
+
+    fn capture(&self, _: f32) -> Self::Snapshot {
+        (self.0.duration(), self.0.remaining())
+    }
+
+    async fn restore(&(duration, remaining): &Self::Snapshot, _: &mut RestoreContext<'_>) -> Self {
+        Lifetime(Timer::with_remaining(duration, remaining))
+    }
+}
+
+#[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
+pub struct ShooterSnapshot {
+    /// The time between two shots
+    pub duration: f64,
+    /// The time until the next shot
+    pub remaining: f64,
+    pub direction: Direction,
+    /// In tiles per second
+    pub projectile_speed: f32,
+    pub projectile_time: f64,
+    pub projectile_texture_key: TextureKey,
+    pub projectile_behaviour: ProjectileBehaviour,
+    pub damage: i16,
+}
+
+/// The solid of a cannon isn't added to the world again, because cannons are part of the level layout
+impl ComponentSnapshot for Shooter {
+    type Snapshot = ShooterSnapshot;
+
+    fn capture(&self, tile_size: f32) -> Self::Snapshot {
+        ShooterSnapshot {
+            duration: self.timer.duration(),
+            remaining: self.timer.remaining(),
+            direction: self.direction,
+            projectile_speed: self.projectile_speed / tile_size,
+            projectile_time: self.projectile_time,
+            projectile_texture_key: self.projectile_texture_key,
+            projectile_behaviour: self.projectile_behaviour,
+            damage: self.damage,
+        }
+    }
+
+    async fn restore(snapshot: &Self::Snapshot, context: &mut RestoreContext<'_>) -> Self {
+        Shooter {
+            timer: Timer::with_remaining(snapshot.duration, snapshot.remaining),
+            direction: snapshot.direction,
+            projectile_speed: snapshot.projectile_speed * context.tile_size,
+            projectile_time: snapshot.projectile_time,
+            projectile_texture_key: snapshot.projectile_texture_key,
+            projectile_behaviour: snapshot.projectile_behaviour,
+            damage: snapshot.damage,
+        }
+    }
+}
+
+/// The speed of the body (in tiles per second), the entity gets a new actor in the world
+impl ComponentSnapshot for Body {
+    type Snapshot = (f32, f32);
+
+    fn capture(&self, tile_size: f32) -> Self::Snapshot {
+        (self.speed / tile_size).into()
+    }
+
+    async fn restore(&(x, y): &Self::Snapshot, context: &mut RestoreContext<'_>) -> Self {
+        let actor = context.world.add_actor(context.pos, context.size.x as i32, context.size.y as i32);
+        Body { actor, speed: vec2(x, y) * context.tile_size }
+    }
+}
+
+/// The overlay isn't saved
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct HealthSnapshot {
+    pub amount: i16,
+    pub max: i16,
+}
+
+impl ComponentSnapshot for Health {
+    type Snapshot = HealthSnapshot;
+
+    fn capture(&self, _: f32) -> Self::Snapshot {
+        HealthSnapshot { amount: self.amount, max: self.max }
+    }
+
+    async fn restore(snapshot: &Self::Snapshot, _: &mut RestoreContext<'_>) -> Self {
+        Health { amount: snapshot.amount, max: snapshot.max, timers: Timers::new() }
+    }
+}
+
+impl ComponentSnapshot for ContactDamage {
+    type Snapshot = i16;
+
+    fn capture(&self, _: f32) -> Self::Snapshot {
+        self.0
+    }
+
+    async fn restore(&damage: &Self::Snapshot, _: &mut RestoreContext<'_>) -> Self {
+        ContactDamage(damage)
+    }
+}
+
+impl ComponentSnapshot for Loot {
+    type Snapshot = Pickup;
+
+    fn capture(&self, _: f32) -> Self::Snapshot {
+        self.0.clone()
+    }
+
+    async fn restore(snapshot: &Self::Snapshot, _: &mut RestoreContext<'_>) -> Self {
+        Loot(snapshot.clone())
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize, Debug)]
+pub struct EnemySnapshot {
+    pub idling_right: bool,
+}
+
+/// The enemy AI starts looking for the player again, only the walking direction is kept
+impl ComponentSnapshot for EnemyAi {
+    type Snapshot = EnemySnapshot;
+
+    fn capture(&self, _: f32) -> Self::Snapshot {
+        EnemySnapshot { idling_right: self.idling_right }
+    }
+
+    async fn restore(snapshot: &Self::Snapshot, context: &mut RestoreContext<'_>) -> Self {
+        let mut ai = EnemyAi::new(context.pos, context.size).await;
+        ai.idling_right = snapshot.idling_right;
+        ai
+    }
+}
+
+impl LevelSnapshot {
+    /// Returns the path of the save file (next to the settings file)
+    pub fn path(settings: &Settings) -> String {
+        Path::new(&settings.path).with_file_name("level_save.json").to_str().unwrap().to_string()
+    }
+
+    pub fn exists(settings: &Settings) -> bool {
+        fs::exists(Self::path(settings)).unwrap_or(false)
+    }
+
+    /// Creates a snapshot of the running level
+    pub async fn capture(level_scene_data: &LevelSceneData, settings: &Settings) -> Self {
+        let tile_size = 128.0 * settings.gui_scale;
+        let level_data = &level_scene_data.level_data;
+        let world = &level_scene_data.world;
+        let player = level_data.player.as_ref().unwrap();
+        let tally = level_data.tally.borrow().clone();
+
+        let player = PlayerSnapshot {
+            x: player.pos.x / tile_size,
+            y: player.pos.y / tile_size,
+            speed_x: player.speed.x / tile_size,
+            speed_y: player.speed.y / tile_size,
+            state: player.state,
+            health: player.health,
+            max_health: player.max_health,
+            kills: tally.kills,
+            coins: tally.coins,
+            total_damage: tally.total_damage,
+            total_damage_received: tally.total_damage_received,
+            lives: player.lives,
+            deaths: tally.deaths,
+            power_ups: player.power_ups.iter().map(|(power_up, collected)| PowerUpSnapshot {
+                power_up: *power_up,
+                duration: collected.duration,
+                remaining: player.power_up_timers.remaining(power_up),
+                texture_key: collected.texture_key,
+                animation: collected.animation.animation_type,
+            }).collect(),
+            weapons: player.weapons.clone(),
+            weapon: player.weapon,
+            camera: player.camera_collider.map(|collider| {
+                let pos = world.actor_pos(collider);
+                (pos.x / tile_size, pos.y / tile_size)
+            }),
+        };
+
+        // Platforms never change, they come from the layout again
+        let entities = &level_data.entities;
+        let entity_snapshots = entities.transforms.keys()
+            .filter(|entity| !entities.is_layout(entity))
+            .map(|entity| EntitySnapshot::capture(entities, entity, tile_size))
+            .collect();
+
+        Self {
+            level: level_data.level.unwrap(),
+            playtime: level_data.playtime().await,
+            player,
+            entities: entity_snapshots,
+            script: level_data.script.as_ref().map(LevelScript::snapshot),
+            replay: level_data.replay.frames.clone(),
+        }
+    }
+
+    /// Creates the level again and puts everything where it was when the snapshot was taken
+    pub async fn restore(&self, settings: &Settings) -> LevelSceneData {
+        let tile_size = 128.0 * settings.gui_scale;
+        let tiles = |x: f32, y: f32| vec2(x, y) * tile_size;
+
+        let mut level_scene_data = levels::layout(self.level, settings).await;
+        let world = &mut level_scene_data.world;
+        let level_data = &mut level_scene_data.level_data;
+
+        level_data.start_time = get_time() - self.playtime;
+
+        { // Player
+            let snapshot = &self.player;
+            let player = level_data.player.as_mut().unwrap();
+            let pos = tiles(snapshot.x, snapshot.y);
+
+            world.set_actor_position(player.collider, pos);
+            player.collider_new.change_pos(pos).await;
+            player.pos = pos;
+            player.speed = tiles(snapshot.speed_x, snapshot.speed_y);
+            player.state = snapshot.state;
+            player.health = snapshot.health;
+            player.max_health = snapshot.max_health;
+            {
+                let mut tally = level_data.tally.borrow_mut();
+                tally.kills = snapshot.kills;
+                tally.coins = snapshot.coins;
+                tally.total_damage = snapshot.total_damage;
+                tally.total_damage_received = snapshot.total_damage_received;
+                tally.deaths = snapshot.deaths;
+            }
+            // A level that was saved in another mode continues in the mode of the settings
+            if player.lives.is_some() && snapshot.lives.is_some() {
+                player.lives = snapshot.lives;
+            }
+
+            for power_up in &snapshot.power_ups {
+                player.power_ups.insert(power_up.power_up, CollectedPowerUp {
+                    duration: power_up.duration,
+                    texture_key: power_up.texture_key,
+                    animation: Animation::new(power_up.animation),
+                });
+                player.power_up_timers.insert(power_up.power_up, Timer::with_remaining(power_up.duration, power_up.remaining));
+            }
+
+            if !snapshot.weapons.is_empty() {
+                player.weapons = snapshot.weapons.clone();
+                player.weapon = snapshot.weapon;
+            }
+
+            for (collider, (x, y)) in player.camera_collider.iter().zip(snapshot.camera) {
+                world.set_actor_position(*collider, tiles(x, y));
+            }
+
+            // The camera only moves with the player, so it has to be set once
+            let left = world.actor_pos(player.camera_collider[0]);
+            let up = world.actor_pos(player.camera_collider[2]);
+            set_camera(&Camera2D::from_display_rect(Rect::new(left.x, up.y + screen_height(), screen_width(), -screen_height())));
+        }
+
+        { // Enemies, coins, power ups, projectiles and cannons
+            let entities = &mut level_data.entities;
+
+            // The entities of the layout get replaced (their actors and solids stay in the world), only the platforms are kept
+            let replaced: Vec<Entity> = entities.transforms.keys().filter(|entity| !entities.is_layout(entity)).copied().collect();
+            for entity in replaced {
+                entities.despawn(entity);
+            }
+
+            for snapshot in &self.entities {
+                snapshot.restore(entities, world, tile_size).await;
+            }
+        }
+
+        if let (Some(script), Some(snapshot)) = (level_data.script.as_mut(), &self.script) {
+            script.restore(snapshot.clone());
+        }
+        level_data.replay = ReplayRecorder::resume(self.replay.clone());
+
+        level_scene_data
+    }
+
+    /// Returns whether the level got saved (errors are shown to the player)
+    pub async fn save(&self, settings: &Settings) -> bool {
+        let path = Self::path(settings);
+        let saved = save_file(&path, "file.saved_level", self, false);
+        if saved {
+            println!("Saved level to {}", path);
+        }
+        saved
+    }
+
+    /// Loads the saved level and deletes the save, so it can only be continued once <br>
+    /// Returns `None` if there is no save or it cannot be read (then it gets moved aside and the player gets told)
+    pub async fn take(settings: &Settings) -> Option<Self> {
+        let path = Self::path(settings);
+        let s_snapshot = fs::read_to_string(&path).ok()?;
+
+        match serde_json::from_str(&s_snapshot) {
+            Ok(snapshot) => {
+                let _ = fs::remove_file(&path);
+                Some(snapshot)
+            }
+            Err(e) => {
+                println!("Couldn't deserialize level snapshot with error \"{}\"", e);
+                // The save is kept next to the settings, so the "Continue" button doesn't try it again
+                let moved_text = match set_aside(&path, "saved level") {
+                    Some(new_path) => i18n::text_with("notice.file_moved", &[("path", new_path)]),
+                    None => String::new(),
+                };
+                notice::push(i18n::text_with("notice.saved_level_not_loaded", &[("moved", moved_text)]));
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::agent::agent_from_name;
+    use crate::simulation::headless::{headless_settings, simulate};
+    use crate::utils::runtime;
+
+    /// Plays the start of the level, saves it and continues it again
+    fn save_and_continue(level: Level, frames: u32) -> (LevelSnapshot, LevelSnapshot) {
+        runtime::enable_headless(1.0 / 60.0);
+        runtime::block_on(async {
+            let settings = headless_settings().await;
+            let mut level_scene_data = levels::layout(level, &settings).await;
+            simulate(&mut level_scene_data, frames, &settings, agent_from_name("simple").unwrap().as_mut()).await;
+
+            let snapshot = LevelSnapshot::capture(&level_scene_data, &settings).await;
+            let s_snapshot = serde_json::to_string(&snapshot).unwrap();
+            let loaded: LevelSnapshot = serde_json::from_str(&s_snapshot).unwrap();
+            let restored = loaded.restore(&settings).await;
+
+            (snapshot, LevelSnapshot::capture(&restored, &settings).await)
+        })
+    }
+
+    #[test]
+    fn restored_level_has_the_saved_entities_script_and_replay() {
+        let (saved, restored) = save_and_continue(Level::Level0, 120);
+
+        assert!(saved.script.as_ref().is_some_and(|script| script.started));
+        assert!(!saved.replay.is_empty());
+        assert_eq!(saved.player, restored.player);
+        assert_eq!(saved.entities, restored.entities);
+        assert_eq!(saved.script, restored.script);
+        // JSON doesn't keep the last digit of every time
+        assert_eq!(saved.replay.len(), restored.replay.len());
+    }
+
+    #[test]
+    fn platforms_are_not_saved_and_come_from_the_layout() {
+        runtime::enable_headless(1.0 / 60.0);
+        runtime::block_on(async {
+            let settings = headless_settings().await;
+            let level_scene_data = levels::layout(Level::Level1, &settings).await;
+            let snapshot = LevelSnapshot::capture(&level_scene_data, &settings).await;
+            let restored = snapshot.restore(&settings).await;
+
+            let platforms = level_scene_data.level_data.entities.platforms.len();
+            assert!(platforms > 0);
+            assert_eq!(snapshot.entities.len() + platforms, level_scene_data.level_data.entities.count());
+            assert_eq!(restored.level_data.entities.platforms.len(), platforms);
+            assert_eq!(restored.level_data.entities.count(), level_scene_data.level_data.entities.count());
+        });
+    }
+
+    #[test]
+    fn restored_enemies_keep_their_health_and_loot() {
+        let (saved, restored) = save_and_continue(Level::Level1, 30);
+
+        assert!(saved.entities.iter().any(|entity| entity.health.is_some() && entity.enemy.is_some()));
+        assert_eq!(saved.entities, restored.entities);
+    }
+}
@@ -0,0 +1,85 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::logic::event::{EventQueue, GameEvent};
+use crate::utils::runtime::get_time;
+
+/// How a level ended
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum LevelOutcome {
+    Completed,
+    GameOver,
+}
+
+/// The score and the outcome of the current run <br>
+/// Only gets changed by the [GameEvent]s of the level (it subscribes to the [crate::logic::event::EventQueue], see [Tally::read()]),
+/// so the HUD, the level script, the level flow and the saved score all count the same things
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct Tally {
+    /// The total amount of collected coins
+    pub coins: u32,
+    /// The total amount of kills
+    pub kills: u32,
+    /// The total amount of damage that the player has done
+    pub total_damage: u32,
+    /// The total amount of damage that the player received
+    pub total_damage_received: u32,
+    /// How often the player died (lost lives and the game over)
+    pub deaths: u32,
+    /// How the level ended and when (see [crate::utils::runtime::get_time()]), `None` while it is running <br>
+    /// Only the first [GameEvent::LevelCompleted] or [GameEvent::GameOver] counts
+    pub outcome: Option<(LevelOutcome, f64)>,
+}
+
+impl Tally {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new tally that counts the events of the queue
+    pub fn subscribe(events: &mut EventQueue) -> Rc<RefCell<Self>> {
+        let tally = Rc::new(RefCell::new(Self::new()));
+        let t = tally.clone();
+        events.subscribe(move |event| t.borrow_mut().read(event));
+        tally
+    }
+
+    /// Counts the event <br>
+    /// Gets called while the frame runs, every time [crate::logic::event::EventQueue::dispatch()] sends the new events
+    pub fn read(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::CoinCollected { amount } => self.coins += amount,
+            GameEvent::EnemyKilled { damage, .. } => {
+                self.kills += 1;
+                self.total_damage += damage;
+            }
+            GameEvent::EnemyDamaged { damage, .. } => self.total_damage += damage,
+            GameEvent::PlayerDamaged { damage } => self.total_damage_received += damage,
+            GameEvent::LifeLost { .. } => self.deaths += 1,
+            GameEvent::LevelCompleted => self.end(LevelOutcome::Completed),
+            GameEvent::GameOver => {
+                if self.outcome.is_none() { self.deaths += 1; }
+                self.end(LevelOutcome::GameOver);
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, outcome: LevelOutcome) {
+        if self.outcome.is_none() {
+            self.outcome = Some((outcome, get_time()));
+        }
+    }
+
+    pub fn completed(&self) -> bool {
+        matches!(self.outcome, Some((LevelOutcome::Completed, _)))
+    }
+
+    pub fn game_over(&self) -> bool {
+        matches!(self.outcome, Some((LevelOutcome::GameOver, _)))
+    }
+
+    /// Returns true if the level was completed or the game is over
+    pub fn is_over(&self) -> bool {
+        self.outcome.is_some()
+    }
+}
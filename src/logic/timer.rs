@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+use crate::utils::runtime::get_time;
+
+/// A timer that runs for a fixed duration (in seconds) <br>
+/// Uses the clock of the level, so it stops while the level is paused (see [crate::utils::runtime::pause()])
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Timer {
+    start: f64,
+    duration: f64,
+}
+
+impl Timer {
+    /// Creates a timer that starts now
+    pub fn new(duration: f64) -> Self {
+        Self::starting_at(get_time(), duration)
+    }
+
+    /// Creates a timer that starts at the provided time
+    pub fn starting_at(start: f64, duration: f64) -> Self {
+        Self { start, duration }
+    }
+
+    /// Creates a timer that runs out after `remaining` seconds (e.g. for timers that get loaded from a save)
+    pub fn with_remaining(duration: f64, remaining: f64) -> Self {
+        Self::starting_at(get_time() - duration + remaining, duration)
+    }
+
+    /// Starts the timer again with the same duration
+    pub fn restart(&mut self) {
+        self.start = get_time();
+    }
+
+    /// Returns the time since the timer started (it keeps counting after the timer finished)
+    pub fn elapsed(&self) -> f64 {
+        (get_time() - self.start).max(0.0)
+    }
+
+    pub fn remaining(&self) -> f64 {
+        (self.duration - self.elapsed()).max(0.0)
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.duration
+    }
+
+    pub fn finished(&self) -> bool {
+        self.elapsed() > self.duration
+    }
+}
+
+/// A set of named timers (like cooldowns or status effects) <br>
+/// A timer is active from [Timers::start()] until it runs out <br>
+/// The owner calls [Timers::expired()] once per frame and pushes a [crate::logic::event::GameEvent::TimerExpired] for timers others react to
+/// (like power ups), so the HUD, the audio and the level script don't have to check the timers themselves
+#[derive(PartialEq, Clone, Debug)]
+pub struct Timers<K: Ord> {
+    timers: BTreeMap<K, Timer>,
+}
+
+impl<K: Ord + Clone> Timers<K> {
+    pub fn new() -> Self {
+        Self { timers: BTreeMap::new() }
+    }
+
+    /// Starts the timer (or restarts it if it is already running)
+    pub fn start(&mut self, key: K, duration: f64) {
+        self.timers.insert(key, Timer::new(duration));
+    }
+
+    /// Adds an already running timer (or replaces the existing one)
+    pub fn insert(&mut self, key: K, timer: Timer) {
+        self.timers.insert(key, timer);
+    }
+
+    /// Removes the timer without it expiring
+    pub fn stop(&mut self, key: &K) {
+        self.timers.remove(key);
+    }
+
+    pub fn is_active(&self, key: &K) -> bool {
+        self.timers.get(key).is_some_and(|timer| !timer.finished())
+    }
+
+    /// Returns the remaining time of the timer (`0.0` if it isn't active)
+    pub fn remaining(&self, key: &K) -> f64 {
+        self.timers.get(key).map(Timer::remaining).unwrap_or(0.0)
+    }
+
+    /// Removes all timers that ran out and returns their keys <br>
+    /// This is how the owner of the timers finds out that they ran out, so call it once per frame <br>
+    /// Every timer gets returned exactly once (timers that got stopped or restarted before they ran out never get returned)
+    pub fn expired(&mut self) -> Vec<K> {
+        let expired: Vec<K> = self.timers.iter()
+            .filter(|(_, timer)| timer.finished())
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in &expired {
+            self.timers.remove(key);
+        }
+
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::runtime;
+
+    /// Starts the simulated clock with 0.1 seconds per frame
+    fn clock() {
+        runtime::enable_headless(0.1);
+    }
+
+    fn advance(frames: u32) {
+        for _ in 0..frames {
+            runtime::advance();
+        }
+    }
+
+    #[test]
+    fn timer_counts_elapsed_and_remaining_time() {
+        clock();
+        let timer = Timer::new(1.0);
+        advance(3);
+
+        assert!((timer.elapsed() - 0.3).abs() < 1e-6);
+        assert!((timer.remaining() - 0.7).abs() < 1e-6);
+        assert!(!timer.finished());
+
+        advance(8);
+        assert!(timer.finished());
+        assert_eq!(timer.remaining(), 0.0);
+        assert!((timer.elapsed() - 1.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn timer_with_remaining_continues_where_it_stopped() {
+        clock();
+        let timer = Timer::with_remaining(2.0, 0.5);
+
+        assert!((timer.elapsed() - 1.5).abs() < 1e-6);
+        assert!((timer.remaining() - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn timers_stop_while_paused() {
+        clock();
+        let timer = Timer::new(1.0);
+        advance(2);
+        runtime::pause();
+        advance(20);
+
+        assert!((timer.elapsed() - 0.2).abs() < 1e-6);
+        assert!(!timer.finished());
+
+        runtime::resume();
+        advance(9);
+        assert!(timer.finished());
+    }
+
+    #[test]
+    fn expired_returns_every_timer_once() {
+        clock();
+        let mut timers = Timers::new();
+        timers.start("short", 0.15);
+        timers.start("long", 1.0);
+        timers.start("stopped", 0.15);
+        timers.stop(&"stopped");
+        advance(2);
+
+        assert_eq!(timers.expired(), vec!["short"]);
+        assert!(timers.expired().is_empty());
+        assert!(timers.is_active(&"long"));
+        assert_eq!(timers.remaining(&"short"), 0.0);
+
+        advance(10);
+        assert_eq!(timers.expired(), vec!["long"]);
+    }
+
+    #[test]
+    fn restarting_a_timer_delays_its_expiry() {
+        clock();
+        let mut timers = Timers::new();
+        timers.start("cooldown", 0.25);
+        advance(2);
+        timers.start("cooldown", 0.25);
+        advance(2);
+
+        assert!(timers.expired().is_empty());
+        assert!((timers.remaining(&"cooldown") - 0.05).abs() < 1e-6);
+    }
+}
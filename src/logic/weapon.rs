@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use crate::logic::ecs::components::ProjectileBehaviour;
+use crate::utils::enums::TextureKey;
+
+// The player can carry multiple weapons and switches between them (see [crate::logic::input::InputAction::NextWeapon]).
+// Every weapon is defined by a [Weapon], so a new weapon only needs a new [WeaponKind] and its definition.
+// Power ups change all weapons the same way (see [crate::logic::power_up::Modifiers]).
+
+#[derive(PartialEq, Eq, Clone, Copy, Ord, PartialOrd, Default, Serialize, Deserialize, Debug)]
+pub enum WeaponKind {
+    /// The weapon every player starts with
+    #[default]
+    Blaster,
+    /// Fires a spread of short-lived pellets
+    Shotgun,
+    /// Fires slow projectiles that do a lot of damage and fly through enemies
+    HeavyShot,
+    /// Fires projectiles that fly in an arc and bounce off platforms
+    Bouncer,
+    /// Fires projectiles that follow the nearest enemy
+    Seeker,
+}
+
+/// Everything that makes up a weapon
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Weapon {
+    pub projectile_texture_key: TextureKey,
+    /// The size of a projectile in pixels (gets multiplied with the GUI scale)
+    pub projectile_size: f32,
+    /// The speed of a projectile in pixels per second (gets multiplied with the GUI scale)
+    pub speed: f32,
+    /// The damage of one projectile
+    pub damage: i16,
+    /// Shots per second
+    pub fire_rate: f64,
+    /// The angle between the outermost projectiles of one shot (in degrees)
+    pub spread: f32,
+    /// The amount of projectiles of one shot
+    pub count: u32,
+    /// The time until a projectile disappears (in seconds)
+    pub lifetime: f64,
+    /// The ammo a pickup of the weapon contains, `None` if the weapon never runs out of ammo
+    pub ammo: Option<u32>,
+    /// How the projectiles fly and what happens when they hit something
+    pub behaviour: ProjectileBehaviour,
+}
+
+impl WeaponKind {
+    pub fn weapon(&self) -> Weapon {
+        match self {
+            WeaponKind::Blaster => Weapon {
+                projectile_texture_key: TextureKey::Projectile0,
+                projectile_size: 32.0,
+                speed: 2000.0,
+                damage: 200,
+                fire_rate: 20.0,
+                spread: 0.0,
+                count: 1,
+                lifetime: 4.0,
+                ammo: None,
+                behaviour: ProjectileBehaviour::default(),
+            },
+            WeaponKind::Shotgun => Weapon {
+                projectile_texture_key: TextureKey::Projectile0,
+                projectile_size: 24.0,
+                speed: 1800.0,
+                damage: 120,
+                fire_rate: 2.5,
+                spread: 30.0,
+                count: 5,
+                lifetime: 0.5,
+                ammo: Some(12),
+                behaviour: ProjectileBehaviour::default(),
+            },
+            WeaponKind::HeavyShot => Weapon {
+                projectile_texture_key: TextureKey::Projectile1,
+                projectile_size: 72.0,
+                speed: 900.0,
+                damage: 800,
+                fire_rate: 1.25,
+                spread: 0.0,
+                count: 1,
+                lifetime: 4.0,
+                ammo: Some(8),
+                behaviour: ProjectileBehaviour { pierce: 2, ..Default::default() },
+            },
+            WeaponKind::Bouncer => Weapon {
+                projectile_texture_key: TextureKey::Projectile0,
+                projectile_size: 40.0,
+                speed: 1400.0,
+                damage: 250,
+                fire_rate: 3.0,
+                spread: 0.0,
+                count: 1,
+                lifetime: 3.0,
+                ammo: Some(15),
+                behaviour: ProjectileBehaviour { gravity: 3000.0, bounces: 3, ..Default::default() },
+            },
+            WeaponKind::Seeker => Weapon {
+                projectile_texture_key: TextureKey::Projectile0,
+                projectile_size: 32.0,
+                speed: 1200.0,
+                damage: 300,
+                fire_rate: 4.0,
+                spread: 0.0,
+                count: 1,
+                lifetime: 3.0,
+                ammo: Some(20),
+                behaviour: ProjectileBehaviour { homing: 240.0, ..Default::default() },
+            },
+        }
+    }
+
+    /// Returns the index of the weapon in [TextureKey::Weapons0]
+    pub fn texture_index(&self) -> usize {
+        match self {
+            WeaponKind::Blaster => 0,
+            WeaponKind::Shotgun => 1,
+            WeaponKind::HeavyShot => 2,
+            WeaponKind::Bouncer => 3,
+            WeaponKind::Seeker => 4,
+        }
+    }
+}
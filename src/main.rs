@@ -2,13 +2,14 @@ mod utils;
 mod scenes;
 mod logic;
 mod ui;
+mod simulation;
 
 use std::collections::BTreeMap;
 use std::fs;
-use std::io::Write;
+use std::process::exit;
 use dirs::config_dir;
 use crate::utils::mathemann::stretch_float_to;
-use crate::utils::text::{draw_text_center, draw_text_centered};
+use crate::utils::text::{self, draw_text_center, draw_text_centered};
 use macroquad::prelude::*;
 use utils::enums::{Scene, SceneTextureKey, TextureKey};
 use crate::scenes::level_selector::level_selector;
@@ -17,8 +18,13 @@ use crate::scenes::main_menu::main_menu;
 use logic::level::LevelSceneData;
 use utils::structs::{Settings, TempSettings};
 use crate::scenes::settings_menu::settings_menu;
-use logic::level::PersistentLevelData;
 use crate::scenes::credits::credits;
+use crate::scenes::profile_selector::{profile_selector, ProfileMenu};
+use crate::utils::profile::Profiles;
+use crate::utils::runtime;
+use crate::utils::audio::Audio;
+use crate::utils::i18n;
+use crate::ui::notice;
 
 fn window_conf() -> Conf {
     Conf {
@@ -31,106 +37,33 @@ fn window_conf() -> Conf {
     }
 }
 
-#[macroquad::main(window_conf)]
-async fn main() {
-    let loading_handler = loading();
-
-    let mut settings = {
-        let config_path = format!("{}/JumboMumbo", config_dir().expect("Couldn't get config path").to_str().unwrap().to_owned());
-
-        if !fs::exists(&config_path).unwrap() {
-            fs::create_dir(&config_path).expect("Couldn't create settings directory");
-            println!("Creating settings directory at: {}", config_path);
-        }
-        println!("Config path: {}", config_path);
-
-        let settings = {
-            let file_path = format!("{}/settings.json", config_path);
-            println!("Settings file path: {}", file_path);
-            match fs::exists(&file_path).unwrap() {
-                true => {
-                    let new_settings = {
-                        let level_data_path = format!("{}/level_data.json", config_path);
-                        Settings::new(file_path.to_owned(), level_data_path).await
-                    };
-
-                    let file = fs::File::open(&file_path).expect("Couldn't open settings file");
-
-                    let settings: Settings = serde_json::from_reader(file).unwrap_or_else(|e| {
-                        println!("Couldn't deserialize persistent level data with error \"{}\"", e);
-                        for i in 0.. {
-                            let path = file_path.replace(".json", format!(".{i}.json").as_str());
-                            if !fs::exists(&path).unwrap() {
-                                match fs::rename(&file_path, &path) {
-                                    Ok(_) => {
-                                        println!("Moved old settings file to {path}");
-                                        break;
-                                    },
-                                    Err(_) => {
-                                        println!("Couldn't move old level data file to \"{}\"! Ignoring...", path);
-                                        break;
-                                    }
-                                }
-                            }
-                        }
-                        new_settings
-                    });
-
-                    settings
-                },
-                false => {
-                    let level_data_path = format!("{}/level_data.json", config_path);
-                    let settings = Settings::new(file_path.to_owned(), level_data_path).await;
-                    let mut file = fs::File::create(&file_path).unwrap();
-
-                    let s_settings = serde_json::to_string_pretty(&settings).expect("Couldn't serialize settings");
-                    file.write_all(s_settings.as_bytes()).expect("Couldn't write settings file");
-
-                    settings
-                }
-            }
-        };
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
 
-        settings
-    };
-    let mut temp_settings = TempSettings { settings: settings.clone() };
-    println!("{:?}", settings);
+    // Simulate a level without opening a window
+    if args.iter().any(|arg| arg == "--headless") {
+        exit(runtime::block_on(simulation::headless::run(&args)));
+    }
 
-    let mut persistent_level_data = {
-        match fs::exists(&settings.level_data_path).unwrap() {
-            true => {
-                let file = fs::File::open(&settings.level_data_path).expect("Couldn't open level data file");
-                let persistent_level_data: PersistentLevelData = serde_json::from_reader(file).unwrap_or_else(|e| {
-                    println!("Couldn't deserialize persistent level data with error \"{}\"", e);
-                    for i in 0.. {
-                        let path = settings.level_data_path.replace(".json", format!(".{i}.json").as_str());
-                        if !fs::exists(&path).unwrap() {
-                            match fs::rename(&settings.level_data_path, &path) {
-                                Ok(_) => {
-                                    println!("Moved old level data file to {path}");
-                                    break;
-                                },
-                                Err(_) => {
-                                    println!("Couldn't move old level data file to \"{}\"! Ignoring...", path);
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                    PersistentLevelData::new()
-                });
+    macroquad::Window::from_config(window_conf(), game());
+}
 
-                persistent_level_data
-            }
-            false => {
-                let persistent_level_data = PersistentLevelData::new();
+async fn game() {
+    text::load_font().await;
+    let loading_handler = loading();
 
-                persistent_level_data.save(&settings).await;
+    let config_path = format!("{}/JumboMumbo", config_dir().expect("Couldn't get config path").to_str().unwrap().to_owned());
+    if !fs::exists(&config_path).unwrap() {
+        fs::create_dir(&config_path).expect("Couldn't create settings directory");
+        println!("Creating settings directory at: {}", config_path);
+    }
+    println!("Config path: {}", config_path);
 
-                persistent_level_data
-            }
-        }
-    };
+    // Every profile has its own settings and level data
+    let mut profiles = Profiles::load(&config_path).await;
+    let (mut settings, mut persistent_level_data) = profiles.load_current().await;
+    let mut temp_settings = TempSettings::new(settings.clone());
+    println!("{:?}", settings);
 
     // Runs to make sure the screen size is the right one
     for _ in 0..4 {
@@ -138,23 +71,28 @@ async fn main() {
     }
     println!("Resolution: {}x{}", screen_width(), screen_height());
 
-    // Holds the current scene
-    let mut scene = Scene::MainMenu;
+    // Holds the current scene (the profile picker is shown first if there is more than one profile)
+    let mut scene = if profiles.names.len() > 1 { Scene::Profiles } else { Scene::MainMenu };
+    let mut profile_menu = ProfileMenu::new();
     // Holds all data of scenes (score, enemies ...)
     let mut level_scene_data = LevelSceneData::empty().await;
     // Holds all textures
     let mut textures = BTreeMap::<SceneTextureKey, BTreeMap<TextureKey, Vec<Texture2D>>>::new();
 
+    let mut audio = Audio::load().await;
+
     loading_handler.await;
 
     loop {
         clear_background(BLACK);
+        // Texts are shown in the language of the current profile (changes with the settings and the profile)
+        i18n::set_language(&settings.language);
         // Depending on the Scene does something else
         match scene {
             Scene::MainMenu => {
-                main_menu(&mut scene, &mut textures, &settings).await;
+                main_menu(&mut scene, &mut textures, &mut level_scene_data, &persistent_level_data, &profiles, &settings).await;
             }
-            Scene::SettingsMenu => {
+            Scene::SettingsMenu(_) => {
                 settings_menu(&mut scene, &mut textures, &mut settings, &mut temp_settings).await;
             }
             Scene::LevelSelector(_) => {
@@ -166,8 +104,18 @@ async fn main() {
             Scene::Credits(_) => {
                 credits(&mut scene, &settings).await;
             }
+            Scene::Profiles => {
+                profile_selector(&mut scene, &mut textures, &mut profile_menu, &mut profiles, &mut settings, &mut temp_settings, &mut persistent_level_data).await;
+            }
         }
 
+        // Sound effects of the level (they subscribe to its events) and the music of the current scene
+        audio.play_queued(level_scene_data.level_data.player.as_ref().map(|player| player.pos), &settings);
+        audio.update_music(&scene, &settings);
+
+        // Tell the player about things like save files that got reset
+        notice::draw(settings.gui_scale).await;
+
         next_frame().await
     };
 }
@@ -211,8 +159,8 @@ async fn loading() {
             last_opacity = alpha;
         }
 
-        draw_text_center("Welcome to JumboMumbo!", screen_height() / 8.0, Color::from_rgba(255, 255, 255, 255)).await;
-        draw_text_centered("Loading...", screen_height() / 4.0, screen_height() / 16.0, Color::from_rgba(255, 255, 255, alpha)).await;
+        draw_text_center(&i18n::text("loading.welcome"), screen_height() / 8.0, Color::from_rgba(255, 255, 255, 255)).await;
+        draw_text_centered(&i18n::text("loading.loading"), screen_height() / 4.0, screen_height() / 16.0, Color::from_rgba(255, 255, 255, alpha)).await;
 
         { // Loading Bar
             let width = screen_width() / 4.0;
@@ -1,12 +1,14 @@
 use macroquad::camera::{set_camera, set_default_camera, Camera2D};
-use macroquad::color::WHITE;
+use macroquad::color::{DARKGRAY, WHITE};
 use macroquad::input::{is_key_pressed, mouse_wheel, KeyCode};
 use macroquad::math::Rect;
 use macroquad::time::get_frame_time;
 use macroquad::window::{screen_height, screen_width};
+use crate::logic::level::Level;
 use crate::utils::enums::Scene;
+use crate::utils::i18n;
 use crate::utils::structs::Settings;
-use crate::utils::text::draw_text_centered;
+use crate::utils::text::{draw_text_box, TextAlign, TextStyle};
 
 pub async fn credits(scene: &mut Scene, settings: &Settings) {
     if is_key_pressed(KeyCode::Escape) {
@@ -24,21 +26,24 @@ pub async fn credits(scene: &mut Scene, settings: &Settings) {
     }
 
     let font_size = 128.0 * settings.gui_scale;
-    draw_text_centered("Credits (Scrolling)", screen_height() / 8.0, font_size, WHITE).await;
+    let title_style = TextStyle { align: TextAlign::Center, shadow: Some(DARKGRAY), ..TextStyle::new(font_size, WHITE) };
+    draw_text_box(&i18n::text("credits.title"), screen_width() / 2.0, screen_height() / 8.0, screen_width(), &title_style, None);
 
     let mut current_y = screen_height() / 8.0 + font_size + screen_height() / 16.0;
-    let font_size = 96.0 * settings.gui_scale;
+    let style = TextStyle { align: TextAlign::Center, shadow: Some(DARKGRAY), ..TextStyle::new(96.0 * settings.gui_scale, WHITE) };
+    // Long lines wrap, so every entry can take more than one line
+    let max_width = screen_width() * 0.8;
 
-    draw_text_centered("Play testing - Gopiler", current_y, font_size, WHITE).await;
-    current_y += font_size * 2.0;
-    draw_text_centered("Player textures - Dinno", current_y, font_size, WHITE).await;
-    current_y += font_size * 2.0;
-    draw_text_centered("Fireball texture - Dinno", current_y, font_size, WHITE).await;
-    current_y += font_size * 2.0;
-    draw_text_centered("Enemy texture - Dinno", current_y, font_size, WHITE).await;
-    current_y += font_size * 2.0;
-    draw_text_centered("Level 2 Design - Kinglui2000 & Gopiler", current_y, font_size, WHITE).await;
-    current_y += font_size * 2.0;
-    draw_text_centered("Level 3 Design - Fossombrome", current_y, font_size, WHITE).await;
-
-}
\ No newline at end of file
+    let entries = [
+        i18n::text_with("credits.play_testing", &[("names", "Gopiler".to_string())]),
+        i18n::text_with("credits.player_textures", &[("names", "Dinno".to_string())]),
+        i18n::text_with("credits.fireball_texture", &[("names", "Dinno".to_string())]),
+        i18n::text_with("credits.enemy_texture", &[("names", "Dinno".to_string())]),
+        i18n::text_with("credits.level_design", &[("level", Level::Level2.name()), ("names", "Kinglui2000 & Gopiler".to_string())]),
+        i18n::text_with("credits.level_design", &[("level", Level::Level3.name()), ("names", "Fossombrome".to_string())]),
+        i18n::text_with("credits.font", &[("names", "Mounir Tohami".to_string())]),
+    ];
+    for entry in entries {
+        current_y += draw_text_box(&entry, screen_width() / 2.0, current_y, max_width, &style, None) + style.font_size * 0.75;
+    }
+}
@@ -1,19 +1,20 @@
 use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime};
 use macroquad::color::WHITE;
 use macroquad::input::MouseButton;
 use macroquad::math::vec2;
 use macroquad::prelude::{is_key_pressed, screen_height, screen_width, KeyCode, Texture2D};
-use macroquad::text::measure_text;
 use crate::logic::level::{Level, PersistentLevelData};
 use crate::utils::enums::{Scene, SceneTextureKey, TextureKey};
+use crate::utils::i18n;
 use crate::ui::buttons::Button;
 use crate::utils::structs::Settings;
-use crate::utils::text::draw_text_centered;
+use crate::utils::text::{draw_text_centered, measure_str};
 use crate::utils::texture::load_textures;
 
 pub async fn level_selector(scene: &mut Scene, textures: &mut BTreeMap<SceneTextureKey, BTreeMap<TextureKey, Vec<Texture2D>>>, settings: &Settings, persistent_level_data: &PersistentLevelData) {
     if !textures.contains_key(&SceneTextureKey::LevelSelector) {
-        textures.insert(SceneTextureKey::LevelSelector, load_textures("Level Selector", [TextureKey::Button0].to_vec()).await);
+        textures.insert(SceneTextureKey::LevelSelector, load_textures(&i18n::text("menu.levels"), [TextureKey::Button0].to_vec()).await);
     }
 
     if is_key_pressed(KeyCode::Escape) {
@@ -94,7 +95,7 @@ async fn level_page(level: Level, button_texture_key: TextureKey, textures: &BTr
     let mut scene = Scene::LevelSelector(1);
 
     draw_text_centered(
-        level.name(),
+        &level.name(),
         screen_height() / 8.0,
         128.0 * settings.gui_scale,
         WHITE
@@ -103,41 +104,52 @@ async fn level_page(level: Level, button_texture_key: TextureKey, textures: &BTr
     { // Stats
         let (plays_text, total_deaths_text, high_coins_text, high_kills_text) = match persistent_level_data.stats.get(&level) {
             Some(stats) => {
-                let plays_text = format!("Plays: {}", stats.plays);
-                let total_deaths_text = format!("Deaths: {}", stats.deaths);
-                let high_coins_text = format!("Max. collected Coins: {}", stats.coins_high);
-                let high_kills_text = format!("Max. killed Enemies: {}", stats.kills_high);
+                let plays_text = i18n::text_with("level_selector.plays", &[("count", stats.plays.to_string())]);
+                let total_deaths_text = i18n::text_with("level_selector.deaths", &[("count", stats.deaths.to_string())]);
+                let high_coins_text = i18n::text_with("level_selector.coins_high", &[("count", stats.coins_high.to_string())]);
+                let high_kills_text = i18n::text_with("level_selector.kills_high", &[("count", stats.kills_high.to_string())]);
 
                 (plays_text, total_deaths_text, high_coins_text, high_kills_text)
             }
             None => {
-                let plays_text = "Plays: None".to_string();
-                let total_deaths_text = "Deaths: None".to_string();
-                let high_coins_text = "Max. collected Coins: None".to_string();
-                let high_kills_text = "Max. killed Enemies: None".to_string();
+                let none = [("count", i18n::text("common.none"))];
+                let plays_text = i18n::text_with("level_selector.plays", &none);
+                let total_deaths_text = i18n::text_with("level_selector.deaths", &none);
+                let high_coins_text = i18n::text_with("level_selector.coins_high", &none);
+                let high_kills_text = i18n::text_with("level_selector.kills_high", &none);
 
                 (plays_text, total_deaths_text, high_coins_text, high_kills_text)
             }
         };
 
-        let font_size = (64.0 * settings.gui_scale) as _;
-        let plays_text_mes = measure_text(&plays_text, None, font_size, 1.0);
-        let total_deaths_text_mes = measure_text(&total_deaths_text, None, font_size, 1.0);
-        let high_coins_text_mes = measure_text(&high_coins_text, None, font_size, 1.0);
-        let high_kills_text_mes = measure_text(&high_kills_text, None, font_size, 1.0);
+        let week_ago = SystemTime::now() - Duration::from_secs(7 * 24 * 60 * 60);
+        let week_plays_text = i18n::text_with("level_selector.plays_week", &[("count", persistent_level_data.scores.count_since(&level, week_ago).to_string())]);
+        let best_time_text = match persistent_level_data.scores.best_time(&level) {
+            Some(best_time) => i18n::text_with("level_selector.best_time", &[("seconds", format!("{:.2}", best_time))]),
+            None => i18n::text("level_selector.best_time_none"),
+        };
+
+        let font_size = 64.0 * settings.gui_scale;
+        let texts = [plays_text, week_plays_text, total_deaths_text, high_coins_text, high_kills_text, best_time_text];
+        let measurements: Vec<_> = texts.iter().map(|text| measure_str(text, font_size)).collect();
 
         let mut y = {
-            let total_height = plays_text_mes.height + total_deaths_text_mes.height + high_coins_text_mes.height + high_kills_text_mes.height + screen_height() / 64.0 * 3.0;
+            let total_height = measurements.iter().map(|mes| mes.height).sum::<f32>() + screen_height() / 64.0 * (texts.len() - 1) as f32;
             screen_height() / 2.0 - total_height / 2.0
         };
 
-        draw_text_centered(&plays_text, y + plays_text_mes.offset_y, font_size as f32, WHITE).await;
-        y += plays_text_mes.height + screen_height() / 64.0;
-        draw_text_centered(&total_deaths_text, y + total_deaths_text_mes.offset_y, font_size as f32, WHITE).await;
-        y += total_deaths_text_mes.height + screen_height() / 64.0;
-        draw_text_centered(&high_coins_text, y + high_coins_text_mes.offset_y, font_size as f32, WHITE).await;
-        y += high_coins_text_mes.height + screen_height() / 64.0;
-        draw_text_centered(&high_kills_text, y + high_kills_text_mes.offset_y, font_size as f32, WHITE).await;
+        for (text, mes) in texts.iter().zip(measurements) {
+            draw_text_centered(text, y + mes.offset_y, font_size, WHITE).await;
+            y += mes.height + screen_height() / 64.0;
+        }
+    }
+
+    if !persistent_level_data.is_unlocked(&level) {
+        let previous = Level::from_number(level.level_selector_page() - 1).unwrap();
+        draw_text_centered(&i18n::text("level_selector.locked"), screen_height() - screen_height() / 8.0 - 100.0 * settings.gui_scale, 96.0 * settings.gui_scale, WHITE).await;
+        draw_text_centered(&i18n::text_with("level_selector.unlock_hint", &[("level", previous.name())]), screen_height() - screen_height() / 8.0, 48.0 * settings.gui_scale, WHITE).await;
+
+        return scene;
     }
 
     let size = vec2(400.0, 150.0) * settings.gui_scale;
@@ -148,7 +160,7 @@ async fn level_page(level: Level, button_texture_key: TextureKey, textures: &BTr
         button_pos,
         size,
         border_size,
-        "Play".to_string(),
+        i18n::text("level_selector.play"),
         64.0 * settings.gui_scale,
         button_texture_key,
     ).await;
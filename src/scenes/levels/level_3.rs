@@ -4,15 +4,17 @@ use macroquad::input::{is_key_down, is_key_pressed, KeyCode};
 use macroquad::math::vec2;
 use macroquad::prelude::{clear_background, Texture2D};
 use macroquad_platformer::World;
+use crate::logic::input::PlayerInput;
 use crate::logic::level;
-use crate::logic::level::{Level, LevelData, LevelSceneData, PersistentLevelData, Trigger};
+use crate::logic::level::{Level, LevelData, LevelSceneData, PersistentLevelData};
+use crate::scenes::levels::levels;
 use crate::utils::debugger;
-use crate::utils::enums::{Scene, SceneTextureKey, TextureKey};
+use crate::utils::enums::{SceneTextureKey, TextureKey};
 use crate::utils::mapper::level_map_from_image;
 use crate::utils::structs::Settings;
 use crate::utils::texture::load_level_textures;
 
-pub async fn level_3(scene: &mut Scene, textures: &mut BTreeMap<SceneTextureKey, BTreeMap<TextureKey, Vec<Texture2D>>>, level_scene_data: &mut LevelSceneData, persistent_level_data: &mut PersistentLevelData, settings: &Settings) {
+pub async fn level_3(textures: &mut BTreeMap<SceneTextureKey, BTreeMap<TextureKey, Vec<Texture2D>>>, level_scene_data: &mut LevelSceneData, persistent_level_data: &mut PersistentLevelData, settings: &Settings) {
     clear_background(DARKBLUE);
 
     // Load textures if not loaded already
@@ -25,64 +27,59 @@ pub async fn level_3(scene: &mut Scene, textures: &mut BTreeMap<SceneTextureKey,
             TextureKey::Enemy0,
             TextureKey::PowerUps0,
             TextureKey::Cannon0,
-            TextureKey::Projectile0
+            TextureKey::Projectile0,
+            TextureKey::Hearts0,
+            TextureKey::PowerUps1,
+            TextureKey::Projectile1,
+            TextureKey::Weapons0
         ].to_vec();
-        textures.insert(SceneTextureKey::Level3, load_level_textures("Level 3", keys).await);
+        textures.insert(SceneTextureKey::Level3, load_level_textures(&Level::Level3.name(), keys).await);
     }
 
     // Load scene data for right level
     if level_scene_data.level_data.level != Some(Level::Level3) {
         *level_scene_data = layout(settings).await;
-    }
-
-    if is_key_pressed(KeyCode::Escape) {
-        level_scene_data.escape(persistent_level_data, settings, scene).await;
-        textures.remove(&SceneTextureKey::Level1);
-        return;
+        level_scene_data.load_ghost(persistent_level_data).await;
     }
 
     if is_key_down(KeyCode::LeftControl) && is_key_pressed(KeyCode::R) {
         level_scene_data.level_data.save(persistent_level_data, settings).await;
         *level_scene_data = layout(settings).await;
+        level_scene_data.load_ghost(persistent_level_data).await;
     }
 
     let textures = textures.get(&SceneTextureKey::Level3).unwrap();
-    let width = 128.0 * settings.gui_scale;
-
-    let mut level_data = level_scene_data.level_data.clone(); // Temporary level data
-    let mut world = &mut level_scene_data.world;
-    let mut player = level_data.player.clone().unwrap();
 
-    player.control(&mut world, &mut level_data, settings).await;
+    let input = PlayerInput::from_keyboard(&settings.keybindings).await;
+    levels::step_level(level_scene_data, &input, settings).await;
 
-    level_data.player = Some(player);
-    level_scene_data.level_data = level_data;
+    let over = level_scene_data.level_data.tally.borrow().is_over();
 
-    if level_scene_data.level_data.player.as_ref().unwrap().pos.x > -0.0 * width { level_scene_data.level_data.triggers.insert(Trigger::LevelCompleted, true); }
-    let won = *level_scene_data.level_data.triggers.get(&Trigger::LevelCompleted).unwrap_or(&false);
+    level::render_level(level_scene_data, textures, settings).await;
 
-    let game_over = level_scene_data.level_data.triggers.get(&Trigger::GameOver).unwrap_or(&false).to_owned();
-
-    if !game_over && !won { level::tick_level(level_scene_data, settings).await; }
-    level::render_level(level_scene_data, &textures, settings).await;
-
-    if !game_over && !won {
-        debugger::check(&mut level_scene_data.level_data.triggers, &mut level_scene_data.level_data.trigger_locks).await;
+    if !over {
+        debugger::check(&mut level_scene_data.level_data.debug_views).await;
         debugger::render(level_scene_data, settings).await;
     }
+}
 
+/// Checks the rules of the level (like winning and dying) <br>
+/// Gets called every frame by [levels::step_level()]
+pub async fn rules(level_scene_data: &mut LevelSceneData, settings: &Settings) {
+    let width = 128.0 * settings.gui_scale;
+    if level_scene_data.level_data.player.as_ref().unwrap().pos.x > -0.0 * width { level_scene_data.level_data.complete().await; }
     if level_scene_data.level_data.player.as_ref().unwrap().pos.y > 2.0 * (128.0 * settings.gui_scale) {
         level_scene_data.level_data.player.as_mut().unwrap().health = 0;
     }
 }
 
-async fn layout(settings: &Settings) -> LevelSceneData {
+pub async fn layout(settings: &Settings) -> LevelSceneData {
     let mut world = World::new();
     let width = 128.0 * settings.gui_scale;
     let height = 128.0 * settings.gui_scale;
     let size = vec2(width, height);
 
-    let (player, platforms, collectibles, enemies, cannons, power_ups) = level_map_from_image(
+    let (player, entities) = level_map_from_image(
         Level::Level3.path().to_string(),
         size,
         settings,
@@ -99,11 +96,7 @@ async fn layout(settings: &Settings) -> LevelSceneData {
         LevelData::new(
             Level::Level3,
             player,
-            platforms,
-            collectibles,
-            enemies,
-            cannons,
-            power_ups
+            entities
         ).await,
         world
     ).await
@@ -1,25 +1,176 @@
+use std::collections::btree_map::Entry;
 use std::collections::BTreeMap;
+use macroquad::camera::set_default_camera;
+use macroquad::input::{is_key_down, is_key_pressed, KeyCode};
 use macroquad::prelude::Texture2D;
+use crate::logic::input::PlayerInput;
+use crate::logic::level;
 use crate::logic::level::{Level, LevelSceneData, PersistentLevelData};
+use crate::logic::snapshot::LevelSnapshot;
 use crate::utils::structs::Settings;
+use crate::scenes::levels::level_0;
 use crate::scenes::levels::level_0::level_0;
+use crate::scenes::levels::level_1;
 use crate::scenes::levels::level_1::level_1;
+use crate::scenes::levels::level_2;
 use crate::scenes::levels::level_2::level_2;
+use crate::scenes::levels::level_3;
 use crate::scenes::levels::level_3::level_3;
+use crate::scenes::levels::pause_menu;
+use crate::scenes::levels::pause_menu::{pause_menu, PauseAction};
+use crate::scenes::levels::result_screen::{result_screen, ResultAction};
 use crate::utils::enums::{Scene, SceneTextureKey, TextureKey};
+use crate::ui::notice;
+use crate::utils::{i18n, runtime};
 
 pub async fn start_level(scene: &mut Scene, textures: &mut BTreeMap<SceneTextureKey, BTreeMap<TextureKey, Vec<Texture2D>>>, level_scene_data: &mut LevelSceneData, persistent_level_data: &mut PersistentLevelData, settings: &Settings) {
-    match scene {
-        Scene::Level(level) => {
-            match level {
-                Level::Level0 => {
-                    level_0(scene, textures, level_scene_data, persistent_level_data, &settings).await;
-                }
-                Level::Level1 => level_1(scene, textures, level_scene_data, persistent_level_data, &settings).await,
-                Level::Level2 => level_2(scene, textures, level_scene_data, persistent_level_data, &settings).await,
-                Level::Level3 => level_3(scene, textures, level_scene_data, persistent_level_data, &settings).await,
+    let level = match scene {
+        Scene::Level(level) => *level,
+        _ => return,
+    };
+    let over = level_scene_data.level_data.tally.borrow().is_over();
+
+    // Escape leaves a level that is over, otherwise it opens or closes the pause menu (like P)
+    if is_key_pressed(KeyCode::Escape) && over {
+        quit(level, scene, textures, level_scene_data, persistent_level_data, settings).await;
+        return;
+    }
+    if (is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::P)) && !over {
+        match runtime::is_paused() {
+            true => runtime::resume(),
+            false => runtime::pause(),
+        }
+    }
+
+    // Save the level and quit (Ctrl + S), it can be continued from the main menu
+    if is_key_down(KeyCode::LeftControl) && is_key_pressed(KeyCode::S) && level_scene_data.level_data.level.is_some() && !over
+        && save_and_quit(scene, textures, level_scene_data, settings).await {
+        return;
+    }
+
+    match level {
+        Level::Level0 => level_0(textures, level_scene_data, persistent_level_data, settings).await,
+        Level::Level1 => level_1(textures, level_scene_data, persistent_level_data, settings).await,
+        Level::Level2 => level_2(textures, level_scene_data, persistent_level_data, settings).await,
+        Level::Level3 => level_3(textures, level_scene_data, persistent_level_data, settings).await,
+    }
+
+    // The level could have been restarted (Ctrl + R) in this frame
+    let over = level_scene_data.level_data.tally.borrow().is_over();
+    if !runtime::is_paused() && !over { return; }
+
+    if let Entry::Vacant(entry) = textures.entry(SceneTextureKey::LevelMenus) {
+        entry.insert(pause_menu::load_textures().await);
+    }
+
+    if over {
+        match result_screen(textures.get(&SceneTextureKey::LevelMenus).unwrap(), &level_scene_data.level_data, persistent_level_data, settings).await {
+            Some(ResultAction::Retry) => {
+                level_scene_data.level_data.save(persistent_level_data, settings).await;
+                *level_scene_data = layout(level, settings).await;
+                level_scene_data.load_ghost(persistent_level_data).await;
+            }
+            // The next level gets loaded by its scene in the next frame
+            Some(ResultAction::NextLevel) => {
+                quit(level, scene, textures, level_scene_data, persistent_level_data, settings).await;
+                *scene = Scene::Level(level.next().unwrap());
             }
+            Some(ResultAction::Back) => quit(level, scene, textures, level_scene_data, persistent_level_data, settings).await,
+            None => {}
         }
-        _ => return,
+        return;
+    }
+
+    match pause_menu(textures.get(&SceneTextureKey::LevelMenus).unwrap(), settings).await {
+        Some(PauseAction::Resume) => runtime::resume(),
+        Some(PauseAction::Restart) => {
+            runtime::resume();
+            level_scene_data.level_data.save(persistent_level_data, settings).await;
+            *level_scene_data = layout(level, settings).await;
+            level_scene_data.load_ghost(persistent_level_data).await;
+        }
+        // The level stays paused and continues when the settings get closed
+        Some(PauseAction::Settings) => {
+            set_default_camera();
+            *scene = Scene::SettingsMenu(Some(level));
+        }
+        // The level stays paused if it couldn't be saved
+        Some(PauseAction::SaveAndQuit) => { save_and_quit(scene, textures, level_scene_data, settings).await; }
+        Some(PauseAction::Quit) => quit(level, scene, textures, level_scene_data, persistent_level_data, settings).await,
+        None => {}
+    }
+}
+
+/// Saves the level and goes back to the main menu, where it can be continued <br>
+/// Returns whether the level got saved, otherwise the player stays in the level and gets told
+async fn save_and_quit(scene: &mut Scene, textures: &mut BTreeMap<SceneTextureKey, BTreeMap<TextureKey, Vec<Texture2D>>>, level_scene_data: &mut LevelSceneData, settings: &Settings) -> bool {
+    if !LevelSnapshot::capture(level_scene_data, settings).await.save(settings).await {
+        notice::push(i18n::text("notice.level_not_left"));
+        return false;
+    }
+
+    runtime::resume();
+    *scene = Scene::MainMenu;
+    *level_scene_data = LevelSceneData::empty().await;
+    textures.remove(&SceneTextureKey::LevelMenus);
+    set_default_camera();
+    true
+}
+
+/// Saves the score of the level and goes back to the level selector
+async fn quit(level: Level, scene: &mut Scene, textures: &mut BTreeMap<SceneTextureKey, BTreeMap<TextureKey, Vec<Texture2D>>>, level_scene_data: &mut LevelSceneData, persistent_level_data: &mut PersistentLevelData, settings: &Settings) {
+    level_scene_data.escape(persistent_level_data, settings, scene).await;
+    textures.remove(&SceneTextureKey::LevelMenus);
+    textures.remove(&match level {
+        Level::Level0 => SceneTextureKey::Level0,
+        Level::Level1 => SceneTextureKey::Level1,
+        Level::Level2 => SceneTextureKey::Level2,
+        Level::Level3 => SceneTextureKey::Level3,
+    });
+}
+
+/// Creates the [LevelSceneData] of the provided [Level]
+pub async fn layout(level: Level, settings: &Settings) -> LevelSceneData {
+    match level {
+        Level::Level0 => level_0::layout(settings).await,
+        Level::Level1 => level_1::layout(settings).await,
+        Level::Level2 => level_2::layout(settings).await,
+        Level::Level3 => level_3::layout(settings).await,
+    }
+}
+
+/// Runs the logic of the current level for one frame without rendering anything <br>
+/// This moves the player, checks the rules of the level and ticks everything else
+pub async fn step_level(level_scene_data: &mut LevelSceneData, input: &PlayerInput, settings: &Settings) {
+    // Cleared even while paused, so nothing reacts to the events of an older frame twice
+    level_scene_data.level_data.events.clear();
+
+    if runtime::is_paused() { return; }
+
+    // The player gets taken out of the level data while it moves, so it can borrow the rest of the level
+    let mut player = level_scene_data.level_data.player.take().unwrap();
+    player.control(&mut level_scene_data.world, &mut level_scene_data.level_data, input, settings).await;
+    level_scene_data.level_data.player = Some(player);
+
+    match level_scene_data.level_data.level.unwrap() {
+        Level::Level0 => level_0::rules(level_scene_data, settings).await,
+        Level::Level1 => level_1::rules(level_scene_data, settings).await,
+        Level::Level2 => level_2::rules(level_scene_data, settings).await,
+        Level::Level3 => level_3::rules(level_scene_data, settings).await,
+    }
+
+    // The subscribers (like the tally) get the events as soon as they happened, so the level ends in the frame it was completed
+    // and the script sees the score of this frame
+    level_scene_data.level_data.events.dispatch();
+
+    if !level_scene_data.level_data.tally.borrow().is_over() {
+        level::tick_level(level_scene_data, settings).await;
+        level_scene_data.level_data.events.dispatch();
+
+        if let Some(mut script) = level_scene_data.level_data.script.take() {
+            script.tick(level_scene_data, settings).await;
+            level_scene_data.level_data.script = Some(script);
+        }
+        level_scene_data.level_data.events.dispatch();
     }
-}
\ No newline at end of file
+}
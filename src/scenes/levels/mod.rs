@@ -3,3 +3,5 @@ pub mod level_0;
 mod level_1;
 mod level_2;
 mod level_3;
+mod pause_menu;
+mod result_screen;
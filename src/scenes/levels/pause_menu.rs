@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+use macroquad::camera::{pop_camera_state, push_camera_state, set_default_camera};
+use macroquad::color::{Color, WHITE};
+use macroquad::input::{is_key_pressed, KeyCode, MouseButton};
+use macroquad::math::vec2;
+use macroquad::prelude::{screen_height, screen_width, Texture2D};
+use macroquad::shapes::draw_rectangle;
+use stopwatch2::Stopwatch;
+use crate::ui::buttons::Button;
+use crate::utils::enums::TextureKey;
+use crate::utils::i18n;
+use crate::utils::structs::Settings;
+use crate::utils::text::draw_text_centered;
+use crate::utils::texture::{get_texture_path, load_textures_from_tile_map};
+
+/// What the player chose in the pause menu
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum PauseAction {
+    Resume,
+    Restart,
+    Settings,
+    /// Saves the level like Ctrl + S, so it can be continued from the main menu
+    SaveAndQuit,
+    Quit,
+}
+
+/// Draws the pause menu above the level and returns what the player chose <br>
+/// The level itself is paused by [crate::utils::runtime::pause()] while the menu is open
+pub async fn pause_menu(textures: &BTreeMap<TextureKey, Vec<Texture2D>>, settings: &Settings) -> Option<PauseAction> {
+    // Levels keep their camera between frames
+    push_camera_state();
+    set_default_camera();
+
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::from_rgba(0, 0, 0, 160));
+    draw_text_centered(&i18n::text("level.paused"), screen_height() / 8.0, 128.0 * settings.gui_scale, WHITE).await;
+
+    let size = vec2(480.0, 128.0) * settings.gui_scale;
+    let border_size = vec2(48.0, 48.0) * settings.gui_scale;
+    let gap = screen_height() / 48.0;
+    // Only resuming has a key (Enter), the other actions are too easy to trigger by accident
+    let buttons = [
+        (PauseAction::Resume, "pause.resume"),
+        (PauseAction::Restart, "pause.restart"),
+        (PauseAction::Settings, "pause.settings"),
+        (PauseAction::SaveAndQuit, "pause.save_and_quit"),
+        (PauseAction::Quit, "pause.quit"),
+    ];
+
+    let mut y = screen_height() / 2.0 - (size.y * buttons.len() as f32 + gap * (buttons.len() - 1) as f32) / 2.0;
+    let mut result = None;
+    for (action, key) in buttons {
+        let button = Button::new(
+            vec2(screen_width() / 2.0 - size.x / 2.0, y),
+            size,
+            border_size,
+            i18n::text(key),
+            64.0 * settings.gui_scale,
+            TextureKey::Button0,
+        ).await;
+        button.render(textures).await;
+
+        if button.is_released(MouseButton::Left).await || (action == PauseAction::Resume && is_key_pressed(KeyCode::Enter)) {
+            result = Some(action);
+        }
+        y += size.y + gap;
+    }
+
+    pop_camera_state();
+    result
+}
+
+pub async fn load_textures() -> BTreeMap<TextureKey, Vec<Texture2D>> {
+    let mut stopwatch = Stopwatch::default();
+    println!("Loading level menu textures...");
+    stopwatch.start();
+    let mut result = BTreeMap::new();
+
+    let button_textures = {
+        let path = get_texture_path(TextureKey::Button0).await;
+        load_textures_from_tile_map(path)
+    };
+
+    result.insert(TextureKey::Button0, button_textures.await);
+
+    stopwatch.stop();
+    println!("Loaded level menu textures! Took {}ms", stopwatch.elapsed().as_millis());
+
+    result
+}
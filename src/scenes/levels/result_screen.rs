@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+use macroquad::camera::{pop_camera_state, push_camera_state, set_default_camera};
+use macroquad::color::{Color, GOLD, WHITE};
+use macroquad::input::{is_key_pressed, KeyCode, MouseButton};
+use macroquad::math::vec2;
+use macroquad::prelude::{screen_height, screen_width, Texture2D};
+use macroquad::shapes::draw_rectangle;
+use crate::logic::level::{LevelData, PersistentLevelData};
+use crate::ui::buttons::Button;
+use crate::utils::enums::TextureKey;
+use crate::utils::i18n;
+use crate::utils::structs::Settings;
+use crate::utils::text::draw_text_centered;
+
+/// What the player chose on the result screen
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ResultAction {
+    Retry,
+    NextLevel,
+    Back,
+}
+
+/// Draws the result screen of a completed or lost level above it and returns what the player chose <br>
+/// The run is compared with the stats from before it, so it has to be shown before the level gets saved
+pub async fn result_screen(textures: &BTreeMap<TextureKey, Vec<Texture2D>>, level_data: &LevelData, persistent_level_data: &PersistentLevelData, settings: &Settings) -> Option<ResultAction> {
+    let level = level_data.level.unwrap();
+    let score = level_data.score().await;
+    let stats = persistent_level_data.stats.get(&level);
+
+    // Levels keep their camera between frames
+    push_camera_state();
+    set_default_camera();
+
+    draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::from_rgba(0, 0, 0, 160));
+
+    let mut y = screen_height() / 10.0;
+    match score.completed {
+        true => {
+            draw_text_centered(&i18n::text("level.congratulations"), y, 128.0 * settings.gui_scale, WHITE).await;
+            y += 160.0 * settings.gui_scale;
+            draw_text_centered(&i18n::text_with("level.completed", &[("level", level.name())]), y, 64.0 * settings.gui_scale, WHITE).await;
+        }
+        false => draw_text_centered(&i18n::text("level.game_over"), y, 128.0 * settings.gui_scale, WHITE).await,
+    }
+
+    // Every line is marked if it beats the best value of all earlier runs
+    let lines = [
+        (
+            i18n::text_with("result.time", &[("seconds", format!("{:.2}", score.playtime))]),
+            score.completed && persistent_level_data.scores.best_time(&level).is_none_or(|best| score.playtime < best),
+        ),
+        (
+            i18n::text_with("result.coins", &[("count", score.coins.to_string()), ("total", level_data.total_coins().to_string())]),
+            stats.is_some_and(|stats| score.coins > stats.coins_high),
+        ),
+        (
+            i18n::text_with("result.kills", &[("count", score.kills.to_string())]),
+            stats.is_some_and(|stats| score.kills > stats.kills_high),
+        ),
+        (i18n::text_with("result.damage_dealt", &[("count", score.total_damage.to_string())]), false),
+        (i18n::text_with("result.damage_taken", &[("count", score.total_damage_received.to_string())]), false),
+    ];
+
+    y = screen_height() / 3.0;
+    for (line, new_best) in lines {
+        match new_best {
+            true => draw_text_centered(&format!("{}  {}", line, i18n::text("result.new_best")), y, 64.0 * settings.gui_scale, GOLD).await,
+            false => draw_text_centered(&line, y, 64.0 * settings.gui_scale, WHITE).await,
+        }
+        y += 80.0 * settings.gui_scale;
+    }
+
+    let size = vec2(480.0, 128.0) * settings.gui_scale;
+    let border_size = vec2(48.0, 48.0) * settings.gui_scale;
+    let gap = screen_width() / 48.0;
+    let mut buttons = vec![(ResultAction::Retry, "result.retry", KeyCode::R)];
+    // Only completed levels unlock the next one
+    if score.completed && level.next().is_some() {
+        buttons.push((ResultAction::NextLevel, "result.next_level", KeyCode::N));
+    }
+    buttons.push((ResultAction::Back, "result.back", KeyCode::Q));
+
+    let mut x = screen_width() / 2.0 - (size.x * buttons.len() as f32 + gap * (buttons.len() - 1) as f32) / 2.0;
+    let mut result = None;
+    for (action, key, shortcut) in buttons {
+        let button = Button::new(
+            vec2(x, screen_height() - screen_height() / 5.0),
+            size,
+            border_size,
+            i18n::text(key),
+            64.0 * settings.gui_scale,
+            TextureKey::Button0,
+        ).await;
+        button.render(textures).await;
+
+        if button.is_released(MouseButton::Left).await || is_key_pressed(shortcut) {
+            result = Some(action);
+        }
+        x += size.x + gap;
+    }
+
+    pop_camera_state();
+    result
+}
@@ -4,21 +4,24 @@ use macroquad::color::Color;
 use macroquad::input::{is_key_pressed, KeyCode, MouseButton};
 use macroquad::math::vec2;
 use macroquad::prelude::{screen_height, screen_width, Texture2D};
-use macroquad::text::measure_text;
 use stopwatch2::Stopwatch;
+use crate::logic::level::{LevelSceneData, PersistentLevelData};
+use crate::logic::snapshot::LevelSnapshot;
 use crate::ui::buttons::Button;
+use crate::utils::profile::Profiles;
 use crate::utils::structs::Settings;
 use crate::utils::enums::{Scene, SceneTextureKey, TextureKey};
-use crate::utils::text::draw_text_centered;
+use crate::utils::i18n;
+use crate::utils::text::{draw_text_centered, measure_str};
 use crate::utils::texture::{get_texture_path, load_textures_from_tile_map};
 
 /// This function gets executed if the Main Menu is set
-pub async fn main_menu(scene: &mut Scene, textures: &mut BTreeMap<SceneTextureKey, BTreeMap<TextureKey, Vec<Texture2D>>>, settings: &Settings) {
+pub async fn main_menu(scene: &mut Scene, textures: &mut BTreeMap<SceneTextureKey, BTreeMap<TextureKey, Vec<Texture2D>>>, level_scene_data: &mut LevelSceneData, persistent_level_data: &PersistentLevelData, profiles: &Profiles, settings: &Settings) {
     if !textures.contains_key(&SceneTextureKey::MainMenu) {
         textures.insert(SceneTextureKey::MainMenu, load_textures().await);
     }
 
-    let title_measurements = measure_text("JumboMumbo", None, (150.0 * settings.gui_scale) as u16, 1.0);
+    let title_measurements = measure_str("JumboMumbo", 150.0 * settings.gui_scale);
     draw_text_centered("JumboMumbo", screen_height() / 16.0 + title_measurements.offset_y, 150.0 * settings.gui_scale, Color::from_rgba(255, 255, 255, 255)).await;
 
     {
@@ -28,12 +31,33 @@ pub async fn main_menu(scene: &mut Scene, textures: &mut BTreeMap<SceneTextureKe
         let font_size = 64.0 * settings.gui_scale;
         let gap = screen_height() / 64.0;
 
+        // Only shown if a level got saved with "Save & Quit" (Ctrl + S)
+        if LevelSnapshot::exists(settings) {
+            let continue_button = Button::new(
+                vec2(screen_width() / 2.0 - size.x / 2.0, screen_height() / 2.0 - size.y / 2.0 - size.y * 2.0 - gap * 2.0 + y_offset),
+                size,
+                border_size,
+                i18n::text("menu.continue"),
+                font_size,
+                TextureKey::Button0,
+            ).await;
+            continue_button.render(textures.get(&SceneTextureKey::MainMenu).unwrap()).await;
+            if continue_button.is_released(MouseButton::Left).await || is_key_pressed(KeyCode::Enter) {
+                if let Some(snapshot) = LevelSnapshot::take(settings).await {
+                    *level_scene_data = snapshot.restore(settings).await;
+                    level_scene_data.load_ghost(persistent_level_data).await;
+                    *scene = Scene::Level(snapshot.level);
+                    textures.remove(&SceneTextureKey::MainMenu);
+                    return;
+                }
+            }
+        }
 
         let level_selector_button = Button::new(
             vec2(screen_width() / 2.0 - size.x / 2.0, screen_height() / 2.0 - size.y / 2.0 - size.y - gap + y_offset),
             size,
             border_size,
-            "Levels".to_string(),
+            i18n::text("menu.levels"),
             font_size,
             TextureKey::Button0,
         ).await;
@@ -48,13 +72,13 @@ pub async fn main_menu(scene: &mut Scene, textures: &mut BTreeMap<SceneTextureKe
             vec2(screen_width() / 2.0 - size.x / 2.0, screen_height() / 2.0 - size.y / 2.0 + y_offset),
             size,
             border_size,
-            "Settings".to_string(),
+            i18n::text("menu.settings"),
             font_size,
             TextureKey::Button0,
         ).await;
         settings_button.render(textures.get(&SceneTextureKey::MainMenu).unwrap()).await;
         if settings_button.is_released(MouseButton::Left).await || is_key_pressed(KeyCode::S){
-            *scene = Scene::SettingsMenu;
+            *scene = Scene::SettingsMenu(None);
             textures.remove(&SceneTextureKey::MainMenu);
             return;
         }
@@ -63,7 +87,7 @@ pub async fn main_menu(scene: &mut Scene, textures: &mut BTreeMap<SceneTextureKe
             vec2(screen_width() / 2.0 - size.x / 2.0, screen_height() / 2.0 - size.y / 2.0 + size.y + gap + y_offset),
             size,
             border_size,
-            "Credits".to_string(),
+            i18n::text("menu.credits"),
             font_size,
             TextureKey::Button0,
         ).await;
@@ -79,13 +103,29 @@ pub async fn main_menu(scene: &mut Scene, textures: &mut BTreeMap<SceneTextureKe
         vec2(0.0, 0.0),
         vec2(256.0, 128.0) * settings.gui_scale,
         vec2(48.0, 48.0) * settings.gui_scale,
-        "Exit".to_string(),
+        i18n::text("menu.exit"),
         64.0 * settings.gui_scale,
         TextureKey::Button0
     ).await;
 
     exit_button.render(textures.get(&SceneTextureKey::MainMenu).unwrap()).await;
     if exit_button.is_released(MouseButton::Left).await { exit(0) }
+
+    let profile_size = vec2(640.0, 128.0) * settings.gui_scale;
+    let profile_button = Button::new(
+        vec2(screen_width() - profile_size.x, 0.0),
+        profile_size,
+        vec2(48.0, 48.0) * settings.gui_scale,
+        i18n::text_with("menu.profile", &[("name", profiles.current.clone())]),
+        48.0 * settings.gui_scale,
+        TextureKey::Button0
+    ).await;
+
+    profile_button.render(textures.get(&SceneTextureKey::MainMenu).unwrap()).await;
+    if profile_button.is_released(MouseButton::Left).await || is_key_pressed(KeyCode::P) {
+        *scene = Scene::Profiles;
+        textures.remove(&SceneTextureKey::MainMenu);
+    }
 }
 
 async fn load_textures() -> BTreeMap<TextureKey, Vec<Texture2D>> {
@@ -2,4 +2,5 @@ pub mod main_menu;
 pub mod level_selector;
 pub mod levels;
 pub mod settings_menu;
-pub mod credits;
\ No newline at end of file
+pub mod credits;
+pub mod profile_selector;
\ No newline at end of file
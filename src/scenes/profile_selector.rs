@@ -0,0 +1,201 @@
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use macroquad::color::{RED, WHITE};
+use macroquad::input::{get_char_pressed, is_key_pressed, KeyCode, MouseButton};
+use macroquad::math::vec2;
+use macroquad::prelude::{screen_height, screen_width, Texture2D};
+use crate::logic::level::PersistentLevelData;
+use crate::ui::buttons::Button;
+use crate::utils::enums::{Scene, SceneTextureKey, TextureKey};
+use crate::utils::i18n;
+use crate::utils::profile::{Profiles, MAX_NAME_LENGTH, MAX_PROFILES};
+use crate::utils::structs::{Settings, TempSettings};
+use crate::utils::text::draw_text_centered;
+use crate::utils::texture::load_textures;
+
+/// What the profile selector is currently doing
+#[derive(PartialEq, Clone, Debug)]
+enum ProfileMenuMode {
+    List,
+    Create,
+    /// Contains the name of the profile that gets renamed
+    Rename(String),
+    /// Contains the name of the profile that gets deleted
+    Delete(String),
+}
+
+/// The state of the profile selector (kept between frames)
+#[derive(PartialEq, Clone, Debug)]
+pub struct ProfileMenu {
+    mode: ProfileMenuMode,
+    /// The name that is currently typed
+    input: String,
+    error: Option<String>,
+}
+
+impl ProfileMenu {
+    pub fn new() -> Self {
+        Self { mode: ProfileMenuMode::List, input: String::new(), error: None }
+    }
+
+    fn set_mode(&mut self, mode: ProfileMenuMode) {
+        // Throw away everything that got typed before
+        while get_char_pressed().is_some() {}
+
+        self.mode = mode;
+        self.input.clear();
+        self.error = None;
+    }
+}
+
+/// Lets the player choose, create, rename and delete profiles
+pub async fn profile_selector(
+    scene: &mut Scene,
+    textures: &mut BTreeMap<SceneTextureKey, BTreeMap<TextureKey, Vec<Texture2D>>>,
+    menu: &mut ProfileMenu,
+    profiles: &mut Profiles,
+    settings: &mut Settings,
+    temp_settings: &mut TempSettings,
+    persistent_level_data: &mut PersistentLevelData,
+) {
+    if let Entry::Vacant(entry) = textures.entry(SceneTextureKey::Profiles) {
+        entry.insert(load_textures(&i18n::text("profiles.title"), [TextureKey::Button0].to_vec()).await);
+    }
+    let scene_textures = textures.get(&SceneTextureKey::Profiles).unwrap();
+
+    draw_text_centered(&i18n::text("profiles.title"), screen_height() / 8.0, 128.0 * settings.gui_scale, WHITE).await;
+
+    if let Some(error) = &menu.error {
+        draw_text_centered(error, screen_height() - screen_height() / 32.0, 48.0 * settings.gui_scale, RED).await;
+    }
+
+    let font_size = 64.0 * settings.gui_scale;
+    let border_size = vec2(32.0, 32.0) * settings.gui_scale;
+    let gap = screen_height() / 64.0;
+
+    match menu.mode.clone() {
+        ProfileMenuMode::List => {
+            if is_key_pressed(KeyCode::Escape) {
+                *scene = Scene::MainMenu;
+                textures.remove(&SceneTextureKey::Profiles);
+                return;
+            }
+
+            let name_size = vec2(600.0, 80.0) * settings.gui_scale;
+            let action_size = vec2(300.0, 80.0) * settings.gui_scale;
+            let x = screen_width() / 2.0 - (name_size.x + action_size.x * 2.0 + gap * 2.0) / 2.0;
+
+            for (i, name) in profiles.names.clone().iter().enumerate() {
+                let y = screen_height() / 5.0 + (name_size.y + gap) * i as f32;
+                let text = if *name == profiles.current { format!("> {} <", name) } else { name.to_owned() };
+
+                let select_button = Button::new(vec2(x, y), name_size, border_size, text, font_size, TextureKey::Button0).await;
+                let rename_button = Button::new(vec2(x + name_size.x + gap, y), action_size, border_size, i18n::text("profiles.rename"), font_size, TextureKey::Button0).await;
+                let delete_button = Button::new(vec2(x + name_size.x + action_size.x + gap * 2.0, y), action_size, border_size, i18n::text("profiles.delete"), font_size, TextureKey::Button0).await;
+
+                select_button.render(scene_textures).await;
+                rename_button.render(scene_textures).await;
+                delete_button.render(scene_textures).await;
+
+                if select_button.is_released(MouseButton::Left).await {
+                    if *name != profiles.current {
+                        (*settings, *persistent_level_data) = profiles.select(name).await;
+                        *temp_settings = TempSettings::new(settings.clone());
+                    }
+                    *scene = Scene::MainMenu;
+                    textures.remove(&SceneTextureKey::Profiles);
+                    return;
+                }
+                if rename_button.is_released(MouseButton::Left).await {
+                    menu.set_mode(ProfileMenuMode::Rename(name.to_owned()));
+                    menu.input = name.to_owned();
+                }
+                if delete_button.is_released(MouseButton::Left).await {
+                    match *name == profiles.current {
+                        true => menu.error = Some(i18n::text("profiles.delete_active")),
+                        false => menu.set_mode(ProfileMenuMode::Delete(name.to_owned())),
+                    }
+                }
+            }
+
+            if profiles.names.len() < MAX_PROFILES {
+                let size = vec2(500.0, 128.0) * settings.gui_scale;
+                let new_button = Button::new(
+                    vec2(screen_width() / 2.0 - size.x / 2.0, screen_height() - screen_height() / 8.0 - size.y),
+                    size,
+                    border_size,
+                    i18n::text("profiles.new"),
+                    font_size,
+                    TextureKey::Button0,
+                ).await;
+                new_button.render(scene_textures).await;
+
+                if new_button.is_released(MouseButton::Left).await || is_key_pressed(KeyCode::N) {
+                    menu.set_mode(ProfileMenuMode::Create);
+                }
+            }
+        }
+        ProfileMenuMode::Create | ProfileMenuMode::Rename(_) => {
+            let title = match &menu.mode {
+                ProfileMenuMode::Rename(name) => i18n::text_with("profiles.rename_prompt", &[("name", name.clone())]),
+                _ => i18n::text("profiles.name_prompt"),
+            };
+            draw_text_centered(&title, screen_height() / 2.0 - 128.0 * settings.gui_scale, font_size, WHITE).await;
+            draw_text_centered(&format!("{}_", menu.input), screen_height() / 2.0, 96.0 * settings.gui_scale, WHITE).await;
+            draw_text_centered(&i18n::text("profiles.confirm_hint"), screen_height() / 2.0 + 128.0 * settings.gui_scale, 48.0 * settings.gui_scale, WHITE).await;
+
+            while let Some(c) = get_char_pressed() {
+                if !c.is_control() && menu.input.chars().count() < MAX_NAME_LENGTH {
+                    menu.input.push(c);
+                }
+            }
+            if is_key_pressed(KeyCode::Backspace) {
+                menu.input.pop();
+            }
+
+            if is_key_pressed(KeyCode::Escape) {
+                menu.set_mode(ProfileMenuMode::List);
+            } else if is_key_pressed(KeyCode::Enter) {
+                let result = match &menu.mode {
+                    ProfileMenuMode::Rename(name) => profiles.rename(name, &menu.input),
+                    _ => profiles.create(&menu.input),
+                };
+
+                match result {
+                    Ok(_) => {
+                        // The paths of the active profile changed if it got renamed
+                        if let ProfileMenuMode::Rename(_) = menu.mode {
+                            (*settings, *persistent_level_data) = profiles.load_current().await;
+                            *temp_settings = TempSettings::new(settings.clone());
+                        }
+                        menu.set_mode(ProfileMenuMode::List);
+                    }
+                    Err(e) => menu.error = Some(e),
+                }
+            }
+        }
+        ProfileMenuMode::Delete(name) => {
+            draw_text_centered(&i18n::text_with("profiles.delete_confirm", &[("name", name.clone())]), screen_height() / 2.0 - 128.0 * settings.gui_scale, font_size, WHITE).await;
+
+            let size = vec2(300.0, 128.0) * settings.gui_scale;
+            let y = screen_height() / 2.0;
+            let yes_button = Button::new(vec2(screen_width() / 2.0 - size.x - gap, y), size, border_size, i18n::text("common.yes"), font_size, TextureKey::Button0).await;
+            let no_button = Button::new(vec2(screen_width() / 2.0 + gap, y), size, border_size, i18n::text("common.no"), font_size, TextureKey::Button0).await;
+
+            yes_button.render(scene_textures).await;
+            no_button.render(scene_textures).await;
+
+            if yes_button.is_released(MouseButton::Left).await || is_key_pressed(KeyCode::Enter) {
+                match profiles.delete(&name) {
+                    Ok(_) => menu.set_mode(ProfileMenuMode::List),
+                    Err(e) => {
+                        menu.set_mode(ProfileMenuMode::List);
+                        menu.error = Some(e);
+                    }
+                }
+            } else if no_button.is_released(MouseButton::Left).await || is_key_pressed(KeyCode::Escape) {
+                menu.set_mode(ProfileMenuMode::List);
+            }
+        }
+    }
+}
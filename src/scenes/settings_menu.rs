@@ -1,16 +1,18 @@
 use std::collections::BTreeMap;
 use macroquad::color::{Color, WHITE};
-use macroquad::input::{is_key_pressed, KeyCode, MouseButton};
+use macroquad::input::{get_last_key_pressed, is_key_pressed, KeyCode, MouseButton};
 use macroquad::math::vec2;
-use macroquad::prelude::{measure_text, screen_height, Texture2D};
-use macroquad::text::draw_text;
+use macroquad::prelude::{screen_height, Texture2D};
 use macroquad::window::screen_width;
 use stopwatch2::Stopwatch;
+use crate::logic::input::{InputAction, Key};
 use crate::ui::buttons::Button;
+use crate::ui::slider::Slider;
 use crate::utils::enums::{Scene, SceneTextureKey, TextureKey};
+use crate::utils::i18n;
 use crate::utils::mathemann::round;
 use crate::utils::structs::{Settings, TempSettings};
-use crate::utils::text::draw_text_centered;
+use crate::utils::text::{draw_str, draw_text_centered, measure_str};
 use crate::utils::texture::{get_texture_path, load_textures_from_tile_map};
 
 pub async fn settings_menu(scene: &mut Scene, textures: &mut BTreeMap<SceneTextureKey, BTreeMap<TextureKey, Vec<Texture2D>>>, settings: &mut Settings, temp_settings: &mut TempSettings) {
@@ -20,8 +22,19 @@ pub async fn settings_menu(scene: &mut Scene, textures: &mut BTreeMap<SceneTextu
 
     draw_text_centered("JumboMumbo", screen_height() / 8.0, 150.0 * settings.gui_scale, Color::from_rgba(255, 255, 255, 255)).await;
 
-    if is_key_pressed(KeyCode::Escape) {
-        *scene = Scene::MainMenu;
+    if let Some(action) = temp_settings.rebinding {
+        // Escape cancels the rebinding instead of leaving the menu
+        if is_key_pressed(KeyCode::Escape) {
+            temp_settings.rebinding = None;
+        } else if let Some(key) = get_last_key_pressed().and_then(Key::new) {
+            temp_settings.settings.keybindings.bind(action, key);
+            temp_settings.rebinding = None;
+        }
+    } else if is_key_pressed(KeyCode::Escape) {
+        *scene = match scene {
+            Scene::SettingsMenu(Some(level)) => Scene::Level(*level),
+            _ => Scene::MainMenu,
+        };
         textures.remove(&SceneTextureKey::SettingsMenu);
         return;
     }
@@ -31,7 +44,7 @@ pub async fn settings_menu(scene: &mut Scene, textures: &mut BTreeMap<SceneTextu
     { // GUI Scale
         let size = vec2(64.0, 64.0) * settings.gui_scale;
         let border_size = vec2(16.0, 16.0) * settings.gui_scale;
-        let y = screen_width() / 4.0;
+        let y = screen_height() / 5.0;
         let plus_button = Button::new(
             vec2(screen_width() - size.x, y),
             size,
@@ -61,10 +74,165 @@ pub async fn settings_menu(scene: &mut Scene, textures: &mut BTreeMap<SceneTextu
             temp_settings.settings.gui_scale = round(temp_settings.settings.gui_scale, 1).await
         }
 
-        let text = format!("GUI Scale: {}", temp_settings.settings.gui_scale);
+        let text = i18n::text_with("settings.gui_scale", &[("scale", temp_settings.settings.gui_scale.to_string())]);
+        let font_size = 64.0 * settings.gui_scale;
+        let text_measures = measure_str(&text, font_size);
+        draw_str(
+            &text,
+            0.0,
+            y + text_measures.offset_y,
+            font_size as _,
+            WHITE
+        );
+    }
+
+    { // Ghost
+        let size = vec2(256.0, 64.0) * settings.gui_scale;
+        let border_size = vec2(16.0, 16.0) * settings.gui_scale;
+        let y = screen_height() / 5.0 + 64.0 * settings.gui_scale * 1.25;
+        let toggle_button = Button::new(
+            vec2(screen_width() - size.x, y),
+            size,
+            border_size,
+            if temp_settings.settings.ghost { i18n::text("settings.on") } else { i18n::text("settings.off") },
+            64.0 * settings.gui_scale,
+            TextureKey::Button0
+        ).await;
+
+        toggle_button.render(textures).await;
+
+        if toggle_button.is_released(MouseButton::Left).await {
+            temp_settings.settings.ghost = !temp_settings.settings.ghost;
+        }
+
+        // The lives mode shares the row, its button says what it is
+        let lives_button = Button::new(
+            vec2(screen_width() / 2.0, y),
+            size,
+            border_size,
+            match temp_settings.settings.lives {
+                Some(lives) => i18n::text_with("settings.lives", &[("count", lives.to_string())]),
+                None => i18n::text("settings.lives_off"),
+            },
+            48.0 * settings.gui_scale,
+            TextureKey::Button0
+        ).await;
+
+        lives_button.render(textures).await;
+
+        // Off -> 3 -> 5 -> Off
+        if lives_button.is_released(MouseButton::Left).await {
+            temp_settings.settings.lives = match temp_settings.settings.lives {
+                None => Some(3),
+                Some(3) => Some(5),
+                Some(_) => None,
+            };
+        }
+
+        let text = i18n::text("settings.ghost");
+        let font_size = 64.0 * settings.gui_scale;
+        let text_measures = measure_str(&text, font_size);
+        draw_str(
+            &text,
+            0.0,
+            y + text_measures.offset_y,
+            font_size as _,
+            WHITE
+        );
+    }
+
+    { // Language
+        let size = vec2(384.0, 64.0) * settings.gui_scale;
+        let border_size = vec2(16.0, 16.0) * settings.gui_scale;
+        let y = screen_height() / 5.0 + 64.0 * settings.gui_scale * 2.5;
+        let languages = i18n::languages();
+        let current = languages.iter().position(|(code, _)| *code == temp_settings.settings.language);
+        let language_button = Button::new(
+            vec2(screen_width() - size.x, y),
+            size,
+            border_size,
+            current.map(|i| languages[i].1.clone()).unwrap_or_else(|| temp_settings.settings.language.clone()),
+            48.0 * settings.gui_scale,
+            TextureKey::Button0
+        ).await;
+
+        language_button.render(textures).await;
+
+        // Switches to the next language
+        if language_button.is_released(MouseButton::Left).await {
+            let next = current.map(|i| (i + 1) % languages.len()).unwrap_or(0);
+            temp_settings.settings.language = languages[next].0.clone();
+        }
+
+        let text = i18n::text("settings.language");
+        let font_size = 64.0 * settings.gui_scale;
+        let text_measures = measure_str(&text, font_size);
+        draw_str(
+            &text,
+            0.0,
+            y + text_measures.offset_y,
+            font_size as _,
+            WHITE
+        );
+    }
+
+    // Volumes
+    let volumes = [
+        ("settings.master_volume", &mut temp_settings.settings.master_volume),
+        ("settings.music_volume", &mut temp_settings.settings.music_volume),
+        ("settings.sfx_volume", &mut temp_settings.settings.sfx_volume),
+    ];
+    for (i, (key, volume)) in volumes.into_iter().enumerate() {
+        let size = vec2(512.0, 48.0) * settings.gui_scale;
+        let y = screen_height() / 5.0 + 64.0 * settings.gui_scale * (3.75 + i as f32 * 1.1);
+        let slider = Slider::new(
+            vec2(screen_width() - size.x - 16.0 * settings.gui_scale, y + 8.0 * settings.gui_scale),
+            size,
+            *volume
+        ).await;
+
+        slider.render().await;
+
+        if let Some(value) = slider.dragged().await {
+            *volume = round(value, 2).await;
+        }
+
+        let text = i18n::text_with(key, &[("percent", (*volume * 100.0).round().to_string())]);
+        let font_size = 64.0 * settings.gui_scale;
+        let text_measures = measure_str(&text, font_size);
+        draw_str(
+            &text,
+            0.0,
+            y + text_measures.offset_y,
+            font_size as _,
+            WHITE
+        );
+    }
+
+    // Keybindings
+    for (i, action) in InputAction::ALL.into_iter().enumerate() {
+        let size = vec2(256.0, 64.0) * settings.gui_scale;
+        let border_size = vec2(16.0, 16.0) * settings.gui_scale;
+        let y = screen_height() / 5.0 + 64.0 * settings.gui_scale * (7.25 + i as f32 * 1.2);
+        let change_button = Button::new(
+            vec2(screen_width() / 2.0, y),
+            size,
+            border_size,
+            if temp_settings.rebinding == Some(action) { i18n::text("settings.press_key") } else { i18n::text("settings.change") },
+            48.0 * settings.gui_scale,
+            TextureKey::Button0
+        ).await;
+
+        change_button.render(textures).await;
+
+        if change_button.is_released(MouseButton::Left).await {
+            temp_settings.rebinding = Some(action);
+        }
+
+        let text = i18n::text_with("settings.keybinding", &[("action", action.name()), ("key", temp_settings.settings.keybindings.text(action))]);
         let font_size = 64.0 * settings.gui_scale;
-        let text_measures = measure_text(&text, None, font_size as _, 1.0);
-        draw_text(
+        let text_measures = measure_str(&text, font_size);
+        draw_str(
             &text,
             0.0,
             y + text_measures.offset_y,
@@ -80,7 +248,7 @@ pub async fn settings_menu(scene: &mut Scene, textures: &mut BTreeMap<SceneTextu
             pos,
             size,
             vec2(32.0, 32.0) * settings.gui_scale,
-            "Apply".to_string(),
+            i18n::text("settings.apply"),
             64.0 * settings.gui_scale,
             TextureKey::Button0,
         ).await;
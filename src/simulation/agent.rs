@@ -0,0 +1,285 @@
+use macroquad::math::{vec2, Vec2};
+use crate::logic::input::PlayerInput;
+use crate::logic::level::LevelSceneData;
+use crate::logic::ecs::components::ProjectileOrigin;
+use crate::simulation::headless::ScriptStep;
+use crate::utils::runtime::get_frame_time;
+use crate::utils::structs::{Rect, Settings};
+
+/// Everything an [Agent] can see in the current frame <br>
+/// All positions are in pixels of the level
+#[derive(PartialEq, Clone, Debug)]
+pub struct Observation {
+    pub time: f64,
+    pub tile_size: Vec2,
+    pub player_pos: Vec2,
+    pub player_size: Vec2,
+    pub player_speed: Vec2,
+    pub on_ground: bool,
+    /// How the player moves (in pixels per second, already multiplied with the GUI scale)
+    pub movement_speed: f32,
+    pub jump_speed: f32,
+    pub gravity: f32,
+    pub terminal_velocity: f32,
+    pub frame_time: f32,
+    /// All solid things (platforms and cannons) near the player
+    pub tiles: Vec<Rect>,
+    pub enemies: Vec<EnemyObservation>,
+    pub projectiles: Vec<ProjectileObservation>,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct EnemyObservation {
+    pub rect: Rect,
+    pub health: i16,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct ProjectileObservation {
+    pub rect: Rect,
+    pub speed: Vec2,
+    /// `true` if the projectile can hurt the player
+    pub hostile: bool,
+}
+
+impl Observation {
+    /// How far (in tiles) the player can see
+    const VIEW_DISTANCE: f32 = 12.0;
+    /// How far (in seconds) the movement of the player gets predicted
+    const PREDICTION_TIME: f32 = 3.0;
+
+    pub async fn new(level_scene_data: &LevelSceneData, settings: &Settings) -> Self {
+        let level_data = &level_scene_data.level_data;
+        let world = &level_scene_data.world;
+        let player = level_data.player.as_ref().unwrap();
+        let tile_size = vec2(128.0, 128.0) * settings.gui_scale;
+        let modifiers = player.modifiers();
+
+        let view = {
+            let distance = tile_size * Self::VIEW_DISTANCE;
+            Rect::new(player.pos.x - distance.x, player.pos.y - distance.y, distance.x * 2.0, distance.y * 2.0).await
+        };
+
+        let mut tiles = Vec::new();
+        for (entity, collider) in &level_data.entities.colliders {
+            let solid = level_data.entities.platforms.contains_key(entity) || level_data.entities.shooters.contains_key(entity);
+            if solid && collider.rect.overlaps(&view).await {
+                tiles.push(collider.rect);
+            }
+        }
+
+        let mut enemies = Vec::new();
+        for (enemy, health) in &level_data.entities.healths {
+            let Some(collider) = level_data.entities.colliders.get(enemy) else { continue };
+            if collider.rect.overlaps(&view).await {
+                enemies.push(EnemyObservation { rect: collider.rect, health: health.amount });
+            }
+        }
+
+        let mut projectiles = Vec::new();
+        for (projectile, damage) in &level_data.entities.damages {
+            let rect = level_data.entities.colliders.get(projectile).unwrap().rect;
+            if rect.overlaps(&view).await {
+                projectiles.push(ProjectileObservation {
+                    rect,
+                    speed: level_data.entities.velocities.get(projectile).unwrap().0,
+                    hostile: damage.origin != ProjectileOrigin::Player,
+                });
+            }
+        }
+
+        Self {
+            time: level_data.playtime().await,
+            tile_size,
+            player_pos: player.pos,
+            player_size: vec2(player.width, player.height),
+            player_speed: player.speed,
+            on_ground: world.collide_check(player.collider, player.pos + vec2(0.0, 1.0)),
+            movement_speed: modifiers.movement_speed * settings.gui_scale,
+            jump_speed: modifiers.jump_speed * settings.gui_scale,
+            gravity: level_data.physics.gravity * settings.gui_scale,
+            terminal_velocity: level_data.physics.terminal_velocity * settings.gui_scale,
+            frame_time: get_frame_time(),
+            tiles,
+            enemies,
+            projectiles,
+        }
+    }
+
+    /// Checks if a solid tile is at the provided position
+    pub fn solid_at(&self, pos: Vec2) -> bool {
+        self.tiles.iter().any(|tile| pos.x >= tile.x && pos.x <= tile.x + tile.w && pos.y >= tile.y && pos.y <= tile.y + tile.h)
+    }
+
+    /// Checks if the player would be inside of a solid tile at the provided position
+    fn player_blocked(&self, pos: Vec2) -> bool {
+        self.tiles.iter().any(|tile| {
+            pos.x < tile.x + tile.w && pos.x + self.player_size.x > tile.x && pos.y < tile.y + tile.h && pos.y + self.player_size.y > tile.y
+        })
+    }
+
+    /// Predicts where the player lands if it jumps in this frame (or just keeps walking) and holds right for the provided amount of frames <br>
+    /// Returns the position the player lands at or `None` if it leaves the view first. Walking on the current ground doesn't count as landing
+    pub fn landing(&self, jump: bool, hold: u32) -> Option<Vec2> {
+        let view = self.tile_size * Self::VIEW_DISTANCE;
+        let frame_time = self.frame_time.max(1.0 / 240.0);
+        let start = self.player_pos;
+        let mut pos = start;
+        let mut speed = vec2(self.movement_speed, if jump { -self.jump_speed } else { self.player_speed.y.max(0.0) });
+
+        // The prediction ends after a few seconds (e.g. if a wall is in the way)
+        for frame in 0..(Self::PREDICTION_TIME / frame_time) as u32 {
+            if frame == hold { speed.x = 0.0; }
+            // Nothing is known about what is outside of the view
+            if pos.y > start.y + view.y || pos.x > start.x + view.x { return None; }
+
+            speed.y = (speed.y + self.gravity * frame_time).min(self.terminal_velocity);
+
+            let next = vec2(pos.x + speed.x * frame_time, pos.y);
+            if !self.player_blocked(next) {
+                pos = next;
+            }
+
+            let next = vec2(pos.x, pos.y + speed.y * frame_time);
+            if !self.player_blocked(next) {
+                pos = next;
+            } else if speed.y > 0.0 && (jump || pos.y != start.y) {
+                // Move the rest of the way down to the ground
+                while !self.player_blocked(pos + vec2(0.0, 1.0)) { pos.y += 1.0; }
+                return Some(pos);
+            } else {
+                speed.y = 0.0;
+            }
+        }
+
+        Some(pos)
+    }
+
+    /// Returns the x position at which the ground in front of the player ends (if that is in view and no wall is in the way)
+    pub fn edge(&self) -> Option<f32> {
+        let step = self.tile_size.x / 16.0;
+        let feet = self.player_pos.y + self.player_size.y + 1.0;
+        let mut x = self.player_pos.x;
+
+        while x < self.player_pos.x + self.tile_size.x * Self::VIEW_DISTANCE {
+            if self.player_blocked(vec2(x, self.player_pos.y - 1.0)) { return None; }
+            if !self.solid_at(vec2(x, feet)) && !self.solid_at(vec2(x + self.player_size.x, feet)) { return Some(x); }
+            x += step;
+        }
+
+        None
+    }
+}
+
+/// Controls the player instead of the keyboard
+pub trait Agent {
+    /// Gets called every frame and returns what the player should do
+    fn act(&mut self, observation: &Observation) -> PlayerInput;
+}
+
+/// Plays back an input script (see [ScriptStep])
+pub struct ScriptedAgent {
+    inputs: Vec<PlayerInput>,
+    index: usize,
+}
+
+impl ScriptedAgent {
+    pub fn new(script: &[ScriptStep]) -> Self {
+        let inputs = script.iter().flat_map(|step| std::iter::repeat_n(step.input, step.frames as usize)).collect();
+        Self { inputs, index: 0 }
+    }
+}
+
+impl Agent for ScriptedAgent {
+    fn act(&mut self, _: &Observation) -> PlayerInput {
+        let input = self.inputs.get(self.index).copied().unwrap_or_default();
+        self.index += 1;
+        input
+    }
+}
+
+/// A very simple agent that runs right, jumps over gaps and walls and shoots enemies in front of it <br>
+/// Before every jump it predicts where the player lands (see [Observation::landing()]), so it jumps before the edge
+/// of a gap and climbs onto higher platforms. Jump is held while the player is rising, so every jump is as high as possible
+#[derive(Default)]
+pub struct SimpleAgent {
+    /// How many more frames right is held during the current jump (`None` while walking or if right is held until landing)
+    hold: Option<u32>,
+}
+
+impl SimpleAgent {
+    /// Decides whether the player (standing on the ground) should jump in this frame <br>
+    /// Returns for how many frames right should be held during the jump (`u32::MAX` for the whole jump)
+    fn plan_jump(observation: &Observation) -> Option<u32> {
+        let tile = observation.tile_size;
+        let pos = observation.player_pos;
+        let size = observation.player_size;
+        // The player stands safely if the ground reaches at least a quarter of its width under it
+        let supported = |landing: Vec2| [0.25, 0.75].iter().any(|part| observation.solid_at(vec2(landing.x + size.x * part, landing.y + size.y + 1.0)));
+
+        let wall_ahead = observation.solid_at(vec2(pos.x + size.x + tile.x * 0.25, pos.y + size.y / 2.0));
+        if wall_ahead { return Some(u32::MAX); }
+
+        // Climb onto higher platforms (y grows downwards)
+        let jump = observation.landing(true, u32::MAX);
+        if jump.is_some_and(|landing| landing.y < pos.y - tile.y / 2.0) { return Some(u32::MAX); }
+
+        let edge = observation.edge()?;
+        let walk = observation.landing(false, u32::MAX);
+
+        // Jump over the gap as soon as the jump lands behind it and at least as high as walking down would
+        let clears_gap = |landing: Option<Vec2>| landing.is_some_and(|landing| {
+            landing.x > edge && supported(landing) && walk.is_none_or(|walk| landing.y <= walk.y)
+        });
+        if clears_gap(jump) { return Some(u32::MAX); }
+
+        // Nothing is safe, so jump at the last moment and try shorter jumps if the full one is too far
+        let last_chance = walk.is_none() && edge - pos.x < observation.movement_speed * observation.frame_time;
+        if !last_chance { return None; }
+
+        (3..60).step_by(3)
+            .find(|&hold| clears_gap(observation.landing(true, hold)))
+            .or(Some(u32::MAX))
+    }
+}
+
+impl Agent for SimpleAgent {
+    fn act(&mut self, observation: &Observation) -> PlayerInput {
+        let tile = observation.tile_size;
+        let pos = observation.player_pos;
+
+        let mut jump = observation.player_speed.y < 0.0;
+        if observation.on_ground {
+            self.hold = Self::plan_jump(observation);
+            jump |= self.hold.is_some();
+        }
+
+        let right = self.hold.is_none_or(|hold| hold > 0);
+        self.hold = self.hold.map(|hold| hold.saturating_sub(1));
+
+        let enemy_ahead = observation.enemies.iter().any(|enemy| {
+            let distance = enemy.rect.x - pos.x;
+            distance > 0.0 && distance < tile.x * 8.0 && (enemy.rect.y - pos.y).abs() < tile.y * 2.0
+        });
+        let enemy_behind = observation.enemies.iter().any(|enemy| {
+            let distance = pos.x - enemy.rect.x;
+            distance > 0.0 && distance < tile.x * 4.0 && (enemy.rect.y - pos.y).abs() < tile.y * 2.0
+        });
+
+        PlayerInput {
+            right,
+            jump,
+            shoot_right: enemy_ahead,
+            shoot_left: enemy_behind && !enemy_ahead,
+            ..Default::default()
+        }
+    }
+}
+
+/// Returns the built-in agent with the provided name
+pub fn agent_from_name(name: &str) -> Option<Box<dyn Agent>> {
+    match name {
+        "simple" => Some(Box::new(SimpleAgent::default())),
+        _ => None,
+    }
+}
@@ -0,0 +1,333 @@
+use std::fs;
+use std::time::Instant;
+use macroquad::math::vec2;
+use serde::{Deserialize, Serialize};
+use crate::logic::input::PlayerInput;
+use crate::logic::level::{Level, LevelSceneData};
+use crate::logic::platform::Platform;
+use crate::scenes::levels::levels;
+use crate::simulation::agent::{agent_from_name, Agent, Observation, ScriptedAgent};
+use crate::utils::enums::TextureKey;
+use crate::utils::runtime;
+use crate::utils::structs::Settings;
+
+// This file runs levels without a window (e.g. for gameplay regression checks on build machines)
+// Usage: `JumboMumbo --headless --level <0-3|all> [--frames <n>] [--fps <n>] [--script <path> | --agent <simple>] [--expect <completed|game-over>] [--lives <n>] [--benchmark [--extra <n,...>] [--clone-baseline]]`
+
+/// One step of an input script <br>
+/// The input is held for the provided amount of frames
+///
+/// # Example
+/// ```json
+/// [
+///   { "frames": 60, "right": true },
+///   { "frames": 10, "right": true, "jump": true }
+/// ]
+/// ```
+#[derive(Clone, Deserialize, Debug)]
+pub struct ScriptStep {
+    pub frames: u32,
+    #[serde(flatten)]
+    pub input: PlayerInput,
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Expectation {
+    Completed,
+    GameOver,
+}
+
+#[derive(Clone, Debug)]
+pub struct HeadlessOptions {
+    pub levels: Vec<Level>,
+    /// The maximum amount of simulated frames
+    pub frames: u32,
+    pub fps: f32,
+    pub script: Vec<ScriptStep>,
+    /// The name of the built-in agent that plays instead of the script
+    pub agent: Option<String>,
+    pub expect: Option<Expectation>,
+    /// Plays in the lives mode with the provided amount of lives
+    pub lives: Option<u32>,
+    /// Measures how long the frames take instead of reporting the result of the level
+    pub benchmark: bool,
+    /// Every level gets benchmarked once with each of these amounts of extra entities (see [spawn_extra_entities()])
+    pub extra: Vec<usize>,
+    /// Copies the level data twice per frame in the benchmark like the old update pipeline did
+    pub clone_baseline: bool,
+}
+
+impl HeadlessOptions {
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let levels = match arg_value(args, "--level").ok_or("Missing --level")? {
+            "all" => (0..).map_while(Level::from_number).collect(),
+            level => vec![level.parse::<usize>().ok().and_then(Level::from_number).ok_or(format!("Unknown level \"{}\"", level))?],
+        };
+
+        let frames = match arg_value(args, "--frames") {
+            Some(frames) => frames.parse().map_err(|_| format!("Invalid frame count \"{}\"", frames))?,
+            None => 60 * 60 * 5,
+        };
+
+        let fps = match arg_value(args, "--fps") {
+            Some(fps) => fps.parse().map_err(|_| format!("Invalid fps \"{}\"", fps))?,
+            None => 60.0,
+        };
+
+        let script = match arg_value(args, "--script") {
+            Some(path) => {
+                let file = fs::File::open(path).map_err(|e| format!("Couldn't open script \"{}\": {}", path, e))?;
+                serde_json::from_reader(file).map_err(|e| format!("Couldn't parse script \"{}\": {}", path, e))?
+            }
+            None => Vec::new(),
+        };
+
+        let agent = match arg_value(args, "--agent") {
+            Some(name) if agent_from_name(name).is_some() => Some(name.to_string()),
+            Some(name) => return Err(format!("Unknown agent \"{}\"", name)),
+            None => None,
+        };
+
+        let expect = match arg_value(args, "--expect") {
+            Some("completed") => Some(Expectation::Completed),
+            Some("game-over") => Some(Expectation::GameOver),
+            Some(expect) => return Err(format!("Unknown expectation \"{}\"", expect)),
+            None => None,
+        };
+
+        let lives = match arg_value(args, "--lives") {
+            Some(lives) => Some(lives.parse().map_err(|_| format!("Invalid lives \"{}\"", lives))?),
+            None => None,
+        };
+
+        let benchmark = args.iter().any(|arg| arg == "--benchmark");
+
+        let extra = match arg_value(args, "--extra") {
+            Some(extra) => extra.split(',').map(|amount| amount.parse().map_err(|_| format!("Invalid amount of extra entities \"{}\"", amount))).collect::<Result<_, _>>()?,
+            None => vec![0],
+        };
+
+        let clone_baseline = args.iter().any(|arg| arg == "--clone-baseline");
+
+        Ok(Self { levels, frames, fps, script, agent, expect, lives, benchmark, extra, clone_baseline })
+    }
+}
+
+/// The result of a simulated level
+#[derive(PartialEq, Clone, Serialize, Debug)]
+pub struct SimulationReport {
+    pub level: Level,
+    pub frames: u32,
+    /// The simulated time in seconds
+    pub time: f64,
+    pub completed: bool,
+    pub game_over: bool,
+    /// The position of the player in tiles
+    pub x: f32,
+    pub y: f32,
+    pub health: i16,
+    pub coins: u32,
+    pub kills: u32,
+    /// The remaining lives (only in the lives mode)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lives: Option<u32>,
+}
+
+/// How long the frames of a simulated level took (in microseconds of real time)
+#[derive(PartialEq, Clone, Serialize, Debug)]
+pub struct BenchmarkReport {
+    pub level: Level,
+    pub frames: u32,
+    /// The amount of entities in the level (platforms, enemies, coins, ...) including the extra ones
+    pub objects: usize,
+    /// The entities that got added for the benchmark (see [spawn_extra_entities()])
+    pub extra: usize,
+    /// Whether every frame copied the level data twice like the old update pipeline (see [HeadlessOptions::clone_baseline])
+    pub clone_baseline: bool,
+    pub average_frame: f64,
+    /// The average frame divided by the amount of objects
+    pub average_per_object: f64,
+    pub max_frame: f64,
+    /// How long copying the whole level data takes (the update pipeline used to do this twice per frame)
+    pub level_data_clone: f64,
+}
+
+/// Returns the value after the provided argument (e.g. `--level 1`)
+fn arg_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let index = args.iter().position(|arg| arg == name)?;
+    args.get(index + 1).map(|value| value.as_str())
+}
+
+/// Creates the settings that are used for simulations (nothing gets saved)
+pub async fn headless_settings() -> Settings {
+    Settings::new(String::new(), String::new()).await
+}
+
+/// Steps the level until it is completed, lost or `max_frames` is reached <br>
+/// The [Agent] gets asked every frame for the input of the player
+pub async fn simulate(level_scene_data: &mut LevelSceneData, max_frames: u32, settings: &Settings, agent: &mut dyn Agent) -> SimulationReport {
+    let mut frames = 0;
+
+    while frames < max_frames {
+        let observation = Observation::new(level_scene_data, settings).await;
+        let input = agent.act(&observation);
+        levels::step_level(level_scene_data, &input, settings).await;
+        runtime::advance();
+        frames += 1;
+
+        if level_scene_data.level_data.tally.borrow().is_over() {
+            break;
+        }
+    }
+
+    report(level_scene_data, frames, settings).await
+}
+
+pub async fn report(level_scene_data: &LevelSceneData, frames: u32, settings: &Settings) -> SimulationReport {
+    let level_data = &level_scene_data.level_data;
+    let player = level_data.player.as_ref().unwrap();
+    let tile_size = 128.0 * settings.gui_scale;
+    let time = level_data.playtime().await;
+    let tally = level_data.tally.borrow();
+
+    SimulationReport {
+        level: level_data.level.unwrap(),
+        frames,
+        time,
+        completed: tally.completed(),
+        game_over: tally.game_over(),
+        x: player.pos.x / tile_size,
+        y: player.pos.y / tile_size,
+        health: player.health,
+        coins: tally.coins,
+        kills: tally.kills,
+        lives: player.lives,
+    }
+}
+
+/// Adds `amount` entities far above the level, so they cost time every frame without getting in the way of the player <br>
+/// Every second one is an enemy walking on a long platform, the others are coins above it
+pub async fn spawn_extra_entities(level_scene_data: &mut LevelSceneData, amount: usize, settings: &Settings) {
+    if amount == 0 { return }
+
+    let size = vec2(128.0, 128.0) * settings.gui_scale;
+    let origin = vec2(0.0, size.y * -1000.0);
+    let entities = &mut level_scene_data.level_data.entities;
+    let world = &mut level_scene_data.world;
+
+    // The platform is an entity too
+    entities.spawn_platform(Platform::floating(amount as i32 / 2, size, TextureKey::Platform0).await, origin, world).await;
+    for i in 1..amount {
+        let pos = origin + vec2(size.x * (i / 2) as f32, size.y * -((1 + i % 2) as f32));
+        if i % 2 == 0 {
+            entities.spawn_enemy(pos, size - vec2(2.0, 2.0), -50, TextureKey::Enemy0, world).await;
+        } else {
+            entities.spawn_coin(pos, size, TextureKey::Coin0).await;
+        }
+    }
+}
+
+/// Steps the level like [simulate()] and measures how long every frame takes <br>
+/// With `clone_baseline` every frame also copies the level data twice, like the update pipeline did before it changed the level in place
+pub async fn benchmark(level_scene_data: &mut LevelSceneData, max_frames: u32, settings: &Settings, agent: &mut dyn Agent, extra: usize, clone_baseline: bool) -> BenchmarkReport {
+    spawn_extra_entities(level_scene_data, extra, settings).await;
+    let level_data = &level_scene_data.level_data;
+    let objects = level_data.entities.count();
+
+    let start = Instant::now();
+    let _ = level_data.clone();
+    let level_data_clone = start.elapsed().as_secs_f64() * 1_000_000.0;
+
+    let mut frames = 0;
+    let mut total = 0.0;
+    let mut max_frame: f64 = 0.0;
+
+    while frames < max_frames {
+        let observation = Observation::new(level_scene_data, settings).await;
+        let input = agent.act(&observation);
+
+        let start = Instant::now();
+        if clone_baseline {
+            let _ = level_scene_data.level_data.clone();
+            let _ = level_scene_data.level_data.clone();
+        }
+        levels::step_level(level_scene_data, &input, settings).await;
+        let elapsed = start.elapsed().as_secs_f64() * 1_000_000.0;
+
+        total += elapsed;
+        max_frame = max_frame.max(elapsed);
+        runtime::advance();
+        frames += 1;
+
+        if level_scene_data.level_data.tally.borrow().is_over() {
+            break;
+        }
+    }
+
+    let average_frame = total / frames.max(1) as f64;
+    BenchmarkReport {
+        level: level_scene_data.level_data.level.unwrap(),
+        frames,
+        objects,
+        extra,
+        clone_baseline,
+        average_frame,
+        average_per_object: average_frame / objects.max(1) as f64,
+        max_frame,
+        level_data_clone,
+    }
+}
+
+/// Runs a headless simulation using the command line arguments and returns the exit code
+pub async fn run(args: &[String]) -> i32 {
+    let options = match HeadlessOptions::from_args(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("Usage: JumboMumbo --headless --level <0-3|all> [--frames <n>] [--fps <n>] [--script <path> | --agent <simple>] [--expect <completed|game-over>] [--lives <n>] [--benchmark [--extra <n,...>] [--clone-baseline]]");
+            return 2;
+        }
+    };
+
+    runtime::enable_headless(1.0 / options.fps);
+    let mut settings = headless_settings().await;
+    settings.lives = options.lives;
+    let mut reports = Vec::new();
+    let mut benchmarks = Vec::new();
+
+    let new_agent = || -> Box<dyn Agent> {
+        match &options.agent {
+            Some(name) => agent_from_name(name).unwrap(),
+            None => Box::new(ScriptedAgent::new(&options.script)),
+        }
+    };
+
+    for level in &options.levels {
+        if options.benchmark {
+            for extra in &options.extra {
+                let mut agent = new_agent();
+                let mut level_scene_data = levels::layout(*level, &settings).await;
+                benchmarks.push(benchmark(&mut level_scene_data, options.frames, &settings, agent.as_mut(), *extra, options.clone_baseline).await);
+            }
+        } else {
+            let mut agent = new_agent();
+            let mut level_scene_data = levels::layout(*level, &settings).await;
+            reports.push(simulate(&mut level_scene_data, options.frames, &settings, agent.as_mut()).await);
+        }
+    }
+
+    if options.benchmark {
+        println!("{}", serde_json::to_string_pretty(&benchmarks).expect("Couldn't serialize benchmarks"));
+        return 0;
+    }
+
+    println!("{}", serde_json::to_string_pretty(&reports).expect("Couldn't serialize reports"));
+
+    let failed = reports.iter().any(|report| match options.expect {
+        Some(Expectation::Completed) => !report.completed,
+        Some(Expectation::GameOver) => !report.game_over,
+        None => false,
+    });
+
+    if failed { 1 } else { 0 }
+}
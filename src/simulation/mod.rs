@@ -0,0 +1,2 @@
+pub mod headless;
+pub mod agent;
\ No newline at end of file
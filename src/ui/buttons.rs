@@ -2,11 +2,11 @@ use std::collections::BTreeMap;
 use macroquad::color::WHITE;
 use macroquad::input::{is_mouse_button_down, is_mouse_button_released, mouse_position, MouseButton};
 use macroquad::math::{vec2, Vec2};
-use macroquad::prelude::{draw_texture_ex, is_mouse_button_pressed, measure_text, Texture2D};
-use macroquad::text::draw_text;
+use macroquad::prelude::{draw_texture_ex, is_mouse_button_pressed, Texture2D};
 use macroquad::texture::DrawTextureParams;
 use crate::utils::enums::TextureKey;
 use crate::utils::structs::Rect;
+use crate::utils::text::{draw_str, measure_str};
 
 pub struct Button {
     pub pos: Vec2,
@@ -61,8 +61,8 @@ impl Button {
             self.render_texture_slice(texture_slice).await;
         }
 
-        let size = measure_text(&self.text, None, self.font_size as _, 1.0);
-        draw_text(
+        let size = measure_str(&self.text, self.font_size);
+        draw_str(
             &self.text,
             self.pos.x + self.size.x / 2.0 - size.width / 2.0,
             self.pos.y + self.size.y / 2.0 + size.offset_y / 2.0,
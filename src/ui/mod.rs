@@ -1 +1,2 @@
-pub mod buttons;
\ No newline at end of file
+pub mod buttons;pub mod notice;
+pub mod slider;
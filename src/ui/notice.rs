@@ -0,0 +1,68 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use macroquad::camera::{pop_camera_state, push_camera_state, set_default_camera};
+use macroquad::color::{Color, WHITE};
+use macroquad::math::vec2;
+use macroquad::shapes::draw_rectangle;
+use macroquad::window::{screen_height, screen_width};
+use macroquad::time::get_time;
+use crate::utils::text::{draw_text_box, line_width, wrap, TextAlign, TextStyle};
+
+// Notices tell the player about things that happened outside of the current scene (e.g. a save file that got reset).
+// They can be pushed from anywhere and get drawn above every scene, one after another.
+
+/// How long a notice is shown (in seconds)
+const NOTICE_DURATION: f64 = 8.0;
+
+struct Notices {
+    queue: VecDeque<String>,
+    /// The time at which the first notice of the queue got shown (if it is shown)
+    shown_at: Option<f64>,
+}
+
+thread_local! {
+    static NOTICES: RefCell<Notices> = const { RefCell::new(Notices { queue: VecDeque::new(), shown_at: None }) };
+}
+
+/// Queues a notice that gets shown to the player
+pub fn push(text: String) {
+    NOTICES.with_borrow_mut(|notices| notices.queue.push_back(text));
+}
+
+/// Draws the current notice at the bottom of the screen (should be called after the scene got drawn)
+pub async fn draw(gui_scale: f32) {
+    let text = NOTICES.with_borrow_mut(|notices| {
+        if let Some(shown_at) = notices.shown_at {
+            if get_time() - shown_at > NOTICE_DURATION {
+                notices.queue.pop_front();
+                notices.shown_at = None;
+            }
+        }
+        if notices.shown_at.is_none() && !notices.queue.is_empty() {
+            notices.shown_at = Some(get_time());
+        }
+
+        notices.queue.front().cloned()
+    });
+
+    if let Some(text) = text {
+        // Levels keep their camera between frames
+        push_camera_state();
+        set_default_camera();
+
+        let style = TextStyle { align: TextAlign::Center, ..TextStyle::new(40.0 * gui_scale, WHITE) };
+        let padding = vec2(24.0, 16.0) * gui_scale;
+        // Long notices (e.g. with paths) wrap instead of leaving the screen
+        let max_width = screen_width() * 0.8;
+        let lines = wrap(&text, style.font_size, max_width);
+        let width = lines.iter().map(|line| line_width(line, style.font_size)).fold(0.0, f32::max);
+        let height = style.font_size * style.line_height * lines.len() as f32;
+        let size = vec2(width, height) + padding * 2.0;
+        let pos = vec2(screen_width() / 2.0 - size.x / 2.0, screen_height() - size.y - padding.y);
+
+        draw_rectangle(pos.x, pos.y, size.x, size.y, Color::from_rgba(0, 0, 0, 200));
+        draw_text_box(&text, screen_width() / 2.0, pos.y + padding.y + style.font_size, max_width, &style, None);
+
+        pop_camera_state();
+    }
+}
@@ -0,0 +1,52 @@
+use macroquad::color::{Color, WHITE};
+use macroquad::input::{is_mouse_button_down, mouse_position, MouseButton};
+use macroquad::math::Vec2;
+use macroquad::shapes::{draw_rectangle, draw_rectangle_lines};
+use crate::utils::structs::Rect;
+
+/// A horizontal slider for values from `0.0` to `1.0`
+pub struct Slider {
+    pub pos: Vec2,
+    pub size: Vec2,
+    pub rect: Rect,
+    pub value: f32,
+}
+
+impl Slider {
+    pub async fn new(pos: Vec2, size: Vec2, value: f32) -> Self {
+        let rect = Rect::new(pos.x, pos.y, size.x, size.y).await;
+        Self { pos, size, rect, value: value.clamp(0.0, 1.0) }
+    }
+
+    pub async fn is_hovered(&self) -> bool {
+        let mouse_rect = {
+            let (mouse_x, mouse_y) = mouse_position();
+            Rect::new(mouse_x, mouse_y, 1.0, 1.0).await
+        };
+
+        self.rect.overlaps(&mouse_rect).await
+    }
+
+    /// Returns the new value if the slider is held down with the left mouse button
+    pub async fn dragged(&self) -> Option<f32> {
+        if self.is_hovered().await && is_mouse_button_down(MouseButton::Left) {
+            let (mouse_x, _) = mouse_position();
+            Some(((mouse_x - self.pos.x) / self.size.x).clamp(0.0, 1.0))
+        } else {
+            None
+        }
+    }
+
+    pub async fn render(&self) {
+        let border = self.size.y / 8.0;
+        let color = if self.is_hovered().await { Color::from_rgba(200, 200, 200, 255) } else { WHITE };
+
+        draw_rectangle(self.pos.x, self.pos.y, self.size.x * self.value, self.size.y, Color::from_rgba(120, 120, 120, 255));
+        draw_rectangle_lines(self.pos.x, self.pos.y, self.size.x, self.size.y, border, color);
+
+        // Knob
+        let knob_width = self.size.y / 2.0;
+        let knob_x = self.pos.x + (self.size.x - knob_width) * self.value;
+        draw_rectangle(knob_x, self.pos.y - border, knob_width, self.size.y + border * 2.0, color);
+    }
+}
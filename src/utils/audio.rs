@@ -0,0 +1,174 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use macroquad::audio::{load_sound_from_bytes, play_sound, set_sound_volume, stop_sound, PlaySoundParams, Sound};
+use macroquad::math::Vec2;
+use stopwatch2::Stopwatch;
+use crate::logic::event::{EventQueue, GameEvent, LevelTimer};
+use crate::utils::enums::{Scene, SoundKey};
+use crate::utils::runtime::screen_width;
+use crate::utils::structs::Settings;
+#[cfg(target_os = "macos")]
+use crate::utils::texture::get_resources_path;
+
+// Sounds only play if the game was built with the `audio` feature, otherwise macroquad uses a silent backend.
+
+/// Returns the path of the provided [SoundKey]
+pub fn get_sound_path(key: SoundKey) -> String {
+    #[cfg(target_os = "linux")]
+    let resource_path = "./res";
+    #[cfg(target_os = "windows")]
+    let resource_path = ".\\res";
+    #[cfg(target_os = "macos")]
+    let resource_path = get_resources_path().unwrap().to_str().unwrap().to_string();
+
+    match key {
+        SoundKey::Jump => format!("{}/sounds/sfx/jump.wav", resource_path),
+        SoundKey::Shoot => format!("{}/sounds/sfx/shoot.wav", resource_path),
+        SoundKey::Coin => format!("{}/sounds/sfx/coin.wav", resource_path),
+        SoundKey::PowerUp => format!("{}/sounds/sfx/power_up.wav", resource_path),
+        SoundKey::PowerDown => format!("{}/sounds/sfx/power_down.wav", resource_path),
+        SoundKey::PlayerDamage => format!("{}/sounds/sfx/player_damage.wav", resource_path),
+        SoundKey::EnemyDamage => format!("{}/sounds/sfx/enemy_damage.wav", resource_path),
+        SoundKey::EnemyDeath => format!("{}/sounds/sfx/enemy_death.wav", resource_path),
+        SoundKey::Cannon => format!("{}/sounds/sfx/cannon.wav", resource_path),
+        SoundKey::LevelComplete => format!("{}/sounds/sfx/level_complete.wav", resource_path),
+        SoundKey::GameOver => format!("{}/sounds/sfx/game_over.wav", resource_path),
+        SoundKey::Music0 => format!("{}/sounds/music/level_0.ogg", resource_path),
+        SoundKey::Music1 => format!("{}/sounds/music/level_1.ogg", resource_path),
+        SoundKey::Music2 => format!("{}/sounds/music/level_2.ogg", resource_path),
+        SoundKey::Music3 => format!("{}/sounds/music/level_3.ogg", resource_path),
+    }
+}
+
+/// A sound effect and the position in the level it comes from (if it has one)
+type QueuedSound = (SoundKey, Option<Vec2>);
+
+thread_local! {
+    /// The sound effects of the level that weren't played yet and where they come from <br>
+    /// `None` until the sounds got loaded, so levels without audio (like headless ones) don't collect them
+    static QUEUED: RefCell<Option<Vec<QueuedSound>>> = const { RefCell::new(None) };
+}
+
+/// Queues the sound effects of the events of the level, [Audio::play_queued()] plays them
+pub fn subscribe(events: &mut EventQueue) {
+    events.subscribe(|event| {
+        if let Some(sound) = sound(event) {
+            QUEUED.with_borrow_mut(|queued| queued.as_mut().map(|queued| queued.push(sound)));
+        }
+    });
+}
+
+/// Returns the sound effect of the event (if it has one)
+fn sound(event: &GameEvent) -> Option<QueuedSound> {
+    let sound = match event {
+        GameEvent::CoinCollected { .. } => (SoundKey::Coin, None),
+        GameEvent::EnemyKilled { pos, .. } => (SoundKey::EnemyDeath, Some(*pos)),
+        GameEvent::EnemyDamaged { pos, .. } => (SoundKey::EnemyDamage, Some(*pos)),
+        GameEvent::PlayerDamaged { .. } => (SoundKey::PlayerDamage, None),
+        GameEvent::PlayerJumped | GameEvent::PlayerDashed => (SoundKey::Jump, None),
+        GameEvent::PlayerShot => (SoundKey::Shoot, None),
+        GameEvent::PowerUpPicked { .. } | GameEvent::WeaponPicked { .. } => (SoundKey::PowerUp, None),
+        GameEvent::LifeLost { .. } => (SoundKey::PlayerDamage, None),
+        GameEvent::LifeGained { .. } => (SoundKey::PowerUp, None),
+        GameEvent::PlayerHealed { .. } => (SoundKey::PowerUp, None),
+        GameEvent::CannonFired { pos } => (SoundKey::Cannon, Some(*pos)),
+        GameEvent::LevelCompleted => (SoundKey::LevelComplete, None),
+        GameEvent::GameOver => (SoundKey::GameOver, None),
+        GameEvent::TimerExpired { timer: LevelTimer::PowerUp(_) } => (SoundKey::PowerDown, None),
+        GameEvent::ProjectileHit { .. } | GameEvent::TimerExpired { .. } => return None,
+    };
+    Some(sound)
+}
+
+/// Plays sound effects and the music of levels
+pub struct Audio {
+    sounds: BTreeMap<SoundKey, Sound>,
+    /// The music track that is currently playing
+    music: Option<SoundKey>,
+}
+
+impl Audio {
+    /// Loads all sounds (a sound that cannot be loaded stays silent)
+    pub async fn load() -> Self {
+        let mut stopwatch = Stopwatch::default();
+        println!("Loading sounds...");
+        stopwatch.start();
+
+        let mut sounds = BTreeMap::new();
+        for key in SoundKey::ALL {
+            let path = get_sound_path(key);
+            let sound = match fs::read(&path) {
+                Ok(bytes) => load_sound_from_bytes(&bytes).await.map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+
+            match sound {
+                Ok(sound) => { sounds.insert(key, sound); },
+                Err(e) => println!("Couldn't load sound {} with error \"{}\"! Ignoring...", path, e),
+            }
+        }
+
+        stopwatch.stop();
+        println!("Loaded sounds! Took {}ms", stopwatch.elapsed().as_millis());
+
+        QUEUED.with_borrow_mut(|queued| *queued = Some(Vec::new()));
+
+        Self { sounds, music: None }
+    }
+
+    /// Plays a sound effect once
+    pub fn play(&self, key: SoundKey, settings: &Settings) {
+        self.play_with_volume(key, 1.0, settings);
+    }
+
+    fn play_with_volume(&self, key: SoundKey, volume: f32, settings: &Settings) {
+        if let Some(sound) = self.sounds.get(&key) {
+            play_sound(sound, PlaySoundParams { looped: false, volume: volume * settings.sfx_volume * settings.master_volume });
+        }
+    }
+
+    /// Plays a sound effect that comes from a position in the level <br>
+    /// It gets quieter the further it is away from the player and cannot be heard more than a screen away
+    fn play_at(&self, key: SoundKey, pos: Vec2, listener: Vec2, settings: &Settings) {
+        let volume = 1.0 - pos.distance(listener) / screen_width();
+        if volume > 0.0 {
+            self.play_with_volume(key, volume, settings);
+        }
+    }
+
+    /// Plays the sound effects of the events since the last call (see [subscribe()]) <br>
+    /// `listener` is the position of the player, sounds that come from a position in the level are skipped without one
+    pub fn play_queued(&self, listener: Option<Vec2>, settings: &Settings) {
+        let queued = QUEUED.with_borrow_mut(|queued| queued.as_mut().map(std::mem::take).unwrap_or_default());
+        for (key, pos) in queued {
+            match (pos, listener) {
+                (None, _) => self.play(key, settings),
+                (Some(pos), Some(listener)) => self.play_at(key, pos, listener, settings),
+                (Some(_), None) => {}
+            }
+        }
+    }
+
+    /// Plays the music of the current scene (only levels have music) <br>
+    /// Gets called every frame, so volume changes are applied right away
+    pub fn update_music(&mut self, scene: &Scene, settings: &Settings) {
+        let music = match scene {
+            Scene::Level(level) => Some(level.music()),
+            _ => None,
+        };
+        let volume = settings.music_volume * settings.master_volume;
+
+        if music != self.music {
+            if let Some(sound) = self.music.and_then(|key| self.sounds.get(&key)) {
+                stop_sound(sound);
+            }
+            if let Some(sound) = music.and_then(|key| self.sounds.get(&key)) {
+                play_sound(sound, PlaySoundParams { looped: true, volume });
+            }
+            self.music = music;
+        } else if let Some(sound) = music.and_then(|key| self.sounds.get(&key)) {
+            set_sound_volume(sound, volume);
+        }
+    }
+}
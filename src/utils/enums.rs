@@ -1,11 +1,15 @@
-use macroquad::time::get_time;
+use serde::{Deserialize, Serialize};
 use crate::logic;
+use crate::utils::runtime::get_time;
 
 #[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone)]
 pub enum SceneTextureKey {
     MainMenu,
     LevelSelector,
     SettingsMenu,
+    Profiles,
+    /// The pause menu and the result screen of levels
+    LevelMenus,
 
     Level0,
     Level1,
@@ -14,11 +18,13 @@ pub enum SceneTextureKey {
 }
 
 /// All textures
-#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Debug)]
+#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Serialize, Deserialize, Debug)]
 pub enum TextureKey {
     Player,
     Enemy0,
     Projectile0,
+    /// The projectile of the heavy shot ([crate::logic::weapon::WeaponKind::HeavyShot])
+    Projectile1,
 
     // Platforms
     Platform0,
@@ -35,6 +41,19 @@ pub enum TextureKey {
     /// `64, 83` is the damage boost texture
     PowerUps0,
 
+    /// The textures of the newer power ups, every power up has one texture (see [crate::logic::power_up]) <br>
+    /// `0` shield, `1` invincibility, `2` double jump, `3` magnet, `4` rapid fire, `5` dash, `6` wall jump
+    PowerUps1,
+
+    /// No Animation <br>
+    /// `0` is the small heart ([crate::logic::ecs::components::Pickup::SmallHeart]) <br>
+    /// `1` is the large heart ([crate::logic::ecs::components::Pickup::LargeHeart])
+    Hearts0,
+
+    /// No Animation <br>
+    /// Every weapon has one texture (see [crate::logic::weapon::WeaponKind::texture_index()])
+    Weapons0,
+
     /// ##### This texture needs to be animated ([AnimationType::Cycle]) <br>
     /// ###### Ranges:
     /// `0, 20` is the sword (kills) texture <br>
@@ -50,15 +69,47 @@ pub enum TextureKey {
     Button0,
 }
 
+/// All sounds (see [crate::utils::audio])
+#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Debug)]
+pub enum SoundKey {
+    // Sound effects
+    Jump,
+    Shoot,
+    Coin,
+    PowerUp,
+    PowerDown,
+    PlayerDamage,
+    EnemyDamage,
+    EnemyDeath,
+    Cannon,
+    LevelComplete,
+    GameOver,
+
+    // Music (one track per level, gets looped)
+    Music0,
+    Music1,
+    Music2,
+    Music3,
+}
+
+impl SoundKey {
+    pub const ALL: [SoundKey; 15] = [
+        SoundKey::Jump, SoundKey::Shoot, SoundKey::Coin, SoundKey::PowerUp, SoundKey::PowerDown, SoundKey::PlayerDamage, SoundKey::EnemyDamage, SoundKey::EnemyDeath,
+        SoundKey::Cannon, SoundKey::LevelComplete, SoundKey::GameOver, SoundKey::Music0, SoundKey::Music1, SoundKey::Music2, SoundKey::Music3,
+    ];
+}
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum Scene {
     MainMenu,
-    SettingsMenu,
+    /// The [logic::level::Level] is the level the settings got opened from (it continues when they get closed)
+    SettingsMenu(Option<logic::level::Level>),
     /// The [std::f32] is the offset of y (when scrolling)
     Credits(f32),
     /// The [std::usize] is the Page
     LevelSelector(usize),
-    Level(logic::level::Level)
+    Level(logic::level::Level),
+    Profiles,
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -106,7 +157,7 @@ impl Animation {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize, Debug)]
 pub enum AnimationType {
     /// Goes through a fixed number of textures <br>
     /// For this animation the index represents the current texture index. <br>
@@ -115,7 +166,7 @@ pub enum AnimationType {
     Cycle(u32, u32, f64)
 }
 
-#[derive(Copy, Clone, PartialEq, Ord, Eq, PartialOrd, Debug)]
+#[derive(Copy, Clone, PartialEq, Ord, Eq, PartialOrd, Serialize, Deserialize, Debug)]
 pub enum Direction {
     Right,
     Left,
@@ -0,0 +1,159 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+#[cfg(target_os = "macos")]
+use crate::utils::texture::get_resources_path;
+
+// All texts that are shown to the player are looked up by a key (e.g. `"menu.levels"`) in the locale files at `res/locales/<language>.json`.
+// A text can contain placeholders like `{level}` that get replaced with [text_with()].
+// Texts that are missing in the current language are taken from English, texts that are missing there show their key.
+
+/// The language that is used if a text is missing in the current language
+pub const FALLBACK_LANGUAGE: &str = "en";
+
+struct Locales {
+    language: String,
+    texts: BTreeMap<String, String>,
+    fallback: BTreeMap<String, String>,
+    /// All languages (see [languages()])
+    languages: Option<Vec<(String, String)>>,
+}
+
+thread_local! {
+    static LOCALES: RefCell<Locales> = RefCell::new(Locales {
+        language: FALLBACK_LANGUAGE.to_string(),
+        texts: BTreeMap::new(),
+        fallback: load_locale(FALLBACK_LANGUAGE),
+        languages: None,
+    });
+}
+
+fn get_locales_path() -> String {
+    #[cfg(target_os = "linux")]
+    let resource_path = "./res";
+    #[cfg(target_os = "windows")]
+    let resource_path = ".\\res";
+    #[cfg(target_os = "macos")]
+    let resource_path = get_resources_path().unwrap().to_str().unwrap().to_string();
+
+    format!("{}/locales", resource_path)
+}
+
+/// Reads the texts of a language (empty if the file cannot be loaded)
+fn load_locale(language: &str) -> BTreeMap<String, String> {
+    let path = format!("{}/{}.json", get_locales_path(), language);
+    let result = fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|s_value| serde_json::from_str(&s_value).map_err(|e| e.to_string()));
+
+    match result {
+        Ok(texts) => texts,
+        Err(e) => {
+            println!("Couldn't load locale {} with error \"{}\"! Ignoring...", path, e);
+            BTreeMap::new()
+        }
+    }
+}
+
+/// Switches to the provided language (does nothing if it is already used) <br>
+/// Can be called every frame
+pub fn set_language(language: &str) {
+    LOCALES.with_borrow_mut(|locales| {
+        if locales.language == language { return; }
+
+        println!("Switching language to {}", language);
+        locales.texts = match language == FALLBACK_LANGUAGE {
+            true => BTreeMap::new(),
+            false => load_locale(language),
+        };
+        locales.language = language.to_string();
+    });
+}
+
+/// Returns the codes of all languages that have a locale file, together with their name (the `language` text of the file) <br>
+/// The files are only searched the first time
+pub fn languages() -> Vec<(String, String)> {
+    LOCALES.with_borrow_mut(|locales| {
+        locales.languages.get_or_insert_with(|| {
+            let mut codes: Vec<String> = match fs::read_dir(get_locales_path()) {
+                Ok(entries) => entries
+                    .filter_map(|entry| entry.ok()?.path().file_stem()?.to_str().map(str::to_string))
+                    .collect(),
+                Err(_) => Vec::new(),
+            };
+            if codes.is_empty() {
+                codes.push(FALLBACK_LANGUAGE.to_string());
+            }
+            codes.sort();
+
+            codes.into_iter()
+                .map(|code| {
+                    let name = load_locale(&code).remove("language").unwrap_or_else(|| code.clone());
+                    (code, name)
+                })
+                .collect()
+        }).clone()
+    })
+}
+
+/// Returns the text of the key in the current language
+pub fn text(key: &str) -> String {
+    LOCALES.with_borrow(|locales| {
+        locales.texts.get(key)
+            .or_else(|| locales.fallback.get(key))
+            .cloned()
+            .unwrap_or_else(|| key.to_string())
+    })
+}
+
+/// Returns the text of the key in the current language with its placeholders replaced <br>
+/// `text_with("level.completed", &[("level", name)])` replaces `{level}` with `name`
+pub fn text_with(key: &str, args: &[(&str, String)]) -> String {
+    let mut result = text(key);
+    for (name, value) in args {
+        result = result.replace(&format!("{{{}}}", name), value);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_texts_fall_back_to_english() {
+        // There is no locale file for this language, so every text comes from English
+        set_language("xx");
+        assert_eq!(text("menu.levels"), "Levels");
+
+        set_language("de");
+        assert_eq!(text("menu.levels"), "Level");
+        set_language(FALLBACK_LANGUAGE);
+        assert_eq!(text("menu.levels"), "Levels");
+    }
+
+    #[test]
+    fn missing_keys_show_the_key() {
+        set_language("de");
+        assert_eq!(text("does.not.exist"), "does.not.exist");
+        assert_eq!(text_with("does.not.exist", &[("level", "1".to_string())]), "does.not.exist");
+    }
+
+    #[test]
+    fn placeholders_get_replaced() {
+        set_language(FALLBACK_LANGUAGE);
+        assert_eq!(text_with("level.completed", &[("level", "Level 1".to_string())]), "You completed Level 1!");
+        // Placeholders without a value are kept
+        assert_eq!(text_with("level.completed", &[]), "You completed {level}!");
+    }
+
+    #[test]
+    fn every_language_only_has_english_keys() {
+        let english = load_locale(FALLBACK_LANGUAGE);
+        for (code, _) in languages() {
+            for key in load_locale(&code).keys() {
+                assert!(english.contains_key(key), "{} has the unknown key {}", code, key);
+            }
+        }
+    }
+}
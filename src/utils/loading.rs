@@ -2,6 +2,7 @@ use macroquad::color::{Color, BLACK, WHITE};
 use macroquad::prelude::{draw_line, screen_height, screen_width};
 use macroquad::time::get_frame_time;
 use macroquad::window::{clear_background, next_frame};
+use crate::utils::i18n;
 use crate::utils::mathemann::stretch_float_to;
 use crate::utils::text::{draw_text_center, draw_text_centered};
 
@@ -13,7 +14,7 @@ pub async fn show_loading_screen(previous_progress: f32, progress: f32, text: &s
 
     clear_background(BLACK);
     draw_text_center(text, screen_height() / 8.0, Color::from_rgba(255, 255, 255, 255)).await;
-    draw_text_centered("Loading...", screen_height() / 4.0, screen_height() / 16.0, Color::from_rgba(255, 255, 255, 255)).await;
+    draw_text_centered(&i18n::text("loading.loading"), screen_height() / 4.0, screen_height() / 16.0, Color::from_rgba(255, 255, 255, 255)).await;
 
     let length = width + stretch_float_to(previous_progress, 100.0, width * 2.0).await;
 
@@ -22,7 +23,7 @@ pub async fn show_loading_screen(previous_progress: f32, progress: f32, text: &s
 
     clear_background(BLACK);
     draw_text_center(text, screen_height() / 8.0, Color::from_rgba(255, 255, 255, 255)).await;
-    draw_text_centered("Loading...", screen_height() / 4.0, screen_height() / 16.0, Color::from_rgba(255, 255, 255, 255)).await;
+    draw_text_centered(&i18n::text("loading.loading"), screen_height() / 4.0, screen_height() / 16.0, Color::from_rgba(255, 255, 255, 255)).await;
 
     let length = width + stretch_float_to(previous_progress, 100.0, width * 2.0).await;
 
@@ -34,7 +35,7 @@ pub async fn show_loading_screen(previous_progress: f32, progress: f32, text: &s
 
         clear_background(BLACK);
         draw_text_center(text, screen_height() / 8.0, Color::from_rgba(255, 255, 255, 255)).await;
-        draw_text_centered("Loading...", screen_height() / 4.0, screen_height() / 16.0, Color::from_rgba(255, 255, 255, 255)).await;
+        draw_text_centered(&i18n::text("loading.loading"), screen_height() / 4.0, screen_height() / 16.0, Color::from_rgba(255, 255, 255, 255)).await;
 
         let length = width + stretch_float_to(current_progress, 100.0, width * 2.0).await;
 
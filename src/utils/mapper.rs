@@ -1,14 +1,16 @@
+use std::fs;
 use macroquad::color::Color;
 use macroquad::math::{vec2, Vec2};
-use macroquad::texture::load_image;
-use macroquad::time::get_time;
+use macroquad::texture::Image;
 use macroquad_platformer::World;
-use crate::logic::cannon::Cannon;
-use crate::logic::collectible::{Collectible, CollectibleType};
-use crate::logic::enemy::Enemy;
+use crate::logic::ecs::components::{Loot, Pickup, ProjectileBehaviour};
+use crate::logic::ecs::prefabs::{CannonSpawn, PowerUpSpawn};
+use crate::logic::ecs::Entities;
 use crate::logic::platform::{Platform, PlatformTile};
-use crate::logic::player::{Player, PlayerPowerUp, PowerUp};
-use crate::utils::enums::{Animation, AnimationType, Direction, TextureKey};
+use crate::logic::player::{Player, PlayerPowerUp};
+use crate::logic::weapon::WeaponKind;
+use crate::utils::enums::{AnimationType, Direction, TextureKey};
+use crate::utils::runtime::get_time;
 use crate::utils::structs::{Matrix, Settings};
 
 /// # Colors
@@ -22,12 +24,31 @@ use crate::utils::structs::{Matrix, Settings};
 /// `(200, 1..255, 0..255, 1..255)` = Cannon (Right) <br>
 /// `(100, 1..255, 0..255, 1.255)` = Cannon (Up) <br>
 /// `(0, 1..255, 0..255, 1.255)` = Cannon (Down) <br>
+/// `(150, 1..255, 0..255, 1..255)` = Homing cannon (Left), its projectiles follow the player (G, B and A like for the other cannons) <br>
+/// `(151, 1..255, 0..255, 1..255)` = Homing cannon (Right) <br>
+/// `(152, 1..255, 0..255, 1..255)` = Mortar (Left), its projectiles fly in an arc and bounce once <br>
+/// `(153, 1..255, 0..255, 1..255)` = Mortar (Right) <br>
 /// `(245, 255, 0, 255)` = Coin <br>
 /// `(244, 120, 0, 1..255)` = Coin2x where A is the duration in Seconds <br>
 /// `(243, 0, 255, 1..255)` = SpeedBoost where A is the duration in Seconds <br>
 /// `(242, 255, 100, 1..255)` = DamageBoost where A is the duration in Seconds <br>
 /// `(80, 255, 255, 1..255)` = JumpBoost where A is the duration in Seconds <br>
 /// `(241, 120, 100, 1..255)` = Enemy where A * 2.0 is the damage (in reverse) <br>
+/// `(236, 200, 255, 1..255)` = Shield where A is the duration in Seconds <br>
+/// `(235, 255, 200, 1..255)` = Invincibility where A is the duration in Seconds <br>
+/// `(234, 255, 255, 1..255)` = DoubleJump where A is the duration in Seconds <br>
+/// `(233, 60, 60, 1..255)` = Magnet where A is the duration in Seconds <br>
+/// `(232, 255, 60, 1..255)` = RapidFire where A is the duration in Seconds <br>
+/// `(231, 255, 140, 1..255)` = Dash where A is the duration in Seconds <br>
+/// `(230, 140, 255, 1..255)` = WallJump where A is the duration in Seconds <br>
+/// `(240, 80, 80, 255)` = Extra life (only in the lives mode) <br>
+/// `(239, 0, 60, 255)` = Small heart <br>
+/// `(239, 0, 120, 255)` = Large heart <br>
+/// `(238, 120, 100, 1..255)` = Enemy that drops a small heart when it dies (A like for the normal enemy) <br>
+/// `(229, 120, 60, 255)` = Shotgun <br>
+/// `(229, 60, 120, 255)` = Heavy shot <br>
+/// `(229, 90, 200, 255)` = Bouncer <br>
+/// `(229, 200, 90, 255)` = Seeker <br>
 ///
 /// 0, 0 is at the bottom left of the image
 pub async fn level_map_from_image(
@@ -41,24 +62,22 @@ pub async fn level_map_from_image(
     cannon_texture_key: TextureKey,
     projectile_texture_key: TextureKey,
     power_ups_texture_key: TextureKey,
-) -> (Player, Vec<Platform>, Vec<Collectible>, Vec<Enemy>, Vec<Cannon>, Vec<PowerUp>) {
+) -> (Player, Entities) {
     let start_time = get_time();
-    let nv2 = vec2(0.0, 0.0);
     let mut player = Player::new(
         0.0,
         0.0,
         vec2(0.0, 0.0),
         0,
+        settings.lives,
         world,
     ).await;
 
-    let mut platforms = Vec::new();
-    let mut collectibles = Vec::new();
-    let mut enemies = Vec::new();
-    let mut cannons = Vec::new();
-    let mut power_ups = Vec::new();
+    let mut entities = Entities::new();
 
-    let matrix: Matrix<Color> = load_image(&path).await.unwrap().into();
+    // Read the image without macroquad's file loader because that needs a window
+    let bytes = fs::read(&path).expect("Couldn't read level map");
+    let matrix: Matrix<Color> = Image::from_file_with_format(&bytes, None).expect("Couldn't decode level map").into();
 
     for ((row, col), color) in matrix {
         let rgba = [(color.r * 255.0) as i32, (color.g * 255.0) as i32, (color.b * 255.0) as i32, (color.a * 255.0) as i32];
@@ -72,6 +91,7 @@ pub async fn level_map_from_image(
                     tile_size.y - 2.0,
                     vec2(tile_size.x * row as f32, tile_size.y * col as f32),
                     1,
+                    settings.lives,
                     world,
                 ).await;
             },
@@ -84,145 +104,245 @@ pub async fn level_map_from_image(
                     ).await
                 ];
 
-                platforms.push(Platform::new(
-                    world.add_solid(pos, tile_size.x as i32, tile_size.y as i32),
+                entities.spawn_platform(
+                    Platform::new(tile_size, tile).await,
                     pos,
-                    tile_size,
-                    tile_size,
-                    tile,
-                    nv2.to_owned()
-                ).await);
+                    world
+                ).await;
             },
             [246, 1..=255, 0..=255, 1..=255] => { // Cannon left
-                cannons.push(Cannon::new(
+                entities.spawn_cannon(CannonSpawn {
                     pos,
-                    tile_size,
-                    rgba[1] as f64 / 10.0,
-                    rgba[2] as f64 / 10.0,
-                    Direction::Left,
+                    size: tile_size,
+                    speed: rgba[1] as f64 / 10.0,
+                    offset: rgba[2] as f64 / 10.0,
+                    direction: Direction::Left,
                     start_time,
-                    1000.0 * settings.gui_scale,
-                    10.0,
-                    cannon_texture_key,
+                    projectile_speed: 1000.0 * settings.gui_scale,
+                    projectile_time: 10.0,
+                    texture_key: cannon_texture_key,
                     projectile_texture_key,
-                    (255 - rgba[3]) as i16 * -2,
-                    world
-                ).await)
+                    projectile_behaviour: ProjectileBehaviour::default(),
+                    damage: (255 - rgba[3]) as i16 * -2,
+                }, world).await;
             },
             [200, 1..=255, 0..=255, 1..=255] => { // Cannon right
-                cannons.push(Cannon::new(
+                entities.spawn_cannon(CannonSpawn {
                     pos,
-                    tile_size,
-                    rgba[1] as f64 / 10.0,
-                    rgba[2] as f64 / 10.0,
-                    Direction::Right,
+                    size: tile_size,
+                    speed: rgba[1] as f64 / 10.0,
+                    offset: rgba[2] as f64 / 10.0,
+                    direction: Direction::Right,
                     start_time,
-                    1000.0 * settings.gui_scale,
-                    10.0,
-                    cannon_texture_key,
+                    projectile_speed: 1000.0 * settings.gui_scale,
+                    projectile_time: 10.0,
+                    texture_key: cannon_texture_key,
                     projectile_texture_key,
-                    (255 - rgba[3]) as i16 * -2,
-                    world
-                ).await);
+                    projectile_behaviour: ProjectileBehaviour::default(),
+                    damage: (255 - rgba[3]) as i16 * -2,
+                }, world).await;
             },
             [100, 1..=255, 0..=255, 1..=255] => { // Cannon up
-                cannons.push(Cannon::new(
+                entities.spawn_cannon(CannonSpawn {
                     pos,
-                    tile_size,
-                    rgba[1] as f64 / 10.0,
-                    rgba[2] as f64 / 10.0,
-                    Direction::Up,
+                    size: tile_size,
+                    speed: rgba[1] as f64 / 10.0,
+                    offset: rgba[2] as f64 / 10.0,
+                    direction: Direction::Up,
                     start_time,
-                    1000.0 * settings.gui_scale,
-                    10.0,
-                    cannon_texture_key,
+                    projectile_speed: 1000.0 * settings.gui_scale,
+                    projectile_time: 10.0,
+                    texture_key: cannon_texture_key,
                     projectile_texture_key,
-                    (255 - rgba[3]) as i16 * -2,
-                    world
-                ).await);
+                    projectile_behaviour: ProjectileBehaviour::default(),
+                    damage: (255 - rgba[3]) as i16 * -2,
+                }, world).await;
             },
             [0, 1..=255, 0..=255, 1..=255] => { // Cannon down
-                cannons.push(Cannon::new(
+                entities.spawn_cannon(CannonSpawn {
                     pos,
-                    tile_size,
-                    rgba[1] as f64 / 10.0,
-                    rgba[2] as f64 / 10.0,
-                    Direction::Down,
+                    size: tile_size,
+                    speed: rgba[1] as f64 / 10.0,
+                    offset: rgba[2] as f64 / 10.0,
+                    direction: Direction::Down,
                     start_time,
-                    1000.0 * settings.gui_scale,
-                    10.0,
-                    cannon_texture_key,
+                    projectile_speed: 1000.0 * settings.gui_scale,
+                    projectile_time: 10.0,
+                    texture_key: cannon_texture_key,
                     projectile_texture_key,
-                    (255 - rgba[3]) as i16 * -2,
-                    world
-                ).await);
+                    projectile_behaviour: ProjectileBehaviour::default(),
+                    damage: (255 - rgba[3]) as i16 * -2,
+                }, world).await;
+            },
+            [150..=153, 1..=255, 0..=255, 1..=255] => { // Homing cannons and mortars
+                let direction = match rgba[0] % 2 {
+                    0 => Direction::Left,
+                    _ => Direction::Right,
+                };
+                let projectile_behaviour = match rgba[0] {
+                    150 | 151 => ProjectileBehaviour { homing: 90.0, ..Default::default() },
+                    _ => ProjectileBehaviour { gravity: 1500.0, bounces: 1, ..Default::default() },
+                };
+                entities.spawn_cannon(CannonSpawn {
+                    pos,
+                    size: tile_size,
+                    speed: rgba[1] as f64 / 10.0,
+                    offset: rgba[2] as f64 / 10.0,
+                    direction,
+                    start_time,
+                    projectile_speed: 1000.0 * settings.gui_scale,
+                    projectile_time: 10.0,
+                    texture_key: cannon_texture_key,
+                    projectile_texture_key,
+                    projectile_behaviour,
+                    damage: (255 - rgba[3]) as i16 * -2,
+                }, world).await;
             },
             [245, 255, 0, 255] => { // Coin
-                collectibles.push(Collectible::new(
-                    CollectibleType::Coin,
+                entities.spawn_coin(
                     pos,
                     tile_size,
                     coin_texture_key,
-                    Animation::new(AnimationType::Cycle(0, 5, 0.1)),
-                    nv2.to_owned()
-                ).await);
+                ).await;
             },
             [244, 120, 0, 1..=255] => { // Coin2x
-                power_ups.push(PowerUp::new(
-                    PlayerPowerUp::Coins2x,
-                    rgba[3] as f64,
+                entities.spawn_power_up(PowerUpSpawn {
+                    power_up: PlayerPowerUp::Coins2x,
+                    duration: rgba[3] as f64,
                     pos,
-                    tile_size,
-                    power_ups_texture_key,
-                    (41, 63),
-                    0.1
-                ).await)
+                    size: tile_size,
+                    texture_key: power_ups_texture_key,
+                    animation: AnimationType::Cycle(41, 63, 0.1),
+                }).await;
             },
             [243, 0, 255, 1..=255] => { // SpeedBoost
-                power_ups.push(PowerUp::new(
-                    PlayerPowerUp::SpeedBoost,
-                    rgba[3] as f64,
+                entities.spawn_power_up(PowerUpSpawn {
+                    power_up: PlayerPowerUp::SpeedBoost,
+                    duration: rgba[3] as f64,
                     pos,
-                    tile_size,
-                    power_ups_texture_key,
-                    (18, 40),
-                    0.1
-                ).await)
+                    size: tile_size,
+                    texture_key: power_ups_texture_key,
+                    animation: AnimationType::Cycle(18, 40, 0.1),
+                }).await;
             },
             [242, 255, 100, 1..=255] => { // DamageBoost
-                power_ups.push(PowerUp::new(
-                    PlayerPowerUp::Damage2x,
-                    rgba[3] as f64,
+                entities.spawn_power_up(PowerUpSpawn {
+                    power_up: PlayerPowerUp::Damage2x,
+                    duration: rgba[3] as f64,
                     pos,
-                    tile_size,
-                    power_ups_texture_key,
-                    (64, 83),
-                    0.1
-                ).await)
+                    size: tile_size,
+                    texture_key: power_ups_texture_key,
+                    animation: AnimationType::Cycle(64, 83, 0.1),
+                }).await;
             },
             [80, 255, 255, 1..=255] => { // JumpBoost
-                power_ups.push(PowerUp::new(
-                    PlayerPowerUp::JumpBoost,
-                    rgba[3] as f64,
+                entities.spawn_power_up(PowerUpSpawn {
+                    power_up: PlayerPowerUp::JumpBoost,
+                    duration: rgba[3] as f64,
+                    pos,
+                    size: tile_size,
+                    texture_key: power_ups_texture_key,
+                    animation: AnimationType::Cycle(0, 17, 0.1),
+                }).await;
+            },
+            [230..=236, _, _, 1..=255] => { // Newer power ups (see [PlayerPowerUp::texture()])
+                let power_up = match rgba[..3] {
+                    [236, 200, 255] => PlayerPowerUp::Shield,
+                    [235, 255, 200] => PlayerPowerUp::Invincibility,
+                    [234, 255, 255] => PlayerPowerUp::DoubleJump,
+                    [233, 60, 60] => PlayerPowerUp::Magnet,
+                    [232, 255, 60] => PlayerPowerUp::RapidFire,
+                    [231, 255, 140] => PlayerPowerUp::Dash,
+                    [230, 140, 255] => PlayerPowerUp::WallJump,
+                    _ => continue,
+                };
+                let (texture_key, (start, end)) = power_up.texture();
+                entities.spawn_power_up(PowerUpSpawn {
+                    power_up,
+                    duration: rgba[3] as f64,
+                    pos,
+                    size: tile_size,
+                    texture_key,
+                    animation: AnimationType::Cycle(start, end, 0.1),
+                }).await;
+            },
+            [240, 80, 80, 255] if settings.lives.is_some() => { // Extra life
+                entities.spawn_extra_life(
                     pos,
                     tile_size,
-                    power_ups_texture_key,
-                    (0, 17),
-                    0.1
-                ).await)
+                    TextureKey::Player,
+                ).await;
             },
-            [241, 120, 100, 1..=255] => { // Enemy
-                enemies.push(Enemy::new(
+            [239, 0, 60, 255] => { // Small heart
+                entities.spawn_heart(
+                    false,
+                    pos,
+                    tile_size,
+                    TextureKey::Hearts0,
+                ).await;
+            },
+            [239, 0, 120, 255] => { // Large heart
+                entities.spawn_heart(
+                    true,
+                    pos,
+                    tile_size,
+                    TextureKey::Hearts0,
+                ).await;
+            },
+            [229, 120, 60, 255] => { // Shotgun
+                entities.spawn_weapon(
+                    WeaponKind::Shotgun,
+                    pos,
+                    tile_size,
+                    TextureKey::Weapons0,
+                ).await;
+            },
+            [229, 60, 120, 255] => { // Heavy shot
+                entities.spawn_weapon(
+                    WeaponKind::HeavyShot,
+                    pos,
+                    tile_size,
+                    TextureKey::Weapons0,
+                ).await;
+            },
+            [229, 90, 200, 255] => { // Bouncer
+                entities.spawn_weapon(
+                    WeaponKind::Bouncer,
+                    pos,
+                    tile_size,
+                    TextureKey::Weapons0,
+                ).await;
+            },
+            [229, 200, 90, 255] => { // Seeker
+                entities.spawn_weapon(
+                    WeaponKind::Seeker,
+                    pos,
+                    tile_size,
+                    TextureKey::Weapons0,
+                ).await;
+            },
+            [238, 120, 100, 1..=255] => { // Enemy that drops a heart
+                let enemy = entities.spawn_enemy(
                     pos,
+                    tile_size - vec2(2.0, 2.0),
                     (255 - rgba[3]) as i16 * -2,
-                    world,
+                    enemy_texture_key,
+                    world
+                ).await;
+                entities.loot.insert(enemy, Loot(Pickup::SmallHeart));
+            }
+            [241, 120, 100, 1..=255] => { // Enemy
+                entities.spawn_enemy(
+                    pos,
                     tile_size - vec2(2.0, 2.0),
-                    enemy_texture_key
-                ).await);
+                    (255 - rgba[3]) as i16 * -2,
+                    enemy_texture_key,
+                    world
+                ).await;
             }
             _ => {}
         }
     }
 
-    (player, platforms, collectibles, enemies, cannons, power_ups)
+    (player, entities)
 }
\ No newline at end of file
@@ -17,9 +17,10 @@ pub async fn plus_minus_range<T: PartialOrd + Clone>(value: T, target: T) -> Ran
     }
 }
 
+/// Returns a zero vector if both points are the same
 pub async fn point_to_point_direction_with_speed(p0: Vec2, p1: Vec2, speed: f32) -> Vec2 {
     let diff_vec = p1 - p0;
-    let vec =diff_vec.normalize();
+    let vec =diff_vec.normalize_or_zero();
     vec * speed
 }
 
@@ -6,4 +6,8 @@ pub mod texture;
 pub mod structs;
 pub mod mapper;
 pub mod loading;
-pub mod random;
\ No newline at end of file
+pub mod runtime;
+pub mod profile;
+pub mod versioning;
+pub mod audio;
+pub mod i18n;
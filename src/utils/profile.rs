@@ -0,0 +1,239 @@
+use std::fs;
+use serde::{Deserialize, Serialize};
+use crate::logic::level::PersistentLevelData;
+use crate::utils::structs::Settings;
+use crate::utils::i18n;
+use crate::utils::versioning::save_file;
+
+// Every profile has its own directory (`<config>/profiles/<name>`) with its own `settings.json` (including the keybindings),
+// `level_data.json` (stats and unlocks), `scores.bin` (see `ScoreLog`) and `level_save.json` (see `LevelSnapshot`).
+// `<config>/profiles.json` remembers the profile that was used last.
+
+/// The maximum amount of profiles
+pub const MAX_PROFILES: usize = 6;
+/// The maximum length of a profile name
+pub const MAX_NAME_LENGTH: usize = 16;
+
+#[derive(PartialEq, Clone, Default, Serialize, Deserialize, Debug)]
+struct ProfileIndex {
+    last: Option<String>,
+}
+
+/// All profiles of the game and the one that is currently used
+#[derive(PartialEq, Clone, Debug)]
+pub struct Profiles {
+    config_path: String,
+    /// The names of all profiles (sorted)
+    pub names: Vec<String>,
+    /// The name of the active profile
+    pub current: String,
+}
+
+impl Profiles {
+    /// Loads all profiles from the config directory <br>
+    /// Creates the `Default` profile if there is none (and moves the files from before profiles existed into it)
+    pub async fn load(config_path: &str) -> Self {
+        let mut profiles = Self { config_path: config_path.to_string(), names: Vec::new(), current: String::new() };
+
+        let profiles_path = profiles.profiles_path();
+        if !fs::exists(&profiles_path).unwrap() {
+            fs::create_dir(&profiles_path).expect("Couldn't create profiles directory");
+            println!("Creating profiles directory at: {}", profiles_path);
+        }
+
+        profiles.refresh();
+
+        if profiles.names.is_empty() {
+            profiles.create("Default").expect("Couldn't create default profile");
+
+            for file in ["settings.json", "level_data.json"] {
+                let old_path = format!("{}/{}", config_path, file);
+                if fs::exists(&old_path).unwrap() {
+                    let new_path = format!("{}/{}", profiles.profile_path("Default"), file);
+                    match fs::rename(&old_path, &new_path) {
+                        Ok(_) => println!("Moved {} to {}", old_path, new_path),
+                        Err(e) => println!("Couldn't move {} to the default profile with error \"{}\"! Ignoring...", old_path, e),
+                    }
+                }
+            }
+        }
+
+        let index: ProfileIndex = fs::read_to_string(profiles.index_path()).ok()
+            .and_then(|s_index| serde_json::from_str(&s_index).ok())
+            .unwrap_or_default();
+
+        profiles.current = index.last
+            .filter(|last| profiles.names.contains(last))
+            .unwrap_or(profiles.names[0].to_owned());
+
+        profiles
+    }
+
+    fn profiles_path(&self) -> String {
+        format!("{}/profiles", self.config_path)
+    }
+
+    fn index_path(&self) -> String {
+        format!("{}/profiles.json", self.config_path)
+    }
+
+    pub fn profile_path(&self, name: &str) -> String {
+        format!("{}/{}", self.profiles_path(), name)
+    }
+
+    /// Reads the names of all profiles from the profiles directory
+    fn refresh(&mut self) {
+        let mut names: Vec<String> = fs::read_dir(self.profiles_path()).expect("Couldn't read profiles directory")
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        names.sort();
+
+        self.names = names;
+    }
+
+    fn save_index(&self) {
+        let index = ProfileIndex { last: Some(self.current.to_owned()) };
+        save_file(&self.index_path(), "file.profile_selection", &index, false);
+    }
+
+    /// Loads the settings and level data of the active profile
+    pub async fn load_current(&self) -> (Settings, PersistentLevelData) {
+        let path = self.profile_path(&self.current);
+        println!("Profile path: {}", path);
+
+        let settings = Settings::load(format!("{}/settings.json", path), format!("{}/level_data.json", path)).await;
+        let persistent_level_data = PersistentLevelData::load(&settings.level_data_path).await;
+
+        (settings, persistent_level_data)
+    }
+
+    /// Makes the profile the active one and loads its settings and level data
+    pub async fn select(&mut self, name: &str) -> (Settings, PersistentLevelData) {
+        self.current = name.to_string();
+        self.save_index();
+        self.load_current().await
+    }
+
+    /// Checks if the name can be used for a new profile and returns it without surrounding spaces <br>
+    /// `renamed` is the profile that gets the name, so it can change the case of its own name
+    fn validate(&self, name: &str, renamed: Option<&str>) -> Result<String, String> {
+        let name = name.trim();
+
+        if name.is_empty() {
+            return Err(i18n::text("profiles.name_empty"));
+        }
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err(i18n::text_with("profiles.name_too_long", &[("count", MAX_NAME_LENGTH.to_string())]));
+        }
+        if !name.chars().all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_') {
+            return Err(i18n::text("profiles.name_characters"));
+        }
+        if self.names.iter().any(|existing| existing.eq_ignore_ascii_case(name) && Some(existing.as_str()) != renamed) {
+            return Err(i18n::text_with("profiles.name_exists", &[("name", name.to_string())]));
+        }
+
+        Ok(name.to_string())
+    }
+
+    pub fn create(&mut self, name: &str) -> Result<(), String> {
+        if self.names.len() >= MAX_PROFILES {
+            return Err(i18n::text_with("profiles.too_many", &[("count", MAX_PROFILES.to_string())]));
+        }
+        let name = self.validate(name, None)?;
+
+        fs::create_dir(self.profile_path(&name)).map_err(|e| i18n::text_with("profiles.create_failed", &[("error", e.to_string())]))?;
+        println!("Created profile \"{}\"", name);
+        self.refresh();
+
+        Ok(())
+    }
+
+    /// Renames the profile <br>
+    /// If the active profile gets renamed its settings and level data have to be loaded again (the paths changed)
+    pub fn rename(&mut self, name: &str, new_name: &str) -> Result<(), String> {
+        let new_name = self.validate(new_name, Some(name))?;
+
+        fs::rename(self.profile_path(name), self.profile_path(&new_name)).map_err(|e| i18n::text_with("profiles.rename_failed", &[("error", e.to_string())]))?;
+        println!("Renamed profile \"{}\" to \"{}\"", name, new_name);
+
+        if self.current == name {
+            self.current = new_name;
+            self.save_index();
+        }
+        self.refresh();
+
+        Ok(())
+    }
+
+    /// Deletes the profile with all of its stats <br>
+    /// The active profile cannot be deleted
+    pub fn delete(&mut self, name: &str) -> Result<(), String> {
+        if self.current == name {
+            return Err(i18n::text("profiles.delete_active"));
+        }
+
+        fs::remove_dir_all(self.profile_path(name)).map_err(|e| i18n::text_with("profiles.delete_failed", &[("error", e.to_string())]))?;
+        println!("Deleted profile \"{}\"", name);
+        self.refresh();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::runtime;
+
+    /// Creates the profiles in a new config directory, so the tests don't share their files
+    fn profiles(test: &str, names: &[&str]) -> Profiles {
+        let config_path = std::env::temp_dir().join(format!("jumbo_mumbo_profiles_{}_{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&config_path);
+        fs::create_dir_all(&config_path).unwrap();
+
+        let mut profiles = runtime::block_on(Profiles::load(config_path.to_str().unwrap()));
+        for name in names {
+            profiles.create(name).unwrap();
+        }
+        profiles
+    }
+
+    #[test]
+    fn validate_trims_names_and_rejects_invalid_ones() {
+        let profiles = profiles("validate", &[]);
+
+        assert_eq!(profiles.validate("  bob ", None), Ok("bob".to_string()));
+        assert!(profiles.validate("   ", None).is_err());
+        assert!(profiles.validate(&"a".repeat(MAX_NAME_LENGTH + 1), None).is_err());
+        assert!(profiles.validate("bob/..", None).is_err());
+    }
+
+    #[test]
+    fn names_are_unique_ignoring_case() {
+        let mut profiles = profiles("unique", &["bob"]);
+
+        assert!(profiles.create("BOB").is_err());
+        assert!(profiles.rename("Default", "Bob").is_err());
+    }
+
+    #[test]
+    fn renaming_can_change_the_case_of_the_own_name() {
+        let mut profiles = profiles("rename_case", &["bob"]);
+
+        assert_eq!(profiles.rename("bob", "Bob"), Ok(()));
+        assert!(profiles.names.contains(&"Bob".to_string()));
+        assert!(!profiles.names.contains(&"bob".to_string()));
+    }
+
+    #[test]
+    fn only_max_profiles_can_be_created() {
+        let mut profiles = profiles("max", &[]);
+        for i in profiles.names.len()..MAX_PROFILES {
+            profiles.create(&format!("Player {}", i)).unwrap();
+        }
+
+        assert!(profiles.create("One more").is_err());
+    }
+}
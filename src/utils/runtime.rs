@@ -0,0 +1,133 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, Waker};
+use macroquad::camera::Camera2D;
+
+// Everything the game logic needs from the window (time, screen size and the camera) goes through this file.
+// This way levels can also be simulated without a window (see [crate::simulation::headless])
+
+/// The resolution that gets used if there is no window
+const HEADLESS_RESOLUTION: (f32, f32) = (1920.0, 1080.0);
+
+struct Runtime {
+    headless: bool,
+    /// The simulated time (only used if headless)
+    time: f64,
+    /// The simulated frame time (only used if headless)
+    frame_time: f32,
+    /// The time at which the clock got paused (if it is paused)
+    paused_at: Option<f64>,
+    /// The total time the clock was paused
+    paused_total: f64,
+}
+
+thread_local! {
+    static RUNTIME: RefCell<Runtime> = const { RefCell::new(Runtime { headless: false, time: 0.0, frame_time: 0.0, paused_at: None, paused_total: 0.0 }) };
+}
+
+/// Switches to the simulated clock and the fixed resolution <br>
+/// `frame_time` is the time (in seconds) that passes with each call of [advance()]
+pub fn enable_headless(frame_time: f32) {
+    RUNTIME.with_borrow_mut(|runtime| {
+        runtime.headless = true;
+        runtime.time = 0.0;
+        runtime.frame_time = frame_time;
+    });
+}
+
+pub fn is_headless() -> bool {
+    RUNTIME.with_borrow(|runtime| runtime.headless)
+}
+
+/// Advances the simulated clock by one frame (does nothing if a window exists)
+pub fn advance() {
+    RUNTIME.with_borrow_mut(|runtime| {
+        if runtime.headless {
+            runtime.time += runtime.frame_time as f64;
+        }
+    });
+}
+
+/// Returns the time of the real (or simulated) clock
+fn raw_time() -> f64 {
+    match is_headless() {
+        true => RUNTIME.with_borrow(|runtime| runtime.time),
+        false => macroquad::time::get_time(),
+    }
+}
+
+/// Same as [macroquad::time::get_time()] but also works without a window <br>
+/// The time stands still while the clock is paused (see [pause()])
+pub fn get_time() -> f64 {
+    let raw_time = raw_time();
+    RUNTIME.with_borrow(|runtime| runtime.paused_at.unwrap_or(raw_time) - runtime.paused_total)
+}
+
+/// Stops the clock, so everything that depends on [get_time()] (like timers) stops as well
+pub fn pause() {
+    let raw_time = raw_time();
+    RUNTIME.with_borrow_mut(|runtime| {
+        if runtime.paused_at.is_none() {
+            runtime.paused_at = Some(raw_time);
+        }
+    });
+}
+
+/// Continues the clock after [pause()]
+pub fn resume() {
+    let raw_time = raw_time();
+    RUNTIME.with_borrow_mut(|runtime| {
+        if let Some(paused_at) = runtime.paused_at.take() {
+            runtime.paused_total += raw_time - paused_at;
+        }
+    });
+}
+
+pub fn is_paused() -> bool {
+    RUNTIME.with_borrow(|runtime| runtime.paused_at.is_some())
+}
+
+/// Same as [macroquad::time::get_frame_time()] but also works without a window
+pub fn get_frame_time() -> f32 {
+    match is_headless() {
+        true => RUNTIME.with_borrow(|runtime| runtime.frame_time),
+        false => macroquad::time::get_frame_time(),
+    }
+}
+
+/// Same as [macroquad::window::screen_width()] but also works without a window
+pub fn screen_width() -> f32 {
+    match is_headless() {
+        true => HEADLESS_RESOLUTION.0,
+        false => macroquad::window::screen_width(),
+    }
+}
+
+/// Same as [macroquad::window::screen_height()] but also works without a window
+pub fn screen_height() -> f32 {
+    match is_headless() {
+        true => HEADLESS_RESOLUTION.1,
+        false => macroquad::window::screen_height(),
+    }
+}
+
+/// Same as [macroquad::camera::set_camera()] but does nothing without a window
+pub fn set_camera(camera: &Camera2D) {
+    if !is_headless() {
+        macroquad::camera::set_camera(camera);
+    }
+}
+
+/// Runs a future to completion without the macroquad executor <br>
+/// Only works for futures that never wait for the window (like [macroquad::window::next_frame()])
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
@@ -1,8 +1,12 @@
 use std::collections::BTreeMap;
 use std::fs;
-use std::io::Write;
 use serde::{Deserialize, Serialize};
 use macroquad::math::f32;
+use serde_json::{json, Value};
+use crate::logic::input::{InputAction, Keybindings};
+use crate::utils::i18n;
+use crate::utils::i18n::FALLBACK_LANGUAGE;
+use crate::utils::versioning::{current_version, load_save_file, save_file, Migration};
 #[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct Rect {
     pub x: f32,
@@ -84,26 +88,112 @@ impl From<macroquad::math::Vec2> for Vec2i {
 
 #[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
 pub struct Settings {
+    /// The version of the file format (see [SETTINGS_MIGRATIONS])
+    pub version: u32,
     /// The Path of the Game's config directory
     pub path: String,
     pub level_data_path: String,
     pub gui_scale: f32,
+    /// Whether the ghost of the best run is shown inside levels
+    #[serde(default = "default_true")]
+    pub ghost: bool,
+    #[serde(default)]
+    pub keybindings: Keybindings,
+    /// All volumes go from `0.0` to `1.0`, the music and sound effect volumes get multiplied with the master volume
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    /// The code of the language (the name of its file in `res/locales`)
+    pub language: String,
+    /// The amount of lives the player starts a level with, `None` turns the lives mode off
+    pub lives: Option<u32>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Upgrades old settings files (see [crate::utils::versioning])
+pub const SETTINGS_MIGRATIONS: [Migration; 4] = [
+    settings_v0_to_v1,
+    settings_v1_to_v2,
+    settings_v2_to_v3,
+    settings_v3_to_v4,
+];
+
+/// Version 0 had no version field and didn't always contain the ghost toggle and the keybindings
+fn settings_v0_to_v1(value: &mut Value) {
+    if let Some(settings) = value.as_object_mut() {
+        settings.entry("ghost").or_insert(json!(true));
+        settings.entry("keybindings").or_insert(json!(Keybindings::default()));
+    }
+}
+
+/// Version 2 added the volumes
+fn settings_v1_to_v2(value: &mut Value) {
+    if let Some(settings) = value.as_object_mut() {
+        settings.insert("master_volume".to_string(), json!(1.0));
+        settings.insert("music_volume".to_string(), json!(0.6));
+        settings.insert("sfx_volume".to_string(), json!(0.8));
+    }
+}
+
+/// Version 3 added the language
+fn settings_v2_to_v3(value: &mut Value) {
+    if let Some(settings) = value.as_object_mut() {
+        settings.insert("language".to_string(), json!(FALLBACK_LANGUAGE));
+    }
+}
+
+/// Version 4 added the lives mode (which is off for existing players)
+fn settings_v3_to_v4(value: &mut Value) {
+    if let Some(settings) = value.as_object_mut() {
+        settings.insert("lives".to_string(), Value::Null);
+    }
 }
 
 impl Settings {
     pub async fn new(path: String, level_data_path: String) -> Settings {
         Settings {
+            version: current_version(&SETTINGS_MIGRATIONS),
             path,
             level_data_path,
             gui_scale: 1.0,
+            ghost: true,
+            keybindings: Keybindings::default(),
+            master_volume: 1.0,
+            music_volume: 0.6,
+            sfx_volume: 0.8,
+            language: FALLBACK_LANGUAGE.to_string(),
+            lives: None,
         }
     }
 
-    pub async fn save(&self) {
-        let mut file = fs::File::create(&self.path).unwrap();
+    /// Loads the settings from the provided path or creates them if they don't exist <br>
+    /// Old files get migrated, a file that cannot be read at all gets replaced by its backup or moved to `settings.<i>.json`
+    pub async fn load(path: String, level_data_path: String) -> Settings {
+        let new_settings = Settings::new(path.to_owned(), level_data_path.to_owned()).await;
+
+        let mut settings = match fs::exists(&path).unwrap() {
+            true => load_save_file(&path, "file.settings", &SETTINGS_MIGRATIONS).unwrap_or(new_settings),
+            false => {
+                new_settings.save().await;
+                new_settings.clone()
+            }
+        };
+
+        // The paths depend on the profile (which may have been renamed)
+        settings.path = path;
+        settings.level_data_path = level_data_path;
+        // Notices about the other files of the profile (e.g. its level data) are already shown in its language
+        i18n::set_language(&settings.language);
 
-        let s_settings = serde_json::to_string_pretty(&self).expect("Couldn't serialize settings");
-        file.write_all(s_settings.as_bytes()).expect("Couldn't write settings file");
+        settings
+    }
+
+    /// Writes the settings to their file (errors are shown to the player)
+    pub async fn save(&self) {
+        save_file(&self.path, "file.settings", self, true);
     }
 }
 
@@ -111,6 +201,15 @@ impl Settings {
 #[derive(PartialEq, Clone, Serialize, Deserialize, Debug)]
 pub struct TempSettings {
     pub settings: Settings,
+    /// The action that gets a new key with the next key press
+    #[serde(skip)]
+    pub rebinding: Option<InputAction>,
+}
+
+impl TempSettings {
+    pub fn new(settings: Settings) -> Self {
+        Self { settings, rebinding: None }
+    }
 }
 
 /// A 2D Matrix
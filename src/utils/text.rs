@@ -1,9 +1,68 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
 use macroquad::prelude::*;
+use crate::utils::enums::TextureKey;
+#[cfg(target_os = "macos")]
+use crate::utils::texture::get_resources_path;
+
+// All texts are drawn with the font of the game (`res/fonts/MinimalPixel v2.ttf`, see `res/credits.md`), which gets loaded once at the start.
+// If it cannot be loaded the default font of macroquad is used instead.
+// Rich texts can contain icons that are drawn between the words, e.g. `"Collect all [coin]!"` (see [Icon]).
+
+thread_local! {
+    static FONT: RefCell<Option<Font>> = const { RefCell::new(None) };
+}
+
+/// Returns the path of the font of the game
+pub fn get_font_path() -> String {
+    #[cfg(target_os = "linux")]
+    let resource_path = "./res";
+    #[cfg(target_os = "windows")]
+    let resource_path = ".\\res";
+    #[cfg(target_os = "macos")]
+    let resource_path = get_resources_path().unwrap().to_str().unwrap().to_string();
+
+    format!("{}/fonts/MinimalPixel v2.ttf", resource_path)
+}
+
+/// Loads the font of the game (needs a window)
+pub async fn load_font() {
+    let path = get_font_path();
+    let font = fs::read(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| load_ttf_font_from_bytes(&bytes).map_err(|e| e.to_string()));
+
+    match font {
+        Ok(mut font) => {
+            // It is a pixel font, so it shouldn't get blurry when it is scaled
+            font.set_filter(FilterMode::Nearest);
+            FONT.set(Some(font));
+            println!("Loaded font {}", path);
+        }
+        Err(e) => println!("Couldn't load font {} with error \"{}\"! Using the default font...", path, e),
+    }
+}
+
+fn font() -> Option<Font> {
+    FONT.with_borrow(|font| font.clone())
+}
+
+/// Like [measure_text()], but with the font of the game
+pub fn measure_str(text: &str, font_size: f32) -> TextDimensions {
+    measure_text(text, font().as_ref(), font_size as _, 1.0)
+}
+
+/// Like [draw_text()], but with the font of the game
+pub fn draw_str(text: &str, x: f32, y: f32, font_size: f32, color: Color) -> TextDimensions {
+    let font = font();
+    draw_text_ex(text, x, y, TextParams { font: font.as_ref(), font_size: font_size as _, color, ..Default::default() })
+}
 
 /// Draws text in screen center
 pub async fn draw_text_center(text: &str, font_size: f32, color: Color) {
-    let size = measure_text(text, None, font_size as _, 1.0);
-    draw_text(
+    let size = measure_str(text, font_size);
+    draw_str(
         text,
         screen_width() / 2.0 - size.width / 2.0,
         screen_height() / 2.0 + size.offset_y / 2.0,
@@ -14,12 +73,200 @@ pub async fn draw_text_center(text: &str, font_size: f32, color: Color) {
 
 /// Draws text in the centered x
 pub async fn draw_text_centered(text: &str, y: f32, font_size: f32, color: Color) {
-    let size = measure_text(text, None, font_size as _, 1.0);
-    draw_text(
+    let size = measure_str(text, font_size);
+    draw_str(
         text,
         screen_width() / 2.0 - size.width / 2.0,
         y + size.offset_y,
         font_size,
         color
     );
-}
\ No newline at end of file
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+impl TextAlign {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "left" => Some(TextAlign::Left),
+            "center" => Some(TextAlign::Center),
+            "right" => Some(TextAlign::Right),
+            _ => None,
+        }
+    }
+}
+
+/// How a text gets drawn by [draw_text_box()]
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct TextStyle {
+    pub font_size: f32,
+    pub color: Color,
+    pub align: TextAlign,
+    /// Gets drawn below and right of the text
+    pub shadow: Option<Color>,
+    /// Gets drawn around the text
+    pub outline: Option<Color>,
+    /// The distance between two lines (multiplied with the font size)
+    pub line_height: f32,
+}
+
+impl TextStyle {
+    pub fn new(font_size: f32, color: Color) -> Self {
+        Self { font_size, color, align: TextAlign::Left, shadow: None, outline: None, line_height: 1.25 }
+    }
+}
+
+/// An icon inside of a rich text, written as its name in brackets (e.g. `[coin]`)
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Icon {
+    Coin,
+    Kill,
+}
+
+impl Icon {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "coin" => Some(Icon::Coin),
+            "kill" => Some(Icon::Kill),
+            _ => None,
+        }
+    }
+
+    /// Returns the texture (and its index) of the icon
+    pub fn texture(&self) -> (TextureKey, usize) {
+        match self {
+            Icon::Coin => (TextureKey::Coin0, 0),
+            Icon::Kill => (TextureKey::Icons0, 0),
+        }
+    }
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    Icon(Icon),
+}
+
+/// Splits a line of a rich text into texts and icons (brackets that don't contain an icon stay text)
+fn segments(line: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = line;
+
+    while let Some(start) = rest.find('[') {
+        let icon = rest[start..].find(']').and_then(|end| Some((Icon::from_name(&rest[start + 1..start + end])?, end)));
+        match icon {
+            Some((icon, end)) => {
+                if start > 0 { segments.push(Segment::Text(&rest[..start])); }
+                segments.push(Segment::Icon(icon));
+                rest = &rest[start + end + 1..];
+            }
+            None => {
+                segments.push(Segment::Text(&rest[..=start]));
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    if !rest.is_empty() { segments.push(Segment::Text(rest)); }
+
+    segments
+}
+
+/// Returns the width of a line of a rich text (icons are as wide as the font size)
+pub fn line_width(line: &str, font_size: f32) -> f32 {
+    segments(line).iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => measure_str(text, font_size).width,
+            Segment::Icon(_) => font_size,
+        })
+        .sum()
+}
+
+/// Splits the text into lines that are at most `max_width` wide <br>
+/// Lines only break between words (a single word that is too long gets its own line) and at `\n`
+pub fn wrap(text: &str, font_size: f32, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+            if !line.is_empty() && line_width(&candidate, font_size) > max_width {
+                lines.push(line);
+                line = word.to_string();
+            } else {
+                line = candidate;
+            }
+        }
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Draws a line of a rich text with its outline and shadow
+fn draw_rich_line(line: &str, x: f32, y: f32, style: &TextStyle, textures: Option<&BTreeMap<TextureKey, Vec<Texture2D>>>) {
+    let offset = (style.font_size / 16.0).max(1.0);
+    let mut layers = Vec::new();
+    if let Some(shadow) = style.shadow {
+        layers.push((vec2(offset, offset), shadow));
+    }
+    if let Some(outline) = style.outline {
+        for direction in [vec2(-1.0, -1.0), vec2(0.0, -1.0), vec2(1.0, -1.0), vec2(-1.0, 0.0), vec2(1.0, 0.0), vec2(-1.0, 1.0), vec2(0.0, 1.0), vec2(1.0, 1.0)] {
+            layers.push((direction * offset, outline));
+        }
+    }
+    layers.push((vec2(0.0, 0.0), style.color));
+
+    for (layer_offset, color) in layers {
+        let mut x = x + layer_offset.x;
+        let y = y + layer_offset.y;
+
+        for segment in segments(line) {
+            match segment {
+                Segment::Text(text) => {
+                    x += draw_str(text, x, y, style.font_size, color).width;
+                }
+                Segment::Icon(icon) => {
+                    let (texture_key, index) = icon.texture();
+                    // Shadows and outlines of icons are drawn as their silhouette
+                    if let Some(texture) = textures.and_then(|textures| textures.get(&texture_key)?.get(index)) {
+                        draw_texture_ex(
+                            texture,
+                            x,
+                            y - style.font_size * 0.8,
+                            color,
+                            DrawTextureParams {
+                                dest_size: Some(vec2(style.font_size, style.font_size)),
+                                ..Default::default()
+                            },
+                        );
+                    }
+                    x += style.font_size;
+                }
+            }
+        }
+    }
+}
+
+/// Draws a rich text that wraps at `max_width` (see [wrap()]) and returns its height <br>
+/// `x` is the left edge, the center or the right edge of the text depending on the alignment, `y` is the baseline of the first line.
+/// Icons are only drawn if their textures are provided, otherwise they stay empty
+pub fn draw_text_box(text: &str, x: f32, y: f32, max_width: f32, style: &TextStyle, textures: Option<&BTreeMap<TextureKey, Vec<Texture2D>>>) -> f32 {
+    let lines = wrap(text, style.font_size, max_width);
+    let line_height = style.font_size * style.line_height;
+
+    for (i, line) in lines.iter().enumerate() {
+        let line_x = match style.align {
+            TextAlign::Left => x,
+            TextAlign::Center => x - line_width(line, style.font_size) / 2.0,
+            TextAlign::Right => x - line_width(line, style.font_size),
+        };
+        draw_rich_line(line, line_x, y + line_height * i as f32, style, textures);
+    }
+
+    line_height * lines.len() as f32
+}
@@ -66,9 +66,13 @@ pub async fn get_texture_path(key: TextureKey) -> String {
         TextureKey::Player => format!("{}/textures/entities/player", resource_path),
         TextureKey::Enemy0 => format!("{}/textures/entities/enemy_0", resource_path),
         TextureKey::Projectile0 => format!("{}/textures/entities/projectile_0", resource_path),
+        TextureKey::Projectile1 => format!("{}/textures/entities/projectile_1", resource_path),
         TextureKey::Platform0 => format!("{}/textures/platforms/platform_0", resource_path),
         TextureKey::Coin0 => format!("{}/textures/items/coin_0", resource_path),
         TextureKey::PowerUps0 => format!("{}/textures/items/power_ups_0", resource_path),
+        TextureKey::PowerUps1 => format!("{}/textures/items/power_ups_1", resource_path),
+        TextureKey::Hearts0 => format!("{}/textures/items/hearts_0", resource_path),
+        TextureKey::Weapons0 => format!("{}/textures/items/weapons_0", resource_path),
         TextureKey::Icons0 => format!("{}/ui/icons_0", resource_path),
         TextureKey::Cannon0 => format!("{}/textures/blocks/cannon_0", resource_path),
         TextureKey::Button0 => format!("{}/ui/button_0", resource_path),
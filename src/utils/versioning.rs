@@ -0,0 +1,253 @@
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use crate::ui::notice;
+use crate::utils::i18n;
+
+// Save files (settings and level data) have a `version` field.
+// Every change to their format gets a migration that upgrades the JSON of the version before it,
+// so old files can still be loaded after an update instead of being thrown away.
+// They are written atomically (see [write_atomic()]) and the previous version is kept as `<name>.bak.json`,
+// which gets used if the file itself cannot be loaded.
+
+/// Upgrades the JSON of a save file by one version
+pub type Migration = fn(&mut Value);
+
+/// Returns the version a file has after all migrations ran
+pub const fn current_version(migrations: &[Migration]) -> u32 {
+    migrations.len() as u32
+}
+
+/// Reads a versioned JSON file and upgrades it to the current version <br>
+/// `migrations[i]` upgrades version `i` to version `i + 1`. Files without a `version` field are version `0`
+pub fn load_versioned<T: DeserializeOwned>(path: &str, migrations: &[Migration]) -> Result<T, String> {
+    let s_value = fs::read_to_string(path).map_err(|e| format!("Couldn't read file: {}", e))?;
+    let mut value: Value = serde_json::from_str(&s_value).map_err(|e| format!("Invalid JSON: {}", e))?;
+
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+    let current_version = current_version(migrations);
+    if version > current_version {
+        return Err(format!("The file has version {} but this game only knows up to version {}", version, current_version));
+    }
+
+    for (i, migration) in migrations.iter().enumerate().skip(version as usize) {
+        println!("Migrating {} from version {} to {}", path, i, i + 1);
+        migration(&mut value);
+    }
+    if let Some(object) = value.as_object_mut() {
+        object.insert("version".to_string(), Value::from(current_version));
+    }
+
+    serde_json::from_value(value).map_err(|e| format!("Couldn't deserialize file: {}", e))
+}
+
+/// Loads a save file (see [load_versioned()]) and falls back to its backup if that doesn't work <br>
+/// Returns `None` if neither can be loaded, so a new file has to be created. The player gets told about both cases <br>
+/// `description_key` is the locale key of the name the player sees for the file (e.g. `"file.settings"`)
+pub fn load_save_file<T: DeserializeOwned>(path: &str, description_key: &str, migrations: &[Migration]) -> Option<T> {
+    let error = match load_versioned(path, migrations) {
+        Ok(value) => return Some(value),
+        Err(e) => e,
+    };
+    let description = i18n::text(description_key);
+    println!("Couldn't load {} with error \"{}\"", description, error);
+
+    let moved_text = match set_aside(path, &description) {
+        Some(new_path) => i18n::text_with("notice.file_moved", &[("path", new_path)]),
+        None => String::new(),
+    };
+
+    match load_versioned(&backup_path(path), migrations) {
+        Ok(value) => {
+            println!("Restored {} from the backup", description);
+            notice::push(i18n::text_with("notice.file_restored", &[("file", description), ("moved", moved_text)]));
+            Some(value)
+        }
+        Err(e) => {
+            println!("Couldn't load the backup of the {} with error \"{}\"", description, e);
+            notice::push(i18n::text_with("notice.file_reset", &[("file", description), ("moved", moved_text)]));
+            None
+        }
+    }
+}
+
+/// Moves a file that cannot be loaded to `<name>.<i>.json`, so a new one can be created <br>
+/// Returns the new path if the file could be moved
+pub fn set_aside(path: &str, description: &str) -> Option<String> {
+    for i in 0.. {
+        let new_path = path.replace(".json", format!(".{i}.json").as_str());
+        if !fs::exists(&new_path).unwrap_or(true) {
+            return match fs::rename(path, &new_path) {
+                Ok(_) => {
+                    println!("Moved old {} file to {new_path}", description);
+                    Some(new_path)
+                },
+                Err(_) => {
+                    println!("Couldn't move old {} file to \"{}\"! Ignoring...", description, new_path);
+                    None
+                }
+            };
+        }
+    }
+    None
+}
+
+/// Returns the path of the backup of a save file (`<name>.bak.json`)
+pub fn backup_path(path: &str) -> String {
+    path.replace(".json", ".bak.json")
+}
+
+/// Serializes and writes a save file (see [write_atomic()]) <br>
+/// Errors get shown to the player instead of crashing the game. Returns whether the file got written <br>
+/// `description_key` is the locale key of the name the player sees for the file (see [load_save_file()])
+pub fn save_file<T: Serialize>(path: &str, description_key: &str, value: &T, backup: bool) -> bool {
+    let result = serde_json::to_string_pretty(value)
+        .map_err(|e| e.to_string())
+        .and_then(|s_value| write_atomic(path, s_value.as_bytes(), backup).map_err(|e| e.to_string()));
+
+    match result {
+        Ok(_) => true,
+        Err(e) => {
+            let description = i18n::text(description_key);
+            println!("Couldn't save {} to {} with error \"{}\"", description, path, e);
+            notice::push(i18n::text_with("notice.file_not_saved", &[("file", description), ("error", e)]));
+            false
+        }
+    }
+}
+
+/// Writes the file so that it either has the old or the new content, even if the game crashes or the disk is full <br>
+/// The content goes to `<path>.tmp` first, gets flushed to the disk and then replaces the file.
+/// If `backup` is `true` the previous file is kept as `<name>.bak.json`
+pub fn write_atomic(path: &str, contents: &[u8], backup: bool) -> io::Result<()> {
+    let tmp_path = format!("{}.tmp", path);
+    let written = File::create(&tmp_path).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    if written.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    written?;
+
+    if backup && fs::exists(path)? {
+        if let Err(e) = fs::copy(path, backup_path(path)) {
+            println!("Couldn't create backup of {} with error \"{}\"! Ignoring...", path, e);
+        }
+    }
+
+    fs::rename(&tmp_path, path)?;
+
+    // Makes sure the rename itself is on the disk
+    #[cfg(unix)]
+    if let Some(parent) = std::path::Path::new(path).parent() {
+        File::open(parent)?.sync_all()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    /// Returns a path in a new directory, so the tests don't share their files
+    fn temp_path(test: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("jumbo_mumbo_versioning_{}_{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("data.json").to_str().unwrap().to_string()
+    }
+
+    #[derive(PartialEq, Deserialize, Debug)]
+    struct Data {
+        version: u32,
+        steps: Vec<u32>,
+    }
+
+    fn add_step_1(value: &mut Value) {
+        value["steps"].as_array_mut().unwrap().push(json!(1));
+    }
+
+    fn add_step_2(value: &mut Value) {
+        value["steps"].as_array_mut().unwrap().push(json!(2));
+    }
+
+    const MIGRATIONS: [Migration; 2] = [add_step_1, add_step_2];
+
+    #[test]
+    fn files_without_version_run_all_migrations() {
+        let path = temp_path("all");
+        fs::write(&path, r#"{ "steps": [] }"#).unwrap();
+
+        assert_eq!(load_versioned::<Data>(&path, &MIGRATIONS), Ok(Data { version: 2, steps: vec![1, 2] }));
+    }
+
+    #[test]
+    fn only_newer_migrations_run() {
+        let path = temp_path("newer");
+        fs::write(&path, r#"{ "version": 1, "steps": [] }"#).unwrap();
+
+        assert_eq!(load_versioned::<Data>(&path, &MIGRATIONS), Ok(Data { version: 2, steps: vec![2] }));
+    }
+
+    #[test]
+    fn files_of_newer_versions_are_rejected() {
+        let path = temp_path("future");
+        fs::write(&path, r#"{ "version": 3, "steps": [] }"#).unwrap();
+
+        assert!(load_versioned::<Data>(&path, &MIGRATIONS).is_err());
+    }
+
+    #[test]
+    fn broken_files_fall_back_to_the_backup() {
+        let path = temp_path("backup");
+        fs::write(&path, "{ broken").unwrap();
+        fs::write(backup_path(&path), r#"{ "version": 2, "steps": [7] }"#).unwrap();
+
+        assert_eq!(load_save_file::<Data>(&path, "test data", &MIGRATIONS), Some(Data { version: 2, steps: vec![7] }));
+        // The broken file got moved aside, so it doesn't get overwritten
+        assert!(!fs::exists(&path).unwrap());
+        assert!(fs::exists(path.replace(".json", ".0.json")).unwrap());
+    }
+
+    #[test]
+    fn write_atomic_replaces_the_file_without_leaving_the_temporary_file() {
+        let path = temp_path("atomic");
+        write_atomic(&path, b"old", false).unwrap();
+        write_atomic(&path, b"new", false).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert!(!fs::exists(format!("{}.tmp", path)).unwrap());
+        assert!(!fs::exists(backup_path(&path)).unwrap());
+    }
+
+    #[test]
+    fn write_atomic_keeps_the_previous_file_as_backup() {
+        let path = temp_path("atomic_backup");
+        write_atomic(&path, b"first", true).unwrap();
+        // There is nothing to back up for a new file
+        assert!(!fs::exists(backup_path(&path)).unwrap());
+
+        write_atomic(&path, b"second", true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert_eq!(fs::read_to_string(backup_path(&path)).unwrap(), "first");
+    }
+
+    #[test]
+    fn write_atomic_keeps_the_old_file_if_writing_fails() {
+        let path = temp_path("atomic_fail");
+        write_atomic(&path, b"old", false).unwrap();
+        // The temporary file cannot be created where a directory is
+        fs::create_dir(format!("{}.tmp", path)).unwrap();
+
+        assert!(write_atomic(&path, b"new", false).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+    }
+}
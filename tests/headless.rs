@@ -0,0 +1,38 @@
+// Plays the levels without a window (see `src/simulation/headless.rs`) to catch changes that make them impossible to complete
+
+use std::process::Command;
+
+/// Runs the built-in agent on the provided level and checks that the result matches `expect`
+fn play(level: &str, expect: &str) {
+    let output = Command::new(env!("CARGO_BIN_EXE_JumboMumbo"))
+        .args(["--headless", "--level", level, "--agent", "simple", "--expect", expect])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("Couldn't start the game");
+
+    assert!(output.status.success(), "Level {} didn't end as {}:\n{}", level, expect, String::from_utf8_lossy(&output.stdout));
+}
+
+#[test]
+fn simple_agent_completes_tutorial() {
+    play("0", "completed");
+}
+
+#[test]
+fn simple_agent_completes_level_1() {
+    play("1", "completed");
+}
+
+#[test]
+fn benchmark_reports_the_extra_entities() {
+    let output = Command::new(env!("CARGO_BIN_EXE_JumboMumbo"))
+        .args(["--headless", "--level", "1", "--agent", "simple", "--benchmark", "--extra", "0,20", "--frames", "5"])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("Couldn't start the game");
+
+    let reports: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).expect("Couldn't parse the benchmark");
+    let objects: Vec<u64> = reports.iter().map(|report| report["objects"].as_u64().unwrap()).collect();
+    assert_eq!(objects.len(), 2);
+    assert_eq!(objects[1], objects[0] + 20);
+}